@@ -0,0 +1,355 @@
+//! Known answers for each day, read from `answers/day<N>.toml`.
+//!
+//! The file records the accepted answer for a part when we have it, and the
+//! answers the site rejected as too high or too low, so a new result can be
+//! checked before submitting it:
+//!
+//! ```toml
+//! [part2]
+//! too_low = [7647, 582991]
+//! wrong = [555710]
+//! ```
+//!
+//! An example's answers, in `examples/day<N>/<name>.toml`, use the same format and may add a
+//! `[params]` section of the `--param` overrides the example is solved with.
+
+use std::fmt;
+use std::fs;
+use std::io;
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_till, take_while, take_while1},
+    character::complete::{char, digit1, space0},
+    combinator::{all_consuming, map, map_res, opt, recognize},
+    multi::separated_list0,
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
+    IResult,
+};
+
+use crate::solution::{Answers, Params};
+
+/// What we know about one part's answer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Expected {
+    pub answer: Option<String>,
+    /// Rejected answers the site said were too high.
+    pub too_high: Vec<i64>,
+    /// Rejected answers the site said were too low.
+    pub too_low: Vec<i64>,
+    /// Rejected answers with no hint either way.
+    pub wrong: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DayAnswers {
+    pub part1: Expected,
+    pub part2: Expected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail,
+    TooHigh,
+    TooLow,
+}
+
+impl Verdict {
+    pub fn is_pass(&self) -> bool {
+        *self == Verdict::Pass
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail => write!(f, "FAIL"),
+            Verdict::TooHigh => write!(f, "TOO_HIGH"),
+            Verdict::TooLow => write!(f, "TOO_LOW"),
+        }
+    }
+}
+
+impl Expected {
+    /// Check `answer` against what we know, or `None` when nothing rules it in or out.
+    pub fn check(&self, answer: &str) -> Option<Verdict> {
+        if let Some(expected) = &self.answer {
+            return Some(if expected == answer { Verdict::Pass } else { Verdict::Fail });
+        }
+        if self.wrong.iter().any(|wrong| wrong == answer) {
+            return Some(Verdict::Fail);
+        }
+        let value = answer.parse::<i64>().ok()?;
+        if self.too_high.iter().any(|&high| value >= high) {
+            Some(Verdict::TooHigh)
+        } else if self.too_low.iter().any(|&low| value <= low) {
+            Some(Verdict::TooLow)
+        } else {
+            None
+        }
+    }
+}
+
+/// The verdicts for a run, one per part that was answered and could be checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Verdicts {
+    pub part1: Option<Verdict>,
+    pub part2: Option<Verdict>,
+}
+
+impl Verdicts {
+    pub fn any_failed(&self) -> bool {
+        [self.part1, self.part2].iter().flatten().any(|verdict| !verdict.is_pass())
+    }
+}
+
+impl DayAnswers {
+    pub fn check(&self, answers: &Answers) -> Verdicts {
+        Verdicts {
+            part1: answers.part1.as_deref().and_then(|answer| self.part1.check(answer)),
+            part2: answers.part2.as_deref().and_then(|answer| self.part2.check(answer)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io { path: String, source: io::Error },
+    Parse { path: String, line: usize, text: String },
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswersError::Io { path, source } => write!(f, "could not read {}: {}", path, source),
+            AnswersError::Parse { path, line, text } => {
+                write!(f, "{}:{}: could not understand `{}`", path, line, text)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+/// Load `answers/day<N>.toml`, or `None` when the day has no answers file.
+pub fn load(day: u8) -> Result<Option<DayAnswers>, AnswersError> {
+    let path = format!("answers/day{}.toml", day);
+    match fs::read_to_string(&path) {
+        Ok(text) => parse(&text)
+            .map(Some)
+            .map_err(|(line, text)| AnswersError::Parse { path, line, text }),
+        Err(source) if source.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(source) => Err(AnswersError::Io { path, source }),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Value {
+    Text(String),
+    Integer(i64),
+    List(Vec<Value>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Line {
+    Blank,
+    Section(String),
+    Entry(String, Value),
+}
+
+fn parse_integer(input: &str) -> IResult<&str, i64> {
+    map_res(
+        recognize(tuple((opt(char('-')), digit1, take_while(|c: char| c.is_ascii_digit() || c == '_')))),
+        |digits: &str| digits.replace('_', "").parse::<i64>(),
+    )(input)
+}
+
+fn parse_text(input: &str) -> IResult<&str, String> {
+    map(delimited(char('"'), take_till(|c| c == '"'), char('"')), String::from)(input)
+}
+
+fn parse_value(input: &str) -> IResult<&str, Value> {
+    alt((
+        map(parse_text, Value::Text),
+        map(parse_integer, Value::Integer),
+        map(
+            delimited(
+                pair(char('['), space0),
+                separated_list0(tuple((space0, char(','), space0)), parse_value),
+                tuple((space0, opt(char(',')), space0, char(']'))),
+            ),
+            Value::List,
+        ),
+    ))(input)
+}
+
+fn parse_key(input: &str) -> IResult<&str, String> {
+    map(take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_'), String::from)(input)
+}
+
+fn parse_line(input: &str) -> IResult<&str, Line> {
+    let comment = opt(preceded(char('#'), take_till(|_| false)));
+    all_consuming(delimited(
+        space0,
+        alt((
+            map(delimited(char('['), parse_key, char(']')), Line::Section),
+            map(separated_pair(parse_key, tuple((space0, tag("="), space0)), parse_value), |(key, value)| {
+                Line::Entry(key, value)
+            }),
+            map(space0, |_| Line::Blank),
+        )),
+        terminated(space0, comment),
+    ))(input)
+}
+
+fn integers(value: Value) -> Option<Vec<i64>> {
+    match value {
+        Value::Integer(n) => Some(vec![n]),
+        Value::List(values) => values
+            .into_iter()
+            .map(|value| match value {
+                Value::Integer(n) => Some(n),
+                _ => None,
+            })
+            .collect(),
+        Value::Text(_) => None,
+    }
+}
+
+fn text(value: Value) -> Option<String> {
+    match value {
+        Value::Text(text) => Some(text),
+        Value::Integer(n) => Some(n.to_string()),
+        Value::List(_) => None,
+    }
+}
+
+fn texts(value: Value) -> Option<Vec<String>> {
+    match value {
+        Value::List(values) => values.into_iter().map(text).collect(),
+        value => text(value).map(|text| vec![text]),
+    }
+}
+
+/// Parse the contents of an answers file. On failure, returns the 1-based line number and its text.
+fn parse(input: &str) -> Result<DayAnswers, (usize, String)> {
+    parse_sections(input, None)
+}
+
+/// Parse an example's answers file, which may also have a `[params]` section.
+pub fn parse_example(input: &str) -> Result<(DayAnswers, Params), (usize, String)> {
+    let mut params = Params::default();
+    let answers = parse_sections(input, Some(&mut params))?;
+    Ok((answers, params))
+}
+
+enum Section<'a> {
+    Part(&'a mut Expected),
+    Params(&'a mut Params),
+}
+
+/// Parse `[part1]` and `[part2]`, and `[params]` into `params` when it is given.
+fn parse_sections(input: &str, mut params: Option<&mut Params>) -> Result<DayAnswers, (usize, String)> {
+    let mut answers = DayAnswers::default();
+    let mut section: Option<Section> = None;
+    for (number, raw) in input.lines().enumerate() {
+        let error = || (number + 1, raw.trim().to_string());
+        let (_, line) = parse_line(raw).map_err(|_| error())?;
+        match line {
+            Line::Blank => {}
+            Line::Section(name) => {
+                section = Some(match name.as_str() {
+                    "part1" => Section::Part(&mut answers.part1),
+                    "part2" => Section::Part(&mut answers.part2),
+                    "params" => Section::Params(params.as_deref_mut().ok_or_else(error)?),
+                    _ => return Err(error()),
+                });
+            }
+            Line::Entry(key, value) => match section.as_mut().ok_or_else(error)? {
+                Section::Params(params) => params.0.push((key, text(value).ok_or_else(error)?)),
+                Section::Part(expected) => match key.as_str() {
+                    "answer" => expected.answer = Some(text(value).ok_or_else(error)?),
+                    "too_high" => expected.too_high.extend(integers(value).ok_or_else(error)?),
+                    "too_low" => expected.too_low.extend(integers(value).ok_or_else(error)?),
+                    "wrong" => expected.wrong.extend(texts(value).ok_or_else(error)?),
+                    _ => return Err(error()),
+                },
+            },
+        }
+    }
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        let input = "\
+# day 20
+[part1]
+answer = 1518
+
+[part2]
+too_low = [7_647, 582991,]  # two early attempts
+wrong = [555710]
+";
+        assert_eq!(
+            parse(input).unwrap(),
+            DayAnswers {
+                part1: Expected { answer: Some("1518".into()), ..Default::default() },
+                part2: Expected {
+                    too_low: vec![7647, 582991],
+                    wrong: vec!["555710".into()],
+                    ..Default::default()
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn parse_text_answer_test() {
+        let answers = parse("[part2]\nanswer = \"co,de,ka,ta\"\n").unwrap();
+        assert_eq!(answers.part2.answer, Some("co,de,ka,ta".into()));
+    }
+
+    #[test]
+    fn parse_example_test() {
+        let (answers, params) = parse_example("[params]\nwidth = 11\nheight = 7\n\n[part1]\nanswer = 12\n").unwrap();
+        assert_eq!(params, Params::new(&[("width", "11"), ("height", "7")]));
+        assert_eq!(answers.part1.answer, Some("12".into()));
+        assert_eq!(answers.part2, Expected::default());
+        // only an example can have params
+        assert_eq!(parse("[params]\nwidth = 11\n"), Err((1, "[params]".into())));
+        assert_eq!(parse_example("[params]\nwidth = [11]\n"), Err((2, "width = [11]".into())));
+    }
+
+    #[test]
+    fn parse_error_reports_line_test() {
+        assert_eq!(parse("[part1]\nanswer 12\n"), Err((2, "answer 12".into())));
+        assert_eq!(parse("answer = 12\n"), Err((1, "answer = 12".into())));
+        assert_eq!(parse("[part3]\n"), Err((1, "[part3]".into())));
+    }
+
+    #[test]
+    fn check_test() {
+        let expected = Expected {
+            too_high: vec![439_726, 180_204],
+            too_low: vec![100],
+            wrong: vec!["150".into()],
+            ..Default::default()
+        };
+        assert_eq!(expected.check("180204"), Some(Verdict::TooHigh));
+        assert_eq!(expected.check("200000"), Some(Verdict::TooHigh));
+        assert_eq!(expected.check("99"), Some(Verdict::TooLow));
+        assert_eq!(expected.check("150"), Some(Verdict::Fail));
+        assert_eq!(expected.check("160"), None);
+
+        let known = Expected { answer: Some("160".into()), ..expected };
+        assert_eq!(known.check("160"), Some(Verdict::Pass));
+        assert_eq!(known.check("161"), Some(Verdict::Fail));
+    }
+}
@@ -0,0 +1,201 @@
+//! Differential testing: run a slow, obviously right implementation and a fast one on the
+//! same random cases, and when they disagree shrink the case to a minimal counterexample.
+//!
+//! ```text
+//! check::agree(2000, random_machine, puzzle_shaped, |case| min_cost_to_win(&machine(case)), |case| solve_machine(&machine(case)));
+//! ```
+//!
+//! Cases come from the seeded [`Rng`] the input generators use, so a failure repeats on every
+//! run. A panic in either implementation fails the test as it stands, without shrinking.
+
+use std::fmt::Debug;
+
+use crate::generate::Rng;
+
+/// The seed every check starts from.
+const SEED: u64 = 2024;
+
+/// A case that can be made simpler.
+pub(crate) trait Shrink: Clone + Debug {
+    /// Cases one step simpler than this one, the most drastic first; empty once there are none.
+    fn shrink(&self) -> Vec<Self>;
+}
+
+macro_rules! shrink_unsigned {
+    ($($type:ty),*) => {$(
+        impl Shrink for $type {
+            fn shrink(&self) -> Vec<Self> {
+                let mut simpler = vec![0, self / 2, self.saturating_sub(1)];
+                simpler.dedup();
+                simpler.retain(|value| value != self);
+                simpler
+            }
+        }
+    )*};
+}
+
+shrink_unsigned!(u8, u64, usize);
+
+impl Shrink for i64 {
+    /// Towards 0, so a negative value shrinks through smaller negative ones.
+    fn shrink(&self) -> Vec<Self> {
+        let mut simpler = vec![0, self / 2, self - self.signum()];
+        simpler.dedup();
+        simpler.retain(|value| value != self);
+        simpler
+    }
+}
+
+impl Shrink for bool {
+    fn shrink(&self) -> Vec<Self> {
+        if *self { vec![false] } else { vec![] }
+    }
+}
+
+impl<T: Shrink> Shrink for Vec<T> {
+    /// Drop runs of items, halving the run length down to single items, then shrink each item.
+    fn shrink(&self) -> Vec<Self> {
+        let mut simpler = vec![];
+        let mut run = self.len();
+        while run > 0 {
+            for start in (0..=self.len() - run).step_by(run) {
+                simpler.push([&self[..start], &self[start + run..]].concat());
+            }
+            run /= 2;
+        }
+        for (index, item) in self.iter().enumerate() {
+            simpler.extend(item.shrink().into_iter().map(|smaller| {
+                let mut case = self.clone();
+                case[index] = smaller;
+                case
+            }));
+        }
+        simpler
+    }
+}
+
+impl<T: Shrink, const N: usize> Shrink for [T; N] {
+    fn shrink(&self) -> Vec<Self> {
+        (0..N)
+            .flat_map(|index| {
+                self[index].shrink().into_iter().map(move |smaller| {
+                    let mut case = self.clone();
+                    case[index] = smaller;
+                    case
+                })
+            })
+            .collect()
+    }
+}
+
+impl<A: Shrink, B: Shrink> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<Self> {
+        let (a, b) = self;
+        let firsts = a.shrink().into_iter().map(|a| (a, b.clone()));
+        firsts.chain(b.shrink().into_iter().map(|b| (a.clone(), b))).collect()
+    }
+}
+
+/// A case the two implementations disagree on, and what each of them made of it.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Counterexample<T, R> {
+    pub(crate) case: T,
+    pub(crate) naive: R,
+    pub(crate) fast: R,
+    /// How many times the first failing case was shrunk to get here.
+    pub(crate) shrinks: usize,
+}
+
+/// The simplest case `naive` and `fast` disagree on, found by trying `cases` cases from
+/// `generate` and shrinking the first that fails. Shrinking only ever tries cases that
+/// `valid` accepts, so it stays inside whatever the implementations promise to handle.
+pub(crate) fn counterexample<T: Shrink, R: PartialEq>(
+    cases: usize,
+    mut generate: impl FnMut(&mut Rng) -> T,
+    valid: impl Fn(&T) -> bool,
+    naive: impl Fn(&T) -> R,
+    fast: impl Fn(&T) -> R,
+) -> Option<Counterexample<T, R>> {
+    let mut rng = Rng::new(SEED);
+    let failing = |case: T| {
+        if !valid(&case) {
+            return None;
+        }
+        let (naive, fast) = (naive(&case), fast(&case));
+        (naive != fast).then_some((case, naive, fast))
+    };
+    let (mut case, mut naive_answer, mut fast_answer) = (0..cases).map(|_| generate(&mut rng)).find_map(&failing)?;
+    let mut shrinks = 0;
+    // greedily take the first simpler case that still fails, until none does
+    while let Some(simpler) = case.shrink().into_iter().find_map(&failing) {
+        (case, naive_answer, fast_answer) = simpler;
+        shrinks += 1;
+    }
+    Some(Counterexample { case, naive: naive_answer, fast: fast_answer, shrinks })
+}
+
+/// Panic with the simplest case `naive` and `fast` disagree on, if there is one; see
+/// [`counterexample`].
+pub(crate) fn agree<T: Shrink, R: PartialEq + Debug>(
+    cases: usize,
+    generate: impl FnMut(&mut Rng) -> T,
+    valid: impl Fn(&T) -> bool,
+    naive: impl Fn(&T) -> R,
+    fast: impl Fn(&T) -> R,
+) {
+    if let Some(Counterexample { case, naive, fast, shrinks }) = counterexample(cases, generate, valid, naive, fast) {
+        panic!("the implementations disagree on {:?} (shrunk {} times): naive gives {:?}, fast gives {:?}", case, shrinks, naive, fast);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(rng: &mut Rng) -> Vec<u64> {
+        (0..rng.between(0, 20)).map(|_| rng.between(0, 100) as u64).collect()
+    }
+
+    #[test]
+    fn shrink_test() {
+        assert_eq!(10u8.shrink(), vec![0, 5, 9]);
+        assert_eq!(1usize.shrink(), vec![0]);
+        assert_eq!(0u64.shrink(), vec![]);
+        assert_eq!((-7i64).shrink(), vec![0, -3, -6]);
+        assert_eq!(true.shrink(), vec![false]);
+        assert_eq!(vec![2u8, 1].shrink(), vec![vec![], vec![1], vec![2], vec![0, 1], vec![1, 1], vec![2, 0]]);
+        assert_eq!([1u8, 2].shrink(), vec![[0, 2], [1, 0], [1, 1]]);
+        assert_eq!((false, 1u8).shrink(), vec![(false, 0)]);
+    }
+
+    #[test]
+    fn counterexample_test() {
+        // a "fast" sum that forgets items over 50
+        let found = counterexample(100, items, |_| true, |items| items.iter().sum::<u64>(), |items| {
+            items.iter().filter(|&&item| item <= 50).sum::<u64>()
+        });
+        let found = found.unwrap();
+        assert_eq!((found.case, found.naive, found.fast), (vec![51], 51, 0));
+        assert!(found.shrinks > 0);
+    }
+
+    #[test]
+    fn valid_test() {
+        // the same bug, but only cases without a 51 are valid, so it shrinks to 52
+        let found = counterexample(100, items, |items| !items.contains(&51), |items| items.iter().sum::<u64>(), |items| {
+            items.iter().filter(|&&item| item <= 50).sum::<u64>()
+        });
+        assert_eq!(found.map(|found| found.case), Some(vec![52]));
+    }
+
+    #[test]
+    fn agree_test() {
+        agree(100, items, |_| true, |items| items.iter().sum::<u64>(), |items| items.iter().rev().sum::<u64>());
+    }
+
+    #[test]
+    #[should_panic(expected = "the implementations disagree on [51] (shrunk")]
+    fn disagree_test() {
+        agree(100, items, |_| true, |items| items.iter().sum::<u64>(), |items| items.iter().filter(|&&item| item <= 50).sum::<u64>());
+    }
+}
@@ -0,0 +1,456 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::answers::AnswersError;
+use crate::error::ParseError;
+use crate::solution::{ExportFormat, ParamError, Params, Part, RunError};
+
+pub const USAGE: &str = "\
+Usage:
+  aoc2024 run <day> [--part 1|2] [--input <path>|- | --example <name>] [--param <key>=<value>]... [--format text|json] [--verbose]
+  aoc2024 run --all [--part 1|2] [--format text|json] [--verbose]
+  aoc2024 export <day> --to plantuml|dot|verilog --output <path> [--input <path>|-]
+  aoc2024 generate <day> [--seed <n>] [--param <key>=<value>]... [--output <path>]
+  aoc2024 list
+  aoc2024 help
+
+Options:
+  --part <1|2>     only answer one part
+  --input <path>   read the puzzle input from <path> instead of input/day<N>.txt; `-` reads stdin
+  --param <k>=<v>  override one of the day's sizes or thresholds, e.g. `--param width=11`;
+                   for `generate`, size the input, e.g. `--param size=500` or `--param swaps=2`
+  --example <name> run examples/day<N>/<name>.txt, checked against the answers beside it
+  --all            run every registered day
+  --format <fmt>   `text` (the default) or `json`, one object per day
+  -v, --verbose    log the days' debug output to stderr
+  --to <fmt>       what `export` writes: `plantuml`, `dot` or `verilog`
+  --output <path>  where `export` writes to; `generate` writes to stdout without it
+  --seed <n>       the seed `generate` starts from, so the same input comes out again";
+
+/// Which days a `run` covers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    Day(u8),
+    All,
+}
+
+/// Where a day reads its puzzle input from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    /// `input/day<N>.txt`.
+    Default,
+    File(PathBuf),
+    Stdin,
+    /// `examples/day<N>/<name>.txt`, with its params and answers.
+    Example(String),
+}
+
+/// How results are written to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Text,
+    /// One JSON object per day, one per line.
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run {
+        target: Target,
+        part: Option<Part>,
+        input: InputSource,
+        /// Overrides for the day's config; answers are not checked when there are any.
+        params: Params,
+        format: Format,
+        verbose: bool,
+    },
+    /// Write a day's parsed input out for other tools.
+    Export {
+        day: u8,
+        format: ExportFormat,
+        input: InputSource,
+        output: PathBuf,
+    },
+    /// Write a random puzzle input, with the answers its construction guarantees on stderr.
+    Generate {
+        day: u8,
+        seed: u64,
+        params: Params,
+        output: Option<PathBuf>,
+    },
+    List,
+    Help,
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    /// The arguments could not be understood.
+    Usage(String),
+    UnknownDay(u8),
+    /// `known` lists the examples the day does have.
+    NoExample { day: u8, name: String, known: Vec<String> },
+    NoExport(u8),
+    /// An example's input was read but its answers file is missing or malformed.
+    Example(AnswersError),
+    Input { path: String, source: std::io::Error },
+    Output { path: String, source: std::io::Error },
+    /// The puzzle input was read but is malformed.
+    Parse(ParseError),
+    /// A `--param` the day does not take, or with a value it cannot use.
+    Param { day: u8, error: ParamError },
+    /// The input parsed, but the day could not answer it.
+    Solve { day: u8, error: RunError },
+}
+
+impl CliError {
+    /// The process exit code to report: 2 for bad arguments, 1 for everything else.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Usage(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::UnknownDay(day) => write!(f, "day {} is not registered; `list` shows the available days", day),
+            CliError::NoExample { day, name, known } if known.is_empty() => {
+                write!(f, "day {} has no example `{}`; it has no examples", day, name)
+            }
+            CliError::NoExample { day, name, known } => {
+                write!(f, "day {} has no example `{}`; it has {}", day, name, known.join(", "))
+            }
+            CliError::NoExport(day) => write!(f, "day {} has nothing to export", day),
+            CliError::Example(error) => write!(f, "{}", error),
+            CliError::Input { path, source } => write!(f, "could not read input from {}: {}", path, source),
+            CliError::Output { path, source } => write!(f, "could not write {}: {}", path, source),
+            CliError::Parse(error) => write!(f, "malformed input in {}", error),
+            CliError::Param { day, error } => write!(f, "day {}: {}", day, error),
+            CliError::Solve { day, error } => write!(f, "day {}: {}", day, error),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+fn usage(message: impl Into<String>) -> CliError {
+    CliError::Usage(message.into())
+}
+
+/// Parse the command line, without the program name.
+pub fn parse_args<I>(args: I) -> Result<Command, CliError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        None | Some("help") | Some("-h") | Some("--help") => Ok(Command::Help),
+        Some("list") => match args.next() {
+            None => Ok(Command::List),
+            Some(extra) => Err(usage(format!("unexpected argument `{}` for `list`", extra))),
+        },
+        Some("run") => parse_run(args),
+        Some("export") => parse_export(args),
+        Some("generate") => parse_generate(args),
+        Some(other) => Err(usage(format!("unknown command `{}`", other))),
+    }
+}
+
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    let mut target = None;
+    let mut part = None;
+    let mut input = InputSource::Default;
+    let mut params = Params::default();
+    let mut format = Format::Text;
+    let mut verbose = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--all" => set_target(&mut target, Target::All)?,
+            "-v" | "--verbose" => verbose = true,
+            "--format" => {
+                let value = args.next().ok_or_else(|| usage("`--format` needs a value of text or json"))?;
+                format = match value.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    _ => return Err(usage(format!("`--format` must be text or json, not `{}`", value))),
+                };
+            }
+            "--part" => {
+                let value = args.next().ok_or_else(|| usage("`--part` needs a value of 1 or 2"))?;
+                part = Some(match value.as_str() {
+                    "1" => Part::One,
+                    "2" => Part::Two,
+                    _ => return Err(usage(format!("`--part` must be 1 or 2, not `{}`", value))),
+                });
+            }
+            "--input" => set_input(&mut input, parse_input(args.next())?)?,
+            "--example" => {
+                let name = args.next().ok_or_else(|| usage("`--example` needs a name"))?;
+                set_input(&mut input, InputSource::Example(name))?;
+            }
+            "--param" => parse_param(args.next(), &mut params)?,
+            flag if flag.starts_with("--") => return Err(usage(format!("unknown option `{}`", flag))),
+            day => {
+                let day = day
+                    .parse::<u8>()
+                    .map_err(|_| usage(format!("`{}` is not a day number", day)))?;
+                set_target(&mut target, Target::Day(day))?;
+            }
+        }
+    }
+
+    let target = target.ok_or_else(|| usage("`run` needs a day number or `--all`"))?;
+    if target == Target::All && input != InputSource::Default {
+        return Err(usage("`--input` and `--example` cannot be combined with `--all`"));
+    }
+    if !params.is_empty() && target == Target::All {
+        return Err(usage("`--param` needs a single day"));
+    }
+    Ok(Command::Run { target, part, input, params, format, verbose })
+}
+
+fn parse_export(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    let mut day = None;
+    let mut format = None;
+    let mut input = InputSource::Default;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--to" => {
+                let value = args.next().ok_or_else(|| usage("`--to` needs a value of plantuml, dot or verilog"))?;
+                format = Some(match value.as_str() {
+                    "plantuml" => ExportFormat::PlantUml,
+                    "dot" => ExportFormat::Dot,
+                    "verilog" => ExportFormat::Verilog,
+                    _ => return Err(usage(format!("`--to` must be plantuml, dot or verilog, not `{}`", value))),
+                });
+            }
+            "--output" => output = Some(PathBuf::from(args.next().ok_or_else(|| usage("`--output` needs a path"))?)),
+            "--input" => input = parse_input(args.next())?,
+            flag if flag.starts_with("--") => return Err(usage(format!("unknown option `{}`", flag))),
+            value => {
+                if day.is_some() {
+                    return Err(usage("`export` takes a single day number"));
+                }
+                day = Some(value.parse::<u8>().map_err(|_| usage(format!("`{}` is not a day number", value)))?);
+            }
+        }
+    }
+
+    Ok(Command::Export {
+        day: day.ok_or_else(|| usage("`export` needs a day number"))?,
+        format: format.ok_or_else(|| usage("`export` needs `--to plantuml|dot|verilog`"))?,
+        input,
+        output: output.ok_or_else(|| usage("`export` needs `--output <path>`"))?,
+    })
+}
+
+fn parse_generate(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    let mut day = None;
+    let mut seed = 2024;
+    let mut params = Params::default();
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let value = args.next().ok_or_else(|| usage("`--seed` needs a number"))?;
+                seed = value.parse().map_err(|_| usage(format!("`--seed` must be a number, not `{}`", value)))?;
+            }
+            "--param" => parse_param(args.next(), &mut params)?,
+            "--output" => output = Some(PathBuf::from(args.next().ok_or_else(|| usage("`--output` needs a path"))?)),
+            flag if flag.starts_with("--") => return Err(usage(format!("unknown option `{}`", flag))),
+            value => {
+                if day.is_some() {
+                    return Err(usage("`generate` takes a single day number"));
+                }
+                day = Some(value.parse::<u8>().map_err(|_| usage(format!("`{}` is not a day number", value)))?);
+            }
+        }
+    }
+
+    Ok(Command::Generate { day: day.ok_or_else(|| usage("`generate` needs a day number"))?, seed, params, output })
+}
+
+/// Add the `key=value` of a `--param` to `params`.
+fn parse_param(value: Option<String>, params: &mut Params) -> Result<(), CliError> {
+    let value = value.ok_or_else(|| usage("`--param` needs a `key=value`"))?;
+    let (key, value) =
+        value.split_once('=').ok_or_else(|| usage(format!("`--param` takes `key=value`, not `{}`", value)))?;
+    params.0.push((key.to_string(), value.to_string()));
+    Ok(())
+}
+
+/// The value of `--input`: a path, or `-` for stdin.
+fn parse_input(value: Option<String>) -> Result<InputSource, CliError> {
+    let value = value.ok_or_else(|| usage("`--input` needs a path, or `-` for stdin"))?;
+    Ok(match value.as_str() {
+        "-" => InputSource::Stdin,
+        _ => InputSource::File(PathBuf::from(value)),
+    })
+}
+
+/// `run` reads from one place: its puzzle input, a file, stdin or an example.
+fn set_input(input: &mut InputSource, value: InputSource) -> Result<(), CliError> {
+    if *input != InputSource::Default {
+        return Err(usage("`run` takes a single `--input` or `--example`"));
+    }
+    *input = value;
+    Ok(())
+}
+
+fn set_target(target: &mut Option<Target>, value: Target) -> Result<(), CliError> {
+    if target.is_some() {
+        return Err(usage("`run` takes a single day number or `--all`"));
+    }
+    *target = Some(value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, CliError> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_shows_help() {
+        assert_eq!(parse(&[]).unwrap(), Command::Help);
+    }
+
+    #[test]
+    fn run_a_day() {
+        assert_eq!(
+            parse(&["run", "7"]).unwrap(),
+            Command::Run { target: Target::Day(7), part: None, input: InputSource::Default, params: Params::default(), format: Format::Text, verbose: false }
+        );
+    }
+
+    #[test]
+    fn run_one_part_from_a_file() {
+        assert_eq!(
+            parse(&["run", "12", "--part", "2", "--input", "my.txt"]).unwrap(),
+            Command::Run {
+                target: Target::Day(12),
+                part: Some(Part::Two),
+                input: InputSource::File(PathBuf::from("my.txt")),
+                params: Params::default(),
+                format: Format::Text,
+                verbose: false,
+            }
+        );
+    }
+
+    #[test]
+    fn dash_reads_stdin() {
+        assert_eq!(
+            parse(&["run", "1", "--input", "-"]).unwrap(),
+            Command::Run { target: Target::Day(1), part: None, input: InputSource::Stdin, params: Params::default(), format: Format::Text, verbose: false }
+        );
+    }
+
+    #[test]
+    fn run_all() {
+        assert_eq!(
+            parse(&["run", "--all"]).unwrap(),
+            Command::Run { target: Target::All, part: None, input: InputSource::Default, params: Params::default(), format: Format::Text, verbose: false }
+        );
+    }
+
+    #[test]
+    fn json_and_verbose() {
+        assert_eq!(
+            parse(&["run", "--all", "--format", "json", "-v"]).unwrap(),
+            Command::Run { target: Target::All, part: None, input: InputSource::Default, params: Params::default(), format: Format::Json, verbose: true }
+        );
+    }
+
+    #[test]
+    fn export_a_day() {
+        assert_eq!(
+            parse(&["export", "24", "--to", "dot", "--output", "day24.dot", "--input", "-"]).unwrap(),
+            Command::Export { day: 24, format: ExportFormat::Dot, input: InputSource::Stdin, output: PathBuf::from("day24.dot") }
+        );
+        assert_eq!(
+            parse(&["export", "--output", "adder.v", "--to", "verilog", "24"]).unwrap(),
+            Command::Export { day: 24, format: ExportFormat::Verilog, input: InputSource::Default, output: PathBuf::from("adder.v") }
+        );
+    }
+
+    #[test]
+    fn params_in_order() {
+        let Command::Run { params, .. } = parse(&["run", "14", "--param", "width=11", "--param", "height=7"]).unwrap() else {
+            panic!("expected a run");
+        };
+        assert_eq!(params, Params::new(&[("width", "11"), ("height", "7")]));
+    }
+
+    #[test]
+    fn generate_a_day() {
+        assert_eq!(
+            parse(&["generate", "23", "--param", "clique=8", "--seed", "7"]).unwrap(),
+            Command::Generate { day: 23, seed: 7, params: Params::new(&[("clique", "8")]), output: None }
+        );
+        assert_eq!(
+            parse(&["generate", "--output", "big.txt", "9"]).unwrap(),
+            Command::Generate { day: 9, seed: 2024, params: Params::default(), output: Some(PathBuf::from("big.txt")) }
+        );
+    }
+
+    #[test]
+    fn run_an_example() {
+        let Command::Run { input, params, .. } = parse(&["run", "18", "--example", "example", "--param", "bytes=20"]).unwrap() else {
+            panic!("expected a run");
+        };
+        assert_eq!((input, params), (InputSource::Example("example".to_string()), Params::new(&[("bytes", "20")])));
+    }
+
+    #[test]
+    fn list() {
+        assert_eq!(parse(&["list"]).unwrap(), Command::List);
+    }
+
+    #[test]
+    fn bad_arguments_are_usage_errors() {
+        for args in [
+            vec!["frobnicate"],
+            vec!["run"],
+            vec!["run", "x"],
+            vec!["run", "1", "2"],
+            vec!["run", "1", "--all"],
+            vec!["run", "1", "--part", "3"],
+            vec!["run", "1", "--part"],
+            vec!["run", "1", "--input"],
+            vec!["run", "--all", "--input", "-"],
+            vec!["run", "1", "--quiet"],
+            vec!["run", "1", "--format", "xml"],
+            vec!["run", "1", "--example"],
+            vec!["run", "1", "--example", "example", "--input", "-"],
+            vec!["run", "--all", "--example", "example"],
+            vec!["list", "1"],
+            vec!["run", "14", "--param"],
+            vec!["run", "14", "--param", "width"],
+            vec!["run", "--all", "--param", "width=11"],
+            vec!["export", "24", "--to", "dot"],
+            vec!["export", "24", "--output", "x.dot"],
+            vec!["export", "--to", "dot", "--output", "x.dot"],
+            vec!["export", "24", "--to", "svg", "--output", "x.svg"],
+            vec!["export", "24", "25", "--to", "dot", "--output", "x.dot"],
+            vec!["generate"],
+            vec!["generate", "9", "--seed", "x"],
+            vec!["generate", "9", "--param", "size"],
+            vec!["generate", "9", "10"],
+        ] {
+            let error = parse(&args).unwrap_err();
+            assert_eq!(error.exit_code(), 2, "{:?} gave {}", args, error);
+        }
+    }
+}
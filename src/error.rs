@@ -0,0 +1,131 @@
+//! The error a day reports when its puzzle input cannot be parsed.
+//!
+//! It points at the offending line and column of the input, so a malformed
+//! file reads as
+//!
+//! ```text
+//! day 14, line 3, column 9: expected Digit
+//!   p=0,4 v=x,-3
+//!           ^
+//! ```
+
+use std::fmt;
+
+use nom::error::ErrorKind;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// Filled in by the runner, which knows which day it is running.
+    pub day: Option<u8>,
+    /// 1-based line of the input.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    pub kind: ErrorKind,
+    /// The text of the offending line.
+    pub text: String,
+}
+
+impl ParseError {
+    /// An error at `position`, which must be a slice of `input`, e.g. what a nom parser had left.
+    pub fn at(input: &str, position: &str, kind: ErrorKind) -> Self {
+        let offset = (position.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|&offset| offset <= input.len())
+            .expect("position is not part of the input");
+        let before = &input[..offset];
+        let start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        let text = input[start..].lines().next().unwrap_or_default();
+        ParseError {
+            day: None,
+            line: before.matches('\n').count() + 1,
+            column: before[start..].chars().count() + 1,
+            kind,
+            text: text.to_string(),
+        }
+    }
+
+    /// Convert a nom error raised while parsing a slice of `input`.
+    pub fn from_nom(input: &str, error: nom::Err<nom::error::Error<&str>>) -> Self {
+        match error {
+            nom::Err::Error(error) | nom::Err::Failure(error) => Self::at(input, error.input, error.code),
+            nom::Err::Incomplete(_) => Self::at(input, &input[input.len()..], ErrorKind::Eof),
+        }
+    }
+
+    pub fn in_day(self, day: u8) -> Self {
+        ParseError { day: Some(day), ..self }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}, ", day)?;
+        }
+        writeln!(f, "line {}, column {}: expected {}", self.line, self.column, self.kind.description())?;
+        writeln!(f, "  {}", self.text)?;
+        write!(f, "  {:>column$}", "^", column = self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Run `parser` over the whole of `input`, converting its error.
+///
+/// Only whitespace may be left over; anything else is reported where it starts.
+pub fn parse_with<'a, O>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> nom::IResult<&'a str, O>,
+) -> Result<O, ParseError> {
+    let (remaining, output) = parser(input).map_err(|error| ParseError::from_nom(input, error))?;
+    let rest = remaining.trim_start();
+    if rest.is_empty() {
+        Ok(output)
+    } else {
+        Err(ParseError::at(input, rest, ErrorKind::Eof))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::character::complete::digit1;
+
+    #[test]
+    fn position_test() {
+        let input = "12\n34 5x\n";
+        let error = ParseError::at(input, &input[8..], ErrorKind::Digit);
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 6, "34 5x"));
+        let start = ParseError::at(input, input, ErrorKind::Tag);
+        assert_eq!((start.line, start.column, start.text.as_str()), (1, 1, "12"));
+        let end = ParseError::at(input, &input[input.len()..], ErrorKind::Eof);
+        assert_eq!((end.line, end.column, end.text.as_str()), (3, 1, ""));
+    }
+
+    #[test]
+    fn display_test() {
+        let input = "1\nab";
+        let error = ParseError::at(input, &input[3..], ErrorKind::Digit).in_day(14);
+        assert_eq!(error.to_string(), "day 14, line 2, column 2: expected Digit\n  ab\n   ^");
+    }
+
+    #[test]
+    fn from_nom_test() {
+        let input = "7\nx";
+        let line = input.lines().nth(1).unwrap();
+        let error = digit1::<_, nom::error::Error<&str>>(line).unwrap_err();
+        let error = ParseError::from_nom(input, error);
+        assert_eq!((error.line, error.column, error.kind), (2, 1, ErrorKind::Digit));
+        let input = "x1";
+        assert_eq!(parse_with(input, digit1), Err(ParseError::at(input, input, ErrorKind::Digit)));
+    }
+
+    #[test]
+    fn parse_with_leftovers_test() {
+        assert_eq!(parse_with("12\n\n", digit1), Ok("12"));
+        let input = "12\n3";
+        let error = parse_with(input, digit1).unwrap_err();
+        assert_eq!((error.line, error.column, error.kind), (2, 1, ErrorKind::Eof));
+    }
+}
@@ -0,0 +1,150 @@
+//! Worked examples for each day, kept as `examples/day<N>/<name>.txt` with the answers beside
+//! them in `<name>.toml`. That is an answers file, as in [`crate::answers`], which may also set
+//! the `--param`s the example is solved with:
+//!
+//! ```toml
+//! [params]
+//! saving = 64
+//!
+//! [part1]
+//! answer = 1
+//! ```
+
+use std::fs;
+use std::io;
+
+use crate::answers::{self, AnswersError, DayAnswers};
+use crate::solution::{Params, Part};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Example {
+    pub input: String,
+    pub params: Params,
+    pub expected: DayAnswers,
+}
+
+impl Example {
+    /// The part the example has an answer for, or `None` when it has both, so a run can skip
+    /// a part the example was never meant for.
+    pub fn part(&self) -> Option<Part> {
+        match (&self.expected.part1.answer, &self.expected.part2.answer) {
+            (Some(_), None) => Some(Part::One),
+            (None, Some(_)) => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+fn directory(day: u8) -> String {
+    format!("examples/day{}", day)
+}
+
+/// The names of the day's examples, sorted; none when it has no examples directory.
+pub fn names(day: u8) -> Result<Vec<String>, AnswersError> {
+    let path = directory(day);
+    let entries = match fs::read_dir(&path) {
+        Ok(entries) => entries,
+        Err(source) if source.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(source) => return Err(AnswersError::Io { path, source }),
+    };
+    let mut names = vec![];
+    for entry in entries {
+        let entry = entry.map_err(|source| AnswersError::Io { path: path.clone(), source })?;
+        if let Some(name) = entry.file_name().to_str().and_then(|file| file.strip_suffix(".txt")) {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Load the example and its answers, or `None` when the day has no example of that name.
+pub fn load(day: u8, name: &str) -> Result<Option<Example>, AnswersError> {
+    let path = format!("{}/{}.txt", directory(day), name);
+    let input = match fs::read_to_string(&path) {
+        Ok(input) => input,
+        Err(source) if source.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => return Err(AnswersError::Io { path, source }),
+    };
+    let path = format!("{}/{}.toml", directory(day), name);
+    let text = fs::read_to_string(&path).map_err(|source| AnswersError::Io { path: path.clone(), source })?;
+    let (expected, params) =
+        answers::parse_example(&text).map_err(|(line, text)| AnswersError::Parse { path, line, text })?;
+    Ok(Some(Example { input, params, expected }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles;
+
+    /// Run the example through its day and check every answer it records.
+    fn solve(day: u8, name: &str) {
+        let example = load(day, name).unwrap().unwrap_or_else(|| panic!("day {} has no example `{}`", day, name));
+        assert!(example.expected.part1.answer.is_some() || example.expected.part2.answer.is_some(), "no answers to check");
+        let (answers, _) = (puzzles::find(day).unwrap().run)(&example.input, example.part(), &example.params).unwrap();
+        for (part, expected, answer) in [
+            ("part1", &example.expected.part1.answer, &answers.part1),
+            ("part2", &example.expected.part2.answer, &answers.part2),
+        ] {
+            if expected.is_some() {
+                assert_eq!(answer, expected, "{} of day {} example `{}`", part, day, name);
+            }
+        }
+    }
+
+    /// One test per fixture; `fixtures_listed_test` checks nothing in `examples/` is missed.
+    macro_rules! examples {
+        ($($test:ident: $day:literal $name:literal,)*) => {
+            $(
+                #[test]
+                fn $test() {
+                    solve($day, $name);
+                }
+            )*
+
+            const LISTED: &[(u8, &str)] = &[$(($day, $name)),*];
+        };
+    }
+
+    examples! {
+        day1_example: 1 "example",
+        day2_example: 2 "example",
+        day2_dampener: 2 "dampener",
+        day3_example: 3 "example",
+        day7_example: 7 "example",
+        day7_concatenation: 7 "concatenation",
+        day15_large: 15 "large",
+        day15_small: 15 "small",
+        day15_wide: 15 "wide",
+        day18_example: 18 "example",
+        day19_example: 19 "example",
+        day20_example: 20 "example",
+        day22_example: 22 "example",
+        day22_prices: 22 "prices",
+        day23_example: 23 "example",
+        day24_small: 24 "small",
+        day24_larger: 24 "larger",
+    }
+
+    #[test]
+    fn fixtures_listed_test() {
+        let found: Vec<(u8, String)> =
+            (1..=25).flat_map(|day| names(day).unwrap().into_iter().map(move |name| (day, name))).collect();
+        let listed: Vec<(u8, String)> = LISTED.iter().map(|&(day, name)| (day, name.to_string())).collect();
+        let mut sorted = listed.clone();
+        sorted.sort();
+        assert_eq!(found, sorted);
+    }
+
+    #[test]
+    fn load_test() {
+        let example = load(18, "example").unwrap().unwrap();
+        assert_eq!(example.params, Params::new(&[("width", "7"), ("height", "7"), ("bytes", "12")]));
+        assert_eq!(example.part(), None);
+        assert_eq!(load(15, "small").unwrap().unwrap().part(), Some(Part::One));
+        assert_eq!(load(18, "missing").unwrap(), None);
+        assert_eq!(names(18).unwrap(), vec!["example"]);
+        assert_eq!(names(99).unwrap(), Vec::<String>::new());
+    }
+}
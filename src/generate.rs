@@ -0,0 +1,1087 @@
+//! Seeded random puzzle inputs, for benchmarking at scale and for trying the solvers on shapes
+//! our own inputs never have.
+//!
+//! Every day has a generator, sized by `--param`s the way `run` configures a day. Where the
+//! construction pins an answer down (a planted clique, a chosen set of swapped gates, ...) the
+//! answer comes back with the input; elsewhere only solving would tell, and it is `None`.
+//!
+//! ```text
+//! let generate = generate::generator(23).unwrap();
+//! let generated = generate(&mut Rng::new(seed), &Params::new(&[("clique", "8")]))?;
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Write};
+
+use num::BigUint;
+
+use crate::grid::{Direction, Grid, Point};
+use crate::search;
+use crate::solution::{param_value, Config, ParamError, Params};
+
+/// SplitMix64: tiny, and the same sequence for a seed on every platform.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`, near enough; `n` must not be 0.
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Uniform in `low..=high`.
+    pub(crate) fn between(&mut self, low: usize, high: usize) -> usize {
+        low + self.below(high - low + 1)
+    }
+
+    pub(crate) fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    pub(crate) fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    /// Fisher-Yates.
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A puzzle input, with whichever answers its construction guarantees.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Generated {
+    pub(crate) input: String,
+    pub(crate) part1: Option<String>,
+    pub(crate) part2: Option<String>,
+}
+
+impl Generated {
+    fn new(input: String) -> Self {
+        Generated { input, part1: None, part2: None }
+    }
+
+    fn with_part1(mut self, answer: impl Display) -> Self {
+        self.part1 = Some(answer.to_string());
+        self
+    }
+
+    fn with_part2(mut self, answer: impl Display) -> Self {
+        self.part2 = Some(answer.to_string());
+        self
+    }
+}
+
+pub(crate) type Generator = fn(&mut Rng, &Params) -> Result<Generated, ParamError>;
+
+/// The generator for `day`, if it is one we have.
+pub(crate) fn generator(day: u8) -> Option<Generator> {
+    Some(match day {
+        1 => day1 as Generator,
+        2 => day2,
+        3 => day3,
+        4 => day4,
+        5 => day5,
+        6 => day6,
+        7 => day7,
+        8 => day8,
+        9 => day9,
+        10 => day10,
+        11 => day11,
+        12 => day12,
+        13 => day13,
+        14 => day14,
+        15 => day15,
+        16 => day16,
+        17 => day17,
+        18 => day18,
+        19 => day19,
+        20 => day20,
+        21 => day21,
+        22 => day22,
+        23 => day23,
+        24 => day24,
+        _ => return None,
+    })
+}
+
+/// The one knob most generators have: how many lines, robots, bytes, ... to write, or how wide a
+/// square map is. `DEFAULT` is about the size of a real puzzle input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Size<const DEFAULT: usize>(usize);
+
+impl<const DEFAULT: usize> Default for Size<DEFAULT> {
+    fn default() -> Self {
+        Size(DEFAULT)
+    }
+}
+
+impl<const DEFAULT: usize> Config for Size<DEFAULT> {
+    const KEYS: &'static [&'static str] = &["size"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "size" => self.0 = param_value(key, value)?,
+            _ => return Err(Self::unknown(key)),
+        }
+        Ok(())
+    }
+}
+
+/// For the days whose inputs have no size worth varying.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct Fixed;
+
+impl Config for Fixed {
+    const KEYS: &'static [&'static str] = &[];
+
+    fn set(&mut self, key: &str, _value: &str) -> Result<(), ParamError> {
+        Err(Self::unknown(key))
+    }
+}
+
+/// The network for day 23: how many computers, and how many of them form the LAN party.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Lan {
+    computers: usize,
+    clique: usize,
+}
+
+impl Default for Lan {
+    fn default() -> Self {
+        Lan { computers: 520, clique: 13 }
+    }
+}
+
+impl Config for Lan {
+    const KEYS: &'static [&'static str] = &["computers", "clique"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "computers" => self.computers = param_value(key, value)?,
+            "clique" => self.clique = param_value(key, value)?,
+            _ => return Err(Self::unknown(key)),
+        }
+        Ok(())
+    }
+}
+
+/// The adder for day 24: how many bits x and y have, and how many pairs of outputs are swapped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Adder {
+    bits: usize,
+    swaps: usize,
+}
+
+impl Default for Adder {
+    fn default() -> Self {
+        Adder { bits: 45, swaps: 4 }
+    }
+}
+
+impl Config for Adder {
+    const KEYS: &'static [&'static str] = &["bits", "swaps"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "bits" => self.bits = param_value(key, value)?,
+            "swaps" => self.swaps = param_value(key, value)?,
+            _ => return Err(Self::unknown(key)),
+        }
+        Ok(())
+    }
+}
+
+fn shape<C: Config>(params: &Params) -> Result<C, ParamError> {
+    let mut config = C::default();
+    config.apply(params)?;
+    Ok(config)
+}
+
+/// `value` for `key` unless it lies outside `range`.
+fn within(key: &str, value: usize, range: std::ops::RangeInclusive<usize>) -> Result<usize, ParamError> {
+    match range.contains(&value) {
+        true => Ok(value),
+        false => Err(ParamError::Invalid { key: key.to_string(), value: value.to_string() }),
+    }
+}
+
+fn join<T: Display>(items: &[T], separator: &str) -> String {
+    items.iter().map(T::to_string).collect::<Vec<_>>().join(separator)
+}
+
+/// Two columns of location ids, some shared between the lists.
+fn day1(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let lines = within("size", shape::<Size<1000>>(params)?.0, 1..=usize::MAX)?;
+    let left: Vec<usize> = (0..lines).map(|_| rng.between(10_000, 99_999)).collect();
+    // part 2 needs ids that appear in both lists
+    let right: Vec<usize> =
+        (0..lines).map(|_| if rng.chance(30) { *rng.pick(&left) } else { rng.between(10_000, 99_999) }).collect();
+    let input = left.iter().zip(&right).map(|(l, r)| format!("{}   {}\n", l, r)).collect();
+
+    let (mut sorted_left, mut sorted_right) = (left.clone(), right.clone());
+    sorted_left.sort();
+    sorted_right.sort();
+    let distance: usize = sorted_left.iter().zip(&sorted_right).map(|(l, r)| l.abs_diff(*r)).sum();
+    let similarity: usize = left.iter().map(|l| l * right.iter().filter(|&r| r == l).count()).sum();
+    Ok(Generated::new(input).with_part1(distance).with_part2(similarity))
+}
+
+/// Reports that are safe, safe but for one repeated level, or repeat two levels too far apart
+/// for the dampener to fix.
+fn day2(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let lines = within("size", shape::<Size<1000>>(params)?.0, 1..=usize::MAX)?;
+    let (mut safe, mut dampened) = (0, 0);
+    let mut input = String::new();
+    for _ in 0..lines {
+        let rising = rng.chance(50);
+        let mut level = rng.between(30, 60);
+        let mut report: Vec<usize> = (0..rng.between(5, 8))
+            .map(|_| {
+                let current = level;
+                let step = rng.between(1, 3);
+                level = if rising { level + step } else { level - step };
+                current
+            })
+            .collect();
+        match rng.below(3) {
+            0 => safe += 1,
+            1 => {
+                let i = rng.below(report.len());
+                report.insert(i, report[i]);
+                dampened += 1;
+            }
+            _ => {
+                let i = rng.below(report.len() - 2);
+                let j = rng.between(i + 2, report.len() - 1);
+                report.insert(j, report[j]);
+                report.insert(i, report[i]);
+            }
+        }
+        writeln!(input, "{}", join(&report, " ")).unwrap();
+    }
+    Ok(Generated::new(input).with_part1(safe).with_part2(safe + dampened))
+}
+
+/// Corrupted memory: real instructions between near misses that must not parse.
+fn day3(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    const JUNK: [&str; 16] = [
+        "mul(4*", "mul[3,7]", "mul ( 2 , 4 )", "?mul(8,5", "from()", "select()", "what()", "where()", "#", "%&",
+        "]then(", "mul(32,64]", "who(", "mul(1,2,3)", "@", "don't",
+    ];
+    let lines = within("size", shape::<Size<6>>(params)?.0, 1..=usize::MAX)?;
+    let (mut all, mut enabled_sum, mut enabled) = (0, 0, true);
+    let mut input = String::new();
+    for _ in 0..lines {
+        for _ in 0..rng.between(40, 80) {
+            match rng.below(20) {
+                0..=8 => {
+                    let (a, b) = (rng.between(1, 999), rng.between(1, 999));
+                    write!(input, "mul({},{})", a, b).unwrap();
+                    all += a * b;
+                    if enabled {
+                        enabled_sum += a * b;
+                    }
+                }
+                9 => {
+                    input.push_str("do()");
+                    enabled = true;
+                }
+                10 => {
+                    input.push_str("don't()");
+                    enabled = false;
+                }
+                _ => input.push_str(rng.pick::<&str>(&JUNK)),
+            }
+        }
+        input.push('\n');
+    }
+    Ok(Generated::new(input).with_part1(all).with_part2(enabled_sum))
+}
+
+/// A square word search with every XMAS and X-MAS placed on purpose: words lie along rows and
+/// crosses in bands of three rows, with an empty row after each, so no word runs between plants.
+fn day4(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let side = within("size", shape::<Size<140>>(params)?.0, 1..=usize::MAX)?;
+    let mut grid = Grid::new(side, side, '.');
+    let (mut words, mut crosses) = (0, 0);
+    let mut y = 1;
+    while y < side {
+        if y + 3 <= side && rng.chance(50) {
+            let mut x = rng.below(4);
+            while x + 3 <= side {
+                // each diagonal reads MAS one way or the other
+                let (top_left, bottom_right) = if rng.chance(50) { ('M', 'S') } else { ('S', 'M') };
+                let (top_right, bottom_left) = if rng.chance(50) { ('M', 'S') } else { ('S', 'M') };
+                grid[Point::new(x, y)] = top_left;
+                grid[Point::new(x + 2, y)] = top_right;
+                grid[Point::new(x + 1, y + 1)] = 'A';
+                grid[Point::new(x, y + 2)] = bottom_left;
+                grid[Point::new(x + 2, y + 2)] = bottom_right;
+                crosses += 1;
+                x += 4 + rng.below(6);
+            }
+            y += 4;
+        } else {
+            let mut x = rng.below(4);
+            while x + 4 <= side {
+                let word = if rng.chance(50) { "XMAS" } else { "SAMX" };
+                for (i, c) in word.chars().enumerate() {
+                    grid[Point::new(x + i, y)] = c;
+                }
+                words += 1;
+                x += 5 + rng.below(6);
+            }
+            y += 2;
+        }
+    }
+    Ok(Generated::new(grid.to_string()).with_part1(words).with_part2(crosses))
+}
+
+/// Rules for every pair of 49 pages in one hidden order, and updates drawn from them.
+fn day5(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let updates = within("size", shape::<Size<200>>(params)?.0, 1..=usize::MAX)?;
+    let mut order: Vec<usize> = (10..100).collect();
+    rng.shuffle(&mut order);
+    order.truncate(49);
+    let rank: HashMap<usize, usize> = order.iter().enumerate().map(|(i, &page)| (page, i)).collect();
+    let mut rules: Vec<String> = order
+        .iter()
+        .enumerate()
+        .flat_map(|(i, before)| order[i + 1..].iter().map(move |after| format!("{}|{}", before, after)))
+        .collect();
+    rng.shuffle(&mut rules);
+
+    let (mut ordered, mut reordered) = (0, 0);
+    let mut lines = vec![];
+    for _ in 0..updates {
+        let mut pages = order.clone();
+        rng.shuffle(&mut pages);
+        pages.truncate(5 + 2 * rng.below(10));
+        if rng.chance(50) {
+            pages.sort_by_key(|page| rank[page]);
+        }
+        let mut sorted = pages.clone();
+        sorted.sort_by_key(|page| rank[page]);
+        if sorted == pages {
+            ordered += sorted[sorted.len() / 2];
+        } else {
+            reordered += sorted[sorted.len() / 2];
+        }
+        lines.push(join(&pages, ","));
+    }
+    let input = format!("{}\n\n{}\n", rules.join("\n"), lines.join("\n"));
+    Ok(Generated::new(input).with_part1(ordered).with_part2(reordered))
+}
+
+/// A lab laid out along a patrol: the guard walks a stretch, meets an obstruction put there
+/// for them, turns, and so on until they have covered a tenth of the lab and the way ahead is
+/// clear to the edge. Scattered obstructions go wherever the guard never walks.
+fn day6(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let side = within("size", shape::<Size<130>>(params)?.0, 1..=usize::MAX)?;
+    'lab: loop {
+        let mut grid = Grid::new(side, side, '.');
+        let start = Point::new(rng.below(side), rng.below(side));
+        let (mut guard, mut direction) = (start, Direction::North);
+        let mut walked = HashSet::from([start]);
+        loop {
+            let ahead: Vec<Point> =
+                std::iter::successors(grid.step(guard, direction), |&point| grid.step(point, direction)).collect();
+            if walked.len() >= side * side / 10 && ahead.iter().all(|&point| grid[point] != '#') {
+                walked.extend(ahead);
+                break;
+            }
+            // favour long stretches; a stuck patrol starts the lab again
+            let stretch = (0..20).find_map(|_| {
+                let length = rng.between(1, ahead.len().max(2) - 1).max(rng.between(1, ahead.len().max(2) - 1));
+                let clear = length < ahead.len() && ahead[..length].iter().all(|&point| grid[point] != '#');
+                (clear && !walked.contains(&ahead[length])).then_some(length)
+            });
+            let Some(length) = stretch else {
+                continue 'lab;
+            };
+            grid[ahead[length]] = '#';
+            walked.extend(&ahead[..length]);
+            guard = ahead[length - 1];
+            direction = direction.turn_right();
+        }
+        for point in grid.points().collect::<Vec<_>>() {
+            if !walked.contains(&point) && rng.chance(3) {
+                grid[point] = '#';
+            }
+        }
+        grid[start] = '^';
+        debug_assert_eq!(patrol(&grid, start), Some(walked.len()));
+        return Ok(Generated::new(grid.to_string()).with_part1(walked.len()));
+    }
+}
+
+/// How many cells the guard covers before leaving the map, or `None` if they go round in a loop.
+fn patrol(grid: &Grid<char>, mut guard: Point) -> Option<usize> {
+    let mut direction = Direction::North;
+    let mut seen = HashSet::new();
+    while seen.insert((guard, direction)) {
+        match grid.step(guard, direction) {
+            None => return Some(seen.iter().map(|(point, _)| point).collect::<HashSet<_>>().len()),
+            Some(next) if grid[next] == '#' => direction = direction.turn_right(),
+            Some(next) => guard = next,
+        }
+    }
+    None
+}
+
+/// Equations that `+` and `*` solve, that need `||`, and that nothing solves.
+///
+/// With every number at least 2, multiplying everything is the most `+` and `*` can make and
+/// concatenating everything the most any operators can, which is what tells the kinds apart.
+fn day7(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let lines = within("size", shape::<Size<850>>(params)?.0, 1..=usize::MAX)?;
+    let concatenate = |a: u64, b: u64| a * 10u64.pow(b.to_string().len() as u32) + b;
+    let (mut calibration, mut concatenated) = (0, 0);
+    let mut input = String::new();
+    for _ in 0..lines {
+        let numbers: Vec<u64> = (0..rng.between(2, 6)).map(|_| rng.between(2, 99) as u64).collect();
+        let product: u64 = numbers.iter().product();
+        let joined = numbers[1..].iter().fold(numbers[0], |acc, &n| concatenate(acc, n));
+        let target = match rng.below(3) {
+            0 => {
+                let target = numbers[1..].iter().fold(numbers[0], |acc, &n| if rng.chance(50) { acc + n } else { acc * n });
+                calibration += target;
+                concatenated += target;
+                target
+            }
+            1 => {
+                let concat_at = rng.below(numbers.len() - 1);
+                let target = numbers[1..].iter().enumerate().fold(numbers[0], |acc, (i, &n)| match rng.below(3) {
+                    _ if i == concat_at => concatenate(acc, n),
+                    0 => acc + n,
+                    1 => acc * n,
+                    _ => concatenate(acc, n),
+                });
+                // out of reach of `+` and `*`, or the line would count for part 1 too
+                let target = if target > product { target } else { joined };
+                concatenated += target;
+                target
+            }
+            _ => joined + 1,
+        };
+        writeln!(input, "{}: {}", target, join(&numbers, " ")).unwrap();
+    }
+    Ok(Generated::new(input).with_part1(calibration).with_part2(concatenated))
+}
+
+/// A square map with a few antennas on each of many frequencies.
+fn day8(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    const FREQUENCIES: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    let side = within("size", shape::<Size<50>>(params)?.0, 1..=usize::MAX)?;
+    let mut grid = Grid::new(side, side, '.');
+    let mut free: Vec<Point> = grid.points().collect();
+    rng.shuffle(&mut free);
+    let antennas = FREQUENCIES.chars().take(side * 4 / 5).flat_map(|frequency| std::iter::repeat_n(frequency, 4));
+    for (point, frequency) in free.into_iter().zip(antennas) {
+        grid[point] = frequency;
+    }
+    Ok(Generated::new(grid.to_string()))
+}
+
+/// A disk map of `size` digits, alternating files and free space.
+fn day9(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let digits = within("size", shape::<Size<19_999>>(params)?.0, 1..=usize::MAX)?;
+    let mut input: String = (0..digits)
+        .map(|i| {
+            let length = if i % 2 == 0 { rng.between(1, 9) } else { rng.below(10) };
+            char::from_digit(length as u32, 10).expect("a single digit")
+        })
+        .collect();
+    input.push('\n');
+    Ok(Generated::new(input))
+}
+
+/// Random heights, with hiking trails from 0 to 9 laid over them.
+fn day10(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let side = within("size", shape::<Size<50>>(params)?.0, 1..=usize::MAX)?;
+    let mut grid = Grid::new(side, side, 0);
+    for point in grid.points().collect::<Vec<_>>() {
+        grid[point] = rng.below(10);
+    }
+    for _ in 0..side * side / 20 {
+        let mut trail = vec![Point::new(rng.below(side), rng.below(side))];
+        while trail.len() < 10 {
+            let point = trail[trail.len() - 1];
+            let next: Vec<Point> = grid.neighbours(point).filter(|next| !trail.contains(next)).collect();
+            if next.is_empty() {
+                break;
+            }
+            trail.push(*rng.pick(&next));
+        }
+        if trail.len() == 10 {
+            trail.iter().enumerate().for_each(|(height, &point)| grid[point] = height);
+        }
+    }
+    let input = grid.render(|&height| char::from_digit(height as u32, 10).expect("a single digit"));
+    Ok(Generated::new(input))
+}
+
+/// A line of stones.
+fn day11(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let stones = within("size", shape::<Size<8>>(params)?.0, 1..=usize::MAX)?;
+    let stones: Vec<usize> = (0..stones).map(|_| rng.below(1_000_000)).collect();
+    Ok(Generated::new(format!("{}\n", join(&stones, " "))))
+}
+
+/// A garden cut into rectangles, each a different plant from the rectangles beside it, so every
+/// rectangle is a region with four sides.
+fn day12(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let side = within("size", shape::<Size<140>>(params)?.0, 1..=usize::MAX)?;
+    let mut cuts = || {
+        let mut cuts = vec![0];
+        while cuts[cuts.len() - 1] < side {
+            cuts.push((cuts[cuts.len() - 1] + rng.between(1, 6)).min(side));
+        }
+        cuts
+    };
+    let (columns, rows) = (cuts(), cuts());
+    let plants: Vec<char> = ('A'..='Z').collect();
+    let mut blocks = Grid::new(columns.len() - 1, rows.len() - 1, ' ');
+    let mut grid = Grid::new(side, side, ' ');
+    let (mut price, mut discounted) = (0, 0);
+    for block in blocks.points().collect::<Vec<_>>() {
+        let beside = [block.step(Direction::West), block.step(Direction::North)].map(|next| next.map(|next| blocks[next]));
+        let plant = loop {
+            let plant = *rng.pick(&plants);
+            if !beside.contains(&Some(plant)) {
+                break plant;
+            }
+        };
+        blocks[block] = plant;
+        let (width, height) = (columns[block.x + 1] - columns[block.x], rows[block.y + 1] - rows[block.y]);
+        price += width * height * 2 * (width + height);
+        discounted += width * height * 4;
+        for y in rows[block.y]..rows[block.y + 1] {
+            for x in columns[block.x]..columns[block.x + 1] {
+                grid[Point::new(x, y)] = plant;
+            }
+        }
+    }
+    Ok(Generated::new(grid.to_string()).with_part1(price).with_part2(discounted))
+}
+
+/// Claw machines that a hundred presses win, and machines that nothing wins because both
+/// buttons move X an even amount and the prize is at an odd X. Part 2 moves every prize too far
+/// to know without solving.
+fn day13(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let machines = within("size", shape::<Size<320>>(params)?.0, 1..=usize::MAX)?;
+    let mut tokens = 0;
+    let mut blocks = vec![];
+    for _ in 0..machines {
+        let winnable = rng.chance(60);
+        let mut button = || {
+            let x = rng.between(10, 99);
+            (if winnable { x } else { x & !1 }, rng.between(10, 99))
+        };
+        // independent buttons, so there is only one way to reach a prize
+        let (a, b) = loop {
+            let (a, b) = (button(), button());
+            if a.0 * b.1 != a.1 * b.0 {
+                break (a, b);
+            }
+        };
+        let (presses_a, presses_b) = (rng.below(101), rng.below(101));
+        let mut prize = (presses_a * a.0 + presses_b * b.0, presses_a * a.1 + presses_b * b.1);
+        if winnable {
+            tokens += 3 * presses_a + presses_b;
+        } else {
+            prize.0 += 1;
+        }
+        blocks.push(format!(
+            "Button a: X+{}, Y+{}\nButton b: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+            a.0, a.1, b.0, b.1, prize.0, prize.1
+        ));
+    }
+    Ok(Generated::new(blocks.join("\n")).with_part1(tokens))
+}
+
+/// Robots in day 14's 101 by 103 bathroom, nine in ten of which crowd into the top left
+/// quadrant at some second. Part 2 looks for the picture as the lowest safety factor in its
+/// first 10000 seconds, so the swarm is redrawn until that second is the only lowest one: x
+/// repeats every 101 seconds and y every 103, which can bring half the crowd back together.
+fn day14(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    const WIDTH: i64 = 101;
+    const HEIGHT: i64 = 103;
+    // fewer robots and the odd quadrant empties by chance, more often than the picture can beat
+    let robots = within("size", shape::<Size<500>>(params)?.0, 100..=usize::MAX)?;
+    loop {
+        let picture = rng.between(1, 10_000);
+        let swarm: Vec<(i64, i64, i64, i64)> = (0..robots)
+            .map(|robot| {
+                let (x, y) = match robot % 10 {
+                    0 => (rng.below(WIDTH as usize), rng.below(HEIGHT as usize)),
+                    _ => (rng.below(WIDTH as usize / 2), rng.below(HEIGHT as usize / 2)),
+                };
+                let (dx, dy) = (rng.between(1, 199) as i64 - 100, rng.between(1, 199) as i64 - 100);
+                let back = picture as i64;
+                ((x as i64 - back * dx).rem_euclid(WIDTH), (y as i64 - back * dy).rem_euclid(HEIGHT), dx, dy)
+            })
+            .collect();
+        let safety_factor = |seconds: usize| {
+            let mut quadrants = [0; 4];
+            for &(x, y, dx, dy) in &swarm {
+                let seconds = seconds as i64;
+                let (x, y) = ((x + seconds * dx).rem_euclid(WIDTH), (y + seconds * dy).rem_euclid(HEIGHT));
+                if x != WIDTH / 2 && y != HEIGHT / 2 {
+                    quadrants[usize::from(x > WIDTH / 2) + 2 * usize::from(y > HEIGHT / 2)] += 1;
+                }
+            }
+            quadrants.iter().product::<usize>()
+        };
+        let lowest = safety_factor(picture);
+        if (0..=10_000).all(|seconds| seconds == picture || safety_factor(seconds) > lowest) {
+            let input = swarm.iter().map(|(x, y, dx, dy)| format!("p={},{} v={},{}\n", x, y, dx, dy)).collect();
+            return Ok(Generated::new(input).with_part1(safety_factor(100)).with_part2(picture));
+        }
+    }
+}
+
+/// A walled warehouse full of boxes, and a long list of moves.
+fn day15(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let side = within("size", shape::<Size<50>>(params)?.0, 3..=usize::MAX)?;
+    let mut grid = Grid::new(side, side, '#');
+    for y in 1..side - 1 {
+        for x in 1..side - 1 {
+            grid[Point::new(x, y)] = match rng.below(100) {
+                0..=4 => '#',
+                5..=34 => 'O',
+                _ => '.',
+            };
+        }
+    }
+    grid[Point::new(rng.between(1, side - 2), rng.between(1, side - 2))] = '@';
+    let mut input = format!("{}\n", grid);
+    for _ in 0..(side * 2 / 5).max(1) {
+        input.extend((0..1000).map(|_| rng.pick(&Direction::ALL).arrow()));
+        input.push('\n');
+    }
+    Ok(Generated::new(input))
+}
+
+/// A maze with a few loops knocked through it, from the bottom left corner to the top right.
+fn day16(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let Size(side) = shape::<Size<141>>(params)?;
+    let mut grid = maze(rng, within("size", side, 5..=usize::MAX)? | 1);
+    let side = grid.width();
+    for point in grid.points().collect::<Vec<_>>() {
+        let inside = (1..side - 1).contains(&point.x) && (1..side - 1).contains(&point.y);
+        // a wall between two cells, not at a corner between four
+        if inside && (point.x % 2 == 0) != (point.y % 2 == 0) && rng.chance(10) {
+            grid[point] = '.';
+        }
+    }
+    grid[Point::new(1, side - 2)] = 'S';
+    grid[Point::new(side - 2, 1)] = 'E';
+    Ok(Generated::new(grid.to_string()))
+}
+
+/// A perfect maze on an odd `side`: cells on odd coordinates, carved by a depth-first walk.
+fn maze(rng: &mut Rng, side: usize) -> Grid<char> {
+    let mut grid = Grid::new(side, side, '#');
+    let start = Point::new(1, 1);
+    grid[start] = '.';
+    let mut stack = vec![start];
+    while let Some(&cell) = stack.last() {
+        let unvisited: Vec<(Point, Point)> = Direction::ALL
+            .iter()
+            .filter_map(|&direction| {
+                let wall = grid.step(cell, direction)?;
+                let next = grid.step(wall, direction)?;
+                (next.x < side - 1 && next.y < side - 1 && grid[next] == '#').then_some((wall, next))
+            })
+            .collect();
+        if unvisited.is_empty() {
+            stack.pop();
+        } else {
+            let (wall, next) = *rng.pick(&unvisited);
+            grid[wall] = '.';
+            grid[next] = '.';
+            stack.push(next);
+        }
+    }
+    grid
+}
+
+/// A program of the usual shape, `bst A; bxl k; cdv B; bxc; bxl k'; adv 3; out B; jnz 0`, with
+/// keys that let it print itself. Both answers follow from that shape: each pass prints
+/// `(A ^ k ^ (A >> (A % 8 ^ k)) ^ k') % 8` and drops A's low three bits.
+fn day17(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    shape::<Fixed>(params)?;
+    let output = |mut a: u64, (k1, k2): (u64, u64)| {
+        let mut output = vec![];
+        loop {
+            let b = (a % 8) ^ k1;
+            output.push(((b ^ (a >> b) ^ k2) % 8) as u8);
+            a >>= 3;
+            if a == 0 {
+                return output;
+            }
+        }
+    };
+    // The last digit printed comes from A's top three bits alone, so grow A from the top, three
+    // bits at a time, lowest first.
+    fn lowest_quine(program: &[u8], a: u64, left: usize, output: &dyn Fn(u64) -> Vec<u8>) -> Option<u64> {
+        if left == 0 {
+            return Some(a);
+        }
+        (0..8)
+            .map(|low| a << 3 | low)
+            .filter(|&next| next != 0 && output(next) == program[left - 1..])
+            .find_map(|next| lowest_quine(program, next, left - 1, output))
+    }
+    loop {
+        let keys = (rng.below(8) as u64, rng.below(8) as u64);
+        let program = [2, 4, 1, keys.0 as u8, 7, 5, 4, rng.below(8) as u8, 1, keys.1 as u8, 0, 3, 5, 5, 3, 0];
+        if let Some(quine) = lowest_quine(&program, 0, program.len(), &|a| output(a, keys)) {
+            let a = rng.between(1 << 45, (1 << 48) - 1) as u64;
+            let input = format!("Register A: {}\nRegister B: 0\nRegister C: 0\n\nProgram: {}\n", a, join(&program, ","));
+            return Ok(Generated::new(input).with_part1(join(&output(a, keys), ",")).with_part2(quine));
+        }
+    }
+}
+
+/// Bytes falling into day 18's 71 by 71 memory space. A column fills in over the first 1024
+/// bytes except for its top cell, while nothing lands on the top row or the right edge, so the
+/// exit stays reachable until that top cell falls some time later.
+fn day18(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    const SIDE: usize = 71;
+    const FALLEN: usize = 1024;
+    let Size(bytes) = shape::<Size<3450>>(params)?;
+    // the wall's top byte must come after the first 1024, and the rest must fit beside the path
+    let bytes = within("size", bytes, FALLEN + 1..=(SIDE - 1) * (SIDE - 2) + SIDE)?;
+    let column = rng.between(1, SIDE - 2);
+    let mut others: Vec<Point> = (1..SIDE)
+        .flat_map(|y| (0..SIDE - 1).map(move |x| Point::new(x, y)))
+        .filter(|point| point.x != column)
+        .collect();
+    rng.shuffle(&mut others);
+    others.truncate(bytes - SIDE);
+    let mut later = others.split_off(FALLEN - (SIDE - 1));
+    let mut fallen = others;
+    fallen.extend((1..SIDE).map(|y| Point::new(column, y)));
+    rng.shuffle(&mut fallen);
+    let blocking = Point::new(column, 0);
+    later.insert(rng.below(later.len() + 1), blocking);
+    let input = fallen.iter().chain(&later).map(|point| format!("{}\n", point)).collect();
+    Ok(Generated::new(input).with_part2(blocking))
+}
+
+/// Towels without any green stripes, and designs built from them, some with a green stripe
+/// slipped in so they cannot be made.
+fn day19(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    const STRIPES: [char; 4] = ['w', 'u', 'b', 'r'];
+    let designs = within("size", shape::<Size<400>>(params)?.0, 1..=usize::MAX)?;
+    let mut towels = HashSet::new();
+    while towels.len() < 440 {
+        towels.insert((0..rng.between(2, 8)).map(|_| *rng.pick(&STRIPES)).collect::<String>());
+    }
+    let mut towels: Vec<String> = towels.into_iter().collect();
+    towels.sort();
+    rng.shuffle(&mut towels);
+
+    let mut possible = 0;
+    let designs: Vec<String> = (0..designs)
+        .map(|_| {
+            let length = rng.between(20, 60);
+            let mut design = String::new();
+            while design.len() < length {
+                design.push_str(rng.pick::<String>(&towels));
+            }
+            if rng.chance(60) {
+                possible += 1;
+            } else {
+                design.insert(rng.below(design.len() + 1), 'g');
+            }
+            design
+        })
+        .collect();
+    let input = format!("{}\n \n{}\n", towels.join(", "), designs.join("\n"));
+    Ok(Generated::new(input).with_part1(possible))
+}
+
+/// A racetrack: the one route through a perfect maze, with the rest of the maze walled up.
+fn day20(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let Size(side) = shape::<Size<141>>(params)?;
+    let maze = maze(rng, within("size", side, 5..=usize::MAX)? | 1);
+    let side = maze.width();
+    let (start, end) = (Point::new(1, side - 2), Point::new(side - 2, 1));
+    let track = |&point: &Point| maze.neighbours(point).filter(|&next| maze[next] == '.').map(|next| (next, 1)).collect::<Vec<_>>();
+    let (_, route) = search::astar(start, track, |_| 0, |&point| point == end).expect("a maze joins every cell");
+    let mut grid = Grid::new(side, side, '#');
+    route.into_iter().for_each(|point| grid[point] = '.');
+    grid[start] = 'S';
+    grid[end] = 'E';
+    Ok(Generated::new(grid.to_string()))
+}
+
+/// Door codes of three digits.
+fn day21(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let codes = within("size", shape::<Size<5>>(params)?.0, 1..=usize::MAX)?;
+    Ok(Generated::new((0..codes).map(|_| format!("{:03}A\n", rng.below(1000))).collect()))
+}
+
+/// Buyers' initial secret numbers, with the answers from playing out each buyer's 2000 secrets.
+fn day22(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let buyers = within("size", shape::<Size<2000>>(params)?.0, 1..=usize::MAX)?;
+    let secrets: Vec<u64> = (0..buyers).map(|_| rng.between(1, (1 << 24) - 1) as u64).collect();
+    let evolve = |secret: u64| {
+        let secret = ((secret << 6) ^ secret) & 0xFF_FFFF;
+        let secret = ((secret >> 5) ^ secret) & 0xFF_FFFF;
+        ((secret << 11) ^ secret) & 0xFF_FFFF
+    };
+    // four changes of -9..=9 each, read as a number in base 19
+    const SEQUENCES: usize = 19 * 19 * 19 * 19;
+    let (mut sum, mut bananas, mut seen_by) = (0, vec![0; SEQUENCES], vec![usize::MAX; SEQUENCES]);
+    for (buyer, &secret) in secrets.iter().enumerate() {
+        let evolved: Vec<u64> = std::iter::successors(Some(secret), |&secret| Some(evolve(secret))).take(2001).collect();
+        sum += evolved[2000];
+        let prices: Vec<usize> = evolved.iter().map(|secret| (secret % 10) as usize).collect();
+        for window in prices.windows(5) {
+            let sequence = window.windows(2).fold(0, |sequence, pair| sequence * 19 + pair[1] + 9 - pair[0]);
+            // a buyer sells the first time the monkey sees the changes
+            if seen_by[sequence] != buyer {
+                seen_by[sequence] = buyer;
+                bananas[sequence] += window[4];
+            }
+        }
+    }
+    let input = secrets.iter().map(|secret| format!("{}\n", secret)).collect();
+    Ok(Generated::new(input).with_part1(sum).with_part2(bananas.into_iter().max().unwrap_or(0)))
+}
+
+/// A network with a clique planted in it. Everyone outside the clique has fewer than
+/// `clique - 1` links, so no other clique is as big and none can grow the planted one; as in
+/// the real puzzle, each member has at most one link leaving the clique.
+fn day23(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let Lan { computers, clique } = shape(params)?;
+    let computers = within("computers", computers, 3..=26 * 26)?;
+    let clique = within("clique", clique, 3..=computers)?;
+    let mut names: Vec<String> = ('a'..='z').flat_map(|a| ('a'..='z').map(move |b| format!("{}{}", a, b))).collect();
+    rng.shuffle(&mut names);
+    names.truncate(computers);
+
+    let mut links: HashSet<(usize, usize)> = (0..clique).flat_map(|a| (a + 1..clique).map(move |b| (a, b))).collect();
+    let mut degree: Vec<usize> = (0..computers).map(|computer| if computer < clique { clique - 1 } else { 0 }).collect();
+    for _ in 0..computers * clique {
+        let (a, b) = (rng.below(computers), rng.below(computers));
+        let full = |computer: usize| degree[computer] >= if computer < clique { clique } else { clique - 2 };
+        if a == b || (a < clique && b < clique) || full(a) || full(b) || !links.insert((a.min(b), a.max(b))) {
+            continue;
+        }
+        degree[a] += 1;
+        degree[b] += 1;
+    }
+    let mut links: Vec<(usize, usize)> = links.into_iter().collect();
+    links.sort();
+    rng.shuffle(&mut links);
+    let input = links
+        .iter()
+        .map(|&(a, b)| if rng.chance(50) { (a, b) } else { (b, a) })
+        .map(|(a, b)| format!("{}-{}\n", names[a], names[b]))
+        .collect();
+
+    let mut party = names[..clique].to_vec();
+    party.sort();
+    Ok(Generated::new(input).with_part2(party.join(",")))
+}
+
+/// A ripple-carry adder with `swaps` pairs of gate outputs exchanged, each pair within one bit
+/// and of a kind day 24 looks for. Part 1 is only known for a working adder.
+fn day24(rng: &mut Rng, params: &Params) -> Result<Generated, ParamError> {
+    let Adder { bits, swaps } = shape(params)?;
+    let bits = within("bits", bits, 2..=99)?;
+    let swaps = within("swaps", swaps, 0..=bits - 2)?;
+    let mut taken = HashSet::new();
+    let mut fresh = |rng: &mut Rng| loop {
+        // never x, y or z first, so no name looks like a register bit
+        let name: String = [rng.below(23), rng.below(26), rng.below(26)].iter().map(|&letter| (b'a' + letter as u8) as char).collect();
+        if taken.insert(name.clone()) {
+            return name;
+        }
+    };
+
+    // xi XOR yi -> ai, xi AND yi -> bi, ai XOR carry -> zi, ai AND carry -> ci, bi OR ci -> carry out
+    let mut gates = vec![("x00".to_string(), "XOR", "y00".to_string(), "z00".to_string())];
+    let mut carry = fresh(rng);
+    gates.push(("x00".to_string(), "AND", "y00".to_string(), carry.clone()));
+    let mut outputs = vec![];
+    for bit in 1..bits {
+        let (x, y, z) = (format!("x{:02}", bit), format!("y{:02}", bit), format!("z{:02}", bit));
+        let (a, b, c) = (fresh(rng), fresh(rng), fresh(rng));
+        let carry_out = if bit + 1 == bits { format!("z{:02}", bits) } else { fresh(rng) };
+        gates.extend([
+            (x.clone(), "XOR", y.clone(), a.clone()),
+            (x, "AND", y, b.clone()),
+            (a.clone(), "XOR", carry.clone(), z.clone()),
+            (a.clone(), "AND", carry, c.clone()),
+            (b.clone(), "OR", c.clone(), carry_out.clone()),
+        ]);
+        outputs.push([(a, b), (z.clone(), c), (z, carry_out.clone())]);
+        carry = carry_out;
+    }
+    // the last bit's carry out is the top z wire, which is not one to swap
+    outputs.pop();
+    rng.shuffle(&mut outputs);
+    let swapped: Vec<(String, String)> = outputs.into_iter().take(swaps).map(|kinds| rng.pick(&kinds).clone()).collect();
+    for gate in &mut gates {
+        if let Some((a, b)) = swapped.iter().find(|(a, b)| gate.3 == *a || gate.3 == *b) {
+            gate.3 = if gate.3 == *a { b.clone() } else { a.clone() };
+        }
+    }
+
+    let (x, y): (Vec<bool>, Vec<bool>) = (0..bits).map(|_| (rng.chance(50), rng.chance(50))).unzip();
+    let mut input = String::new();
+    for (register, values) in [('x', &x), ('y', &y)] {
+        for (bit, &value) in values.iter().enumerate() {
+            writeln!(input, "{}{:02}: {}", register, bit, u8::from(value)).unwrap();
+        }
+    }
+    input.push('\n');
+    rng.shuffle(&mut gates);
+    for (in1, gate_type, in2, output) in gates {
+        let (in1, in2) = if rng.chance(50) { (in1, in2) } else { (in2, in1) };
+        writeln!(input, "{} {} {} -> {}", in1, gate_type, in2, output).unwrap();
+    }
+
+    let mut names: Vec<String> = swapped.into_iter().flat_map(|(a, b)| [a, b]).collect();
+    names.sort();
+    let generated = Generated::new(input).with_part2(names.join(","));
+    if swaps > 0 {
+        return Ok(generated);
+    }
+    let value = |bits: &[bool]| {
+        let mut value = BigUint::default();
+        bits.iter().enumerate().filter(|(_, &set)| set).for_each(|(bit, _)| value.set_bit(bit as u64, true));
+        value
+    };
+    Ok(generated.with_part1(value(&x) + value(&y)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles;
+
+    /// Small enough for the solvers to get through quickly in a debug build.
+    const SMALL: [(u8, &[(&str, &str)]); 24] = [
+        (1, &[("size", "40")]),
+        (2, &[("size", "40")]),
+        (3, &[("size", "3")]),
+        (4, &[("size", "20")]),
+        (5, &[("size", "20")]),
+        (6, &[("size", "12")]),
+        (7, &[("size", "40")]),
+        (8, &[("size", "12")]),
+        (9, &[("size", "41")]),
+        (10, &[("size", "12")]),
+        (11, &[("size", "3")]),
+        (12, &[("size", "20")]),
+        (13, &[("size", "20")]),
+        (14, &[("size", "100")]),
+        (15, &[("size", "10")]),
+        (16, &[("size", "15")]),
+        (17, &[]),
+        (18, &[("size", "1040")]),
+        (19, &[("size", "20")]),
+        (20, &[("size", "15")]),
+        (21, &[("size", "5")]),
+        (22, &[("size", "10")]),
+        (23, &[("computers", "60"), ("clique", "6")]),
+        (24, &[("bits", "12"), ("swaps", "3")]),
+    ];
+
+    #[test]
+    fn rng_test() {
+        let mut rng = Rng::new(7);
+        let first: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+        let mut again = Rng::new(7);
+        assert_eq!(first, (0..4).map(|_| again.next_u64()).collect::<Vec<_>>());
+        assert_ne!(first, (0..4).map(|_| Rng::new(8).next_u64()).collect::<Vec<_>>());
+        assert!((0..1000).all(|_| (3..=5).contains(&rng.between(3, 5))));
+        let mut items: Vec<usize> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn solvers_agree_with_generated_answers_test() {
+        for (day, params) in SMALL {
+            let generate = generator(day).unwrap();
+            let entry = puzzles::find(day).unwrap();
+            for seed in 0..2 {
+                let generated = generate(&mut Rng::new(seed), &Params::new(params)).unwrap();
+                let (answers, _) = (entry.run)(&generated.input, None, &Params::default())
+                    .unwrap_or_else(|error| panic!("day {} seed {}: {}", day, seed, error));
+                for (expected, answer) in [(&generated.part1, &answers.part1), (&generated.part2, &answers.part2)] {
+                    if expected.is_some() {
+                        assert_eq!(answer, expected, "day {} seed {}", day, seed);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn planted_answers_test() {
+        let generate = |day: u8, params: &[(&str, &str)]| generator(day).unwrap()(&mut Rng::new(3), &Params::new(params)).unwrap();
+        let party = generate(23, &[("computers", "40"), ("clique", "5")]);
+        assert_eq!(party.part2.unwrap().split(',').count(), 5);
+        let adder = generate(24, &[("bits", "10"), ("swaps", "2")]);
+        assert_eq!((adder.part1, adder.part2.unwrap().split(',').count()), (None, 4));
+        assert!(generate(24, &[("bits", "10"), ("swaps", "0")]).part1.is_some());
+        assert_eq!(generate(9, &[("size", "11")]).input.trim().len(), 11);
+        assert_eq!(generate(18, &[("size", "1100")]).input.lines().count(), 1100);
+        // the sum of 2000 buyers' secrets needs more than 32 bits
+        let buyers = generate(22, &[]);
+        assert!(buyers.part1.unwrap().parse::<u64>().unwrap() > u32::MAX as u64);
+        assert!(buyers.part2.is_some());
+    }
+
+    #[test]
+    fn params_test() {
+        let generate = |day: u8, params: &[(&str, &str)]| generator(day).unwrap()(&mut Rng::new(0), &Params::new(params));
+        assert_eq!(
+            generate(1, &[("width", "3")]).unwrap_err().to_string(),
+            "unknown parameter `width`; this day takes size"
+        );
+        assert_eq!(
+            generate(17, &[("size", "3")]).unwrap_err().to_string(),
+            "unknown parameter `size`; this day takes none"
+        );
+        assert_eq!(
+            generate(18, &[("size", "1000")]).unwrap_err(),
+            ParamError::Invalid { key: "size".to_string(), value: "1000".to_string() }
+        );
+        assert!(generate(23, &[("computers", "10"), ("clique", "11")]).is_err());
+        assert!(generate(24, &[("bits", "4"), ("swaps", "3")]).is_err());
+        for day in [4, 6, 8, 10, 12, 22] {
+            assert_eq!(
+                generate(day, &[("size", "0")]).unwrap_err(),
+                ParamError::Invalid { key: "size".to_string(), value: "0".to_string() }
+            );
+        }
+        assert!(generator(25).is_none());
+    }
+}
@@ -0,0 +1,381 @@
+//! A rectangular grid of cells addressed by [`Point`], for the puzzles whose input is a map.
+//!
+//! ```text
+//! let grid = Grid::parse(input, |c| Some(c == '#'))?;
+//! let open = grid.neighbours(start).filter(|&p| !grid[p]).count();
+//! ```
+
+// A toolkit for every map day; no single day needs all of it.
+#![allow(dead_code)]
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use nom::error::ErrorKind;
+
+use crate::error::ParseError;
+
+/// A cell position; `x` grows to the east and `y` to the south.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub const fn new(x: usize, y: usize) -> Self {
+        Point { x, y }
+    }
+
+    /// The point `dx`, `dy` away, or `None` when that is west of or north of the origin.
+    pub fn offset(self, dx: isize, dy: isize) -> Option<Point> {
+        Some(Point::new(self.x.checked_add_signed(dx)?, self.y.checked_add_signed(dy)?))
+    }
+
+    /// The neighbouring point in `direction`, or `None` when that is off the top or left edge.
+    pub fn step(self, direction: Direction) -> Option<Point> {
+        let (dx, dy) = direction.delta();
+        self.offset(dx, dy)
+    }
+
+    pub fn manhattan(self, other: Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// One of the four compass directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Clockwise from north, so `ALL[d.index()] == d`.
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    /// The change in `x` and `y` for one step.
+    pub const fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::East => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+        }
+    }
+
+    /// 0 to 3, clockwise from north.
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub const fn turn_right(self) -> Self {
+        Self::ALL[(self.index() + 1) % 4]
+    }
+
+    pub const fn turn_left(self) -> Self {
+        Self::ALL[(self.index() + 3) % 4]
+    }
+
+    pub const fn opposite(self) -> Self {
+        Self::ALL[(self.index() + 2) % 4]
+    }
+
+    /// Read `^`, `>`, `v` or `<`.
+    pub const fn from_arrow(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Direction::North),
+            '>' => Some(Direction::East),
+            'v' => Some(Direction::South),
+            '<' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    pub const fn arrow(self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::East => '>',
+            Direction::South => 'v',
+            Direction::West => '<',
+        }
+    }
+}
+
+/// The eight cells around a cell, clockwise from north.
+const AROUND: [(isize, isize); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+/// A `width` by `height` grid, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// Build a grid from its rows, or `None` when they are empty or of different widths.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first()?.len();
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let height = rows.len();
+        Some(Grid { width, height, cells: rows.into_iter().flatten().collect() })
+    }
+
+    /// Read a grid with one line per row, mapping each character with `cell`.
+    ///
+    /// Fails on a character `cell` rejects, on rows of different widths, and on an empty input.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError> {
+        let mut lines: Vec<&str> = input.lines().collect();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        let Some(first) = lines.first() else {
+            return Err(ParseError::at(input, input, ErrorKind::Eof));
+        };
+        let width = first.chars().count();
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for line in &lines {
+            let mut count = 0;
+            for (i, c) in line.char_indices() {
+                if count == width {
+                    return Err(ParseError::at(input, &line[i..], ErrorKind::Eof));
+                }
+                cells.push(cell(c).ok_or_else(|| ParseError::at(input, &line[i..], ErrorKind::Char))?);
+                count += 1;
+            }
+            if count < width {
+                return Err(ParseError::at(input, &line[line.len()..], ErrorKind::Char));
+            }
+        }
+        Ok(Grid { width, height: lines.len(), cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.contains(point).then(|| &self.cells[point.y * self.width + point.x])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if self.contains(point) {
+            Some(&mut self.cells[point.y * self.width + point.x])
+        } else {
+            None
+        }
+    }
+
+    /// The neighbour of `point` in `direction`, if it is on the grid.
+    pub fn step(&self, point: Point, direction: Direction) -> Option<Point> {
+        point.step(direction).filter(|&next| self.contains(next))
+    }
+
+    /// The neighbour of `point` in `direction`, wrapping around at the edges.
+    pub fn wrapping_step(&self, point: Point, direction: Direction) -> Point {
+        let (dx, dy) = direction.delta();
+        self.wrapping_offset(point, dx, dy)
+    }
+
+    fn wrapping_offset(&self, point: Point, dx: isize, dy: isize) -> Point {
+        Point::new(
+            (point.x as isize + dx).rem_euclid(self.width as isize) as usize,
+            (point.y as isize + dy).rem_euclid(self.height as isize) as usize,
+        )
+    }
+
+    /// The up to four orthogonal neighbours of `point` on the grid, clockwise from north.
+    pub fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL.into_iter().filter_map(move |direction| self.step(point, direction))
+    }
+
+    /// The up to eight neighbours of `point` on the grid, diagonals included, clockwise from north.
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        AROUND
+            .into_iter()
+            .filter_map(move |(dx, dy)| point.offset(dx, dy))
+            .filter(|&next| self.contains(next))
+    }
+
+    /// The four orthogonal neighbours of `point`, wrapping around at the edges.
+    pub fn wrapping_neighbours(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL.into_iter().map(move |direction| self.wrapping_step(point, direction))
+    }
+
+    /// The eight neighbours of `point`, wrapping around at the edges.
+    pub fn wrapping_neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        AROUND.into_iter().map(move |(dx, dy)| self.wrapping_offset(point, dx, dy))
+    }
+
+    /// Row `y`, west to east.
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Column `x`, north to south.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    /// Every point, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Point::new(i % width, i / width))
+    }
+
+    /// Every cell with its point, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    /// The first point, row by row, whose cell matches.
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter().find(|(_, cell)| predicate(cell)).map(|(point, _)| point)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    /// Draw the grid back as text, one line per row, mapping each cell with `cell`.
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            text.extend(row.iter().map(&mut cell));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// The text of `input`, a grid as [`Grid::parse`] reads it, from the cell at `point` on, so a
+/// [`ParseError`] can point at that cell.
+pub fn text_at(input: &str, point: Point) -> &str {
+    let Some(line) = input.lines().nth(point.y) else {
+        return &input[input.len()..];
+    };
+    let offset = line.char_indices().nth(point.x).map_or(line.len(), |(i, _)| i);
+    &line[offset..]
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        assert!(self.contains(point), "{} is outside the {}x{} grid", point, self.width, self.height);
+        &self.cells[point.y * self.width + point.x]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        assert!(self.contains(point), "{} is outside the {}x{} grid", point, self.width, self.height);
+        &mut self.cells[point.y * self.width + point.x]
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(|&c| c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "#..\n.#.\n..@\n";
+
+    #[test]
+    fn parse_and_render_test() {
+        let grid = Grid::parse(MAP, Some).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid[Point::new(2, 2)], '@');
+        assert_eq!(grid.position(|&c| c == '@'), Some(Point::new(2, 2)));
+        assert_eq!(grid.to_string(), MAP);
+        assert_eq!(grid.map(|&c| c == '#').render(|&wall| if wall { '#' } else { '.' }), "#..\n.#.\n...\n");
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        let error = Grid::parse("#.\n#x\n", |c| (c != 'x').then_some(c)).unwrap_err();
+        assert_eq!((error.line, error.column, error.kind), (2, 2, ErrorKind::Char));
+        let error = Grid::parse("#.\n#\n", Some).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        let error = Grid::parse("#.\n#..\n", Some).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(Grid::parse("\n", Some).unwrap_err().kind, ErrorKind::Eof);
+    }
+
+    #[test]
+    fn text_at_test() {
+        assert_eq!(text_at(MAP, Point::new(2, 2)), "@");
+        assert_eq!(text_at(MAP, Point::new(1, 1)), "#.");
+        assert_eq!(text_at(MAP, Point::new(0, 3)), "");
+    }
+
+    #[test]
+    fn neighbours_test() {
+        let grid = Grid::parse(MAP, Some).unwrap();
+        let corner: Vec<Point> = grid.neighbours(Point::new(0, 0)).collect();
+        assert_eq!(corner, [Point::new(1, 0), Point::new(0, 1)]);
+        assert_eq!(grid.neighbours(Point::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8(Point::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 8);
+        let wrapped: Vec<Point> = grid.wrapping_neighbours(Point::new(0, 0)).collect();
+        assert_eq!(wrapped, [Point::new(0, 2), Point::new(1, 0), Point::new(0, 1), Point::new(2, 0)]);
+        assert_eq!(grid.wrapping_neighbours8(Point::new(0, 0)).count(), 8);
+        assert_eq!(grid.wrapping_step(Point::new(2, 1), Direction::East), Point::new(0, 1));
+    }
+
+    #[test]
+    fn rows_and_columns_test() {
+        let grid = Grid::parse(MAP, Some).unwrap();
+        assert_eq!(grid.row(1), ['.', '#', '.']);
+        assert_eq!(grid.column(2).collect::<String>(), "..@");
+        assert_eq!(grid.rows().count(), 3);
+        assert_eq!(Grid::from_rows(vec![vec!['#', '.', '.'], vec!['.', '#', '.'], vec!['.', '.', '@']]), Some(grid));
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3]]), None);
+        assert_eq!(Grid::<u8>::from_rows(vec![]), None);
+    }
+
+    #[test]
+    fn direction_test() {
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::East.opposite(), Direction::West);
+        assert_eq!(Direction::from_arrow('v'), Some(Direction::South));
+        assert_eq!(Point::new(0, 0).step(Direction::North), None);
+        assert_eq!(Point::new(1, 2).manhattan(Point::new(4, 0)), 5);
+    }
+}
@@ -0,0 +1,140 @@
+//! Writes a day's results as a single-line JSON object for `--format json`.
+//!
+//! ```json
+//! {"day":1,"part1":"11","part2":"31","check":{"part1":"PASS","part2":null},
+//!  "timings":{"parse_ns":5000,"part1_ns":20000,"part2_ns":null,"spans":[]},"error":null}
+//! ```
+//!
+//! Answers are always strings, since some days answer with text. Timings are whole nanoseconds.
+
+use std::fmt::Write;
+use std::time::Duration;
+
+use timing_util::Span;
+
+use crate::answers::Verdict;
+use crate::summary::Row;
+
+fn string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn optional(value: Option<String>) -> String {
+    value.unwrap_or_else(|| "null".to_string())
+}
+
+fn nanos(duration: Duration) -> String {
+    duration.as_nanos().to_string()
+}
+
+fn verdict(verdict: Option<Verdict>) -> String {
+    optional(verdict.map(|verdict| string(&verdict.to_string())))
+}
+
+fn span(span: &Span) -> String {
+    let children: Vec<String> = span.children.iter().map(self::span).collect();
+    format!(
+        "{{\"name\":{},\"ns\":{},\"children\":[{}]}}",
+        string(&span.name),
+        nanos(span.duration),
+        children.join(",")
+    )
+}
+
+/// Render one day as a JSON object.
+pub fn row(row: &Row) -> String {
+    let (part1, part2, timings, error) = match &row.result {
+        Ok((answers, timings)) => {
+            let spans: Vec<String> = timings.spans.iter().map(span).collect();
+            (
+                optional(answers.part1.as_deref().map(string)),
+                optional(answers.part2.as_deref().map(string)),
+                format!(
+                    "{{\"parse_ns\":{},\"part1_ns\":{},\"part2_ns\":{},\"spans\":[{}]}}",
+                    nanos(timings.parse),
+                    optional(timings.part1.map(nanos)),
+                    optional(timings.part2.map(nanos)),
+                    spans.join(",")
+                ),
+                "null".to_string(),
+            )
+        }
+        Err(error) => ("null".to_string(), "null".to_string(), "null".to_string(), string(error)),
+    };
+    format!(
+        "{{\"day\":{},\"part1\":{},\"part2\":{},\"check\":{{\"part1\":{},\"part2\":{}}},\"timings\":{},\"error\":{}}}",
+        row.day,
+        part1,
+        part2,
+        verdict(row.verdicts.part1),
+        verdict(row.verdicts.part2),
+        timings,
+        error
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::Verdicts;
+    use crate::solution::{Answers, Timings};
+
+    #[test]
+    fn string_escape_test() {
+        assert_eq!(string("co,de"), "\"co,de\"");
+        assert_eq!(string("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    }
+
+    #[test]
+    fn row_test() {
+        let row = Row {
+            day: 9,
+            result: Ok((
+                Answers { part1: Some("1928".into()), part2: None },
+                Timings {
+                    parse: Duration::from_nanos(1500),
+                    part1: Some(Duration::from_micros(20)),
+                    part2: None,
+                    spans: vec![Span {
+                        name: "part1".into(),
+                        duration: Duration::from_micros(19),
+                        children: vec![Span { name: "compact".into(), duration: Duration::from_nanos(860), children: vec![] }],
+                    }],
+                },
+            )),
+            verdicts: Verdicts { part1: Some(Verdict::Pass), part2: None },
+        };
+        assert_eq!(
+            super::row(&row),
+            concat!(
+                "{\"day\":9,\"part1\":\"1928\",\"part2\":null,\"check\":{\"part1\":\"PASS\",\"part2\":null},",
+                "\"timings\":{\"parse_ns\":1500,\"part1_ns\":20000,\"part2_ns\":null,",
+                "\"spans\":[{\"name\":\"part1\",\"ns\":19000,\"children\":[{\"name\":\"compact\",\"ns\":860,\"children\":[]}]}]},",
+                "\"error\":null}"
+            )
+        );
+    }
+
+    #[test]
+    fn error_row_test() {
+        let row = Row { day: 5, result: Err("could not read input".into()), verdicts: Verdicts::default() };
+        assert_eq!(
+            super::row(&row),
+            "{\"day\":5,\"part1\":null,\"part2\":null,\"check\":{\"part1\":null,\"part2\":null},\"timings\":null,\"error\":\"could not read input\"}"
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Sends the days' debug chatter to stderr, so stdout only carries answers.
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            // `aoc2024::puzzles::day17` reads better as `day17`.
+            let target = record.target().rsplit("::").next().unwrap_or_default();
+            eprintln!("[{} {}] {}", record.level(), target, record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+pub fn init(level: LevelFilter) {
+    // Only fails if a logger is already installed, in which case that one keeps working.
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(level);
+}
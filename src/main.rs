@@ -1,107 +1,202 @@
+// Days return their concrete answer types from `Solution::part1`/`part2`.
+#![allow(refining_impl_trait)]
 extern crate core;
 
-use std::time::Instant;
 use std::fs;
-use timing_util::measure_time;
+use std::io::Read;
+use std::process::ExitCode;
 
+use log::LevelFilter;
+
+use answers::Verdicts;
+use cli::{CliError, Command, Format, InputSource, Target};
+use examples::Example;
+use solution::{Answers, Day, Params, Part, RunError};
+use summary::{format_duration, Row};
+
+mod answers;
+#[cfg(test)]
+mod check;
+mod cli;
+mod error;
+mod examples;
+mod generate;
+mod grid;
+mod json;
+mod logging;
 mod puzzles;
-use crate::puzzles::*;
-fn main() {
-    let args = std::env::args().collect::<Vec<String>>();
-    match args.len() {
-        1 => panic!("Please provide a day number"),
-        _ => {
-            let day = args[1].as_str();
-            let test = args.get(2).map(|x| x.as_str()) == Some("test");
-            let input =
-                fs::read_to_string(format!("input/day{}.txt", day)).expect("Could not read file");
-            match day {
-                "1" => {
-                    if test {
-                        day1::test()
-                    } else {
-                        day1::solve(input)
-                    }
-                }
-                "2" => {
-                    if test {
-                        day2::test()
-                    } else {
-                        day2::solve(input)
+mod search;
+mod solution;
+mod summary;
+
+fn main() -> ExitCode {
+    match cli::parse_args(std::env::args().skip(1)).and_then(execute) {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            if let CliError::Usage(_) = error {
+                eprintln!("\n{}", cli::USAGE);
+            }
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
+
+fn execute(command: Command) -> Result<ExitCode, CliError> {
+    match command {
+        Command::Help => println!("{}", cli::USAGE),
+        Command::List => {
+            for entry in puzzles::DAYS {
+                let examples = match examples::names(entry.day) {
+                    Ok(names) if !names.is_empty() => format!(" (examples: {})", names.join(", ")),
+                    Ok(_) => String::new(),
+                    Err(error) => {
+                        eprintln!("day {}: {}", entry.day, error);
+                        String::new()
                     }
-                }
-                "3" => {
-                    if test {
-                        day3::test()
-                    } else {
-                        day3::solve(input)
+                };
+                let export = if entry.export.is_some() { " (export)" } else { "" };
+                println!("day {}{}{}", entry.day, examples, export);
+            }
+        }
+        Command::Export { day, format, input, output } => {
+            logging::init(LevelFilter::Warn);
+            let entry = puzzles::find(day).ok_or(CliError::UnknownDay(day))?;
+            let export = entry.export.ok_or(CliError::NoExport(day))?;
+            let rendered = export(&read_input(day, &input)?, format).map_err(|error| CliError::Parse(error.in_day(day)))?;
+            fs::write(&output, rendered)
+                .map_err(|source| CliError::Output { path: output.display().to_string(), source })?;
+        }
+        Command::Generate { day, seed, params, output } => {
+            let generate = generate::generator(day).ok_or(CliError::UnknownDay(day))?;
+            let generated = generate(&mut generate::Rng::new(seed), &params).map_err(|error| CliError::Param { day, error })?;
+            match output {
+                Some(path) => fs::write(&path, &generated.input)
+                    .map_err(|source| CliError::Output { path: path.display().to_string(), source })?,
+                None => print!("{}", generated.input),
+            }
+            for (name, answer) in [("part1", &generated.part1), ("part2", &generated.part2)] {
+                eprintln!("{}: {}", name, answer.as_deref().unwrap_or("unknown"));
+            }
+        }
+        Command::Run { target, part, input, params, format, verbose } => {
+            logging::init(if verbose { LevelFilter::Debug } else { LevelFilter::Warn });
+            let entries = match target {
+                Target::Day(day) => vec![puzzles::find(day).ok_or(CliError::UnknownDay(day))?],
+                Target::All => puzzles::DAYS.iter().collect(),
+            };
+            let rows: Vec<Row> = entries.iter().map(|entry| run_day(entry, &input, part, &params)).collect();
+            match (format, target) {
+                (Format::Json, _) => rows.iter().for_each(|row| println!("{}", json::row(row))),
+                (Format::Text, Target::All) => {
+                    for row in &rows {
+                        // The error names the day, through its input path or the parse error.
+                        if let Err(error) = &row.result {
+                            eprintln!("error: {}", error);
+                        }
                     }
+                    print!("{}", summary::render(&rows));
                 }
-                "4" => {
-                    day4::solve(input);
-                }
-                "5" => {
-                    day5::solve(input);
-                }
-                "6" => {
-                    day6::solve(input);
-                }
-                "7" => {
-                    day7::solve(input);
-                }
-                "8" => {
-                    day8::solve(input);
-                }
-                "9" => {
-                    day9::solve(input);
-                }
-                "10" => {
-                    day10::solve(input);
-                }
-                "11" => {
-                    day11::solve(input);
-                }
-                "12" => {
-                    println!("{:?}", day12::solve(&*input));
-                }
-                "13" => {
-                    println!("{:?}", day13::solve(input));
-                }
-                "14" => {
-                    println!("{:?}", measure_time!(day14::solve(input)));
-                }
-                "15" => {
-                    println!("{:?}", measure_time!(day15::solve(input)));
-                }
-                "16" => {
-                    println!("{:?}", measure_time!(day16::solve(input)));
-                }
-                "17" => {
-                    println!("{:?}", measure_time!(day17::solve(input)));
-                }
-                "18" => {
-                    println!("{:?}", measure_time!(day18::solve(input)));
-                }
-                "19" => {
-                    println!("{:?}", measure_time!(day19::solve(input)));
-                }
-                "20" => {
-                    println!("{:?}", measure_time!(day20::solve(input)));
-                }
-                "21" => {
-                    println!("{:?}", measure_time!(day21::solve(input)));
-                }
-                "22" => {
-                    println!("{:?}", measure_time!(day22::solve(input)));
-                }
-                "23" => {
-                    println!("{:?}", measure_time!(day23::solve(input)));
-                }
-                "24" => {
-                    println!("{:?}", measure_time!(day24::solve(input)));
-                }
-                _ => panic!("Day {} not found", day),
+                (Format::Text, Target::Day(_)) => print_day(&rows[0]),
+            }
+            if rows.iter().any(Row::failed) {
+                return Ok(ExitCode::FAILURE);
             }
         }
     }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Read the day's input, answer it and check the answers, unless `params` changed the puzzle.
+/// Only `input/day<N>.txt` is checked against `answers/day<N>.toml`; an example brings its own
+/// params, which `params` add to, and its own answers; any other input has nothing to check.
+fn run_day(entry: &Day, source: &InputSource, part: Option<Part>, params: &Params) -> Row {
+    let loaded = match source {
+        InputSource::Example(name) => load_example(entry.day, name).map(|example| {
+            let part = part.or(example.part());
+            let mut all = example.params;
+            all.0.extend(params.0.iter().cloned());
+            (example.input, part, all, Some(example.expected))
+        }),
+        _ => read_input(entry.day, source).map(|input| (input, part, params.clone(), None)),
+    };
+    let result = loaded
+        .and_then(|(input, part, all, expected)| {
+            let outcome = (entry.run)(&input, part, &all).map_err(|error| match error {
+                RunError::Parse(error) => CliError::Parse(error.in_day(entry.day)),
+                RunError::Param(error) => CliError::Param { day: entry.day, error },
+                error @ RunError::Solve { .. } => CliError::Solve { day: entry.day, error },
+            })?;
+            Ok((outcome, expected))
+        })
+        .map_err(|error| error.to_string());
+    let verdicts = match &result {
+        Ok(((answers, _), Some(expected))) if params.is_empty() => expected.check(answers),
+        Ok(((answers, _), None)) if params.is_empty() && *source == InputSource::Default => verify(entry.day, answers),
+        _ => Verdicts::default(),
+    };
+    Row { day: entry.day, result: result.map(|(outcome, _)| outcome), verdicts }
+}
+
+/// The day's example called `name`, or an error listing the ones it has.
+fn load_example(day: u8, name: &str) -> Result<Example, CliError> {
+    match examples::load(day, name).map_err(CliError::Example)? {
+        Some(example) => Ok(example),
+        None => Err(CliError::NoExample { day, name: name.to_string(), known: examples::names(day).unwrap_or_default() }),
+    }
+}
+
+fn print_day(row: &Row) {
+    let (answers, timings) = match &row.result {
+        Ok(result) => result,
+        Err(error) => return eprintln!("error: {}", error),
+    };
+    println!("{}", answers);
+    println!(
+        "parse: {}, solve: {}",
+        format_duration(timings.parse),
+        format_duration(timings.solve())
+    );
+    for span in &timings.spans {
+        print!("{}", span);
+    }
+    for (name, verdict) in [("part1", row.verdicts.part1), ("part2", row.verdicts.part2)] {
+        if let Some(verdict) = verdict {
+            println!("{}: {}", name, verdict);
+        }
+    }
+}
+
+/// Check the answers against `answers/day<N>.toml`; a broken answers file is reported and skipped.
+fn verify(day: u8, answers: &Answers) -> Verdicts {
+    match answers::load(day) {
+        Ok(Some(expected)) => expected.check(answers),
+        Ok(None) => Verdicts::default(),
+        Err(error) => {
+            eprintln!("day {}: {}", day, error);
+            Verdicts::default()
+        }
+    }
+}
+
+fn read_input(day: u8, source: &InputSource) -> Result<String, CliError> {
+    let (path, result) = match source {
+        InputSource::Default => {
+            let path = format!("input/day{}.txt", day);
+            let result = fs::read_to_string(&path);
+            (path, result)
+        }
+        InputSource::File(path) => (path.display().to_string(), fs::read_to_string(path)),
+        InputSource::Stdin => {
+            let mut input = String::new();
+            let result = std::io::stdin().read_to_string(&mut input).map(|_| input);
+            ("stdin".to_string(), result)
+        }
+        InputSource::Example(name) => {
+            let path = format!("examples/day{}/{}.txt", day, name);
+            let result = fs::read_to_string(&path);
+            (path, result)
+        }
+    };
+    result.map_err(|source| CliError::Input { path, source })
 }
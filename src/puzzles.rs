@@ -1,4 +1,5 @@
-// This file is auto-generated by build.rs
+// The day modules and the `DAYS` registry below are kept by hand: a new day adds its module here
+// and its entry to `DAYS`.
 pub mod day1;
 pub mod day10;
 pub mod day2;
@@ -7,9 +8,9 @@ pub mod day4;
 pub mod day5;
 pub mod day6;
 pub mod day7;
-mod day7_test_util;
 pub mod day8;
 pub mod day9;
+mod day9_disk;
 pub mod day11;
 pub mod day12;
 pub mod day13;
@@ -21,6 +22,44 @@ pub mod day15;
 pub mod day21;
 pub mod day22;
 pub mod day17;
+mod day17_vm;
 pub mod day23;
 pub mod day24;
+mod day24_export;
+mod day24_netlist;
 pub mod day19;
+
+use crate::solution::Day;
+
+/// Every solved day, in calendar order.
+pub const DAYS: &[Day] = &[
+    Day::new::<day1::Day1>(1),
+    Day::new::<day2::Day2>(2),
+    Day::new::<day3::Day3>(3),
+    Day::new::<day4::Day4>(4),
+    Day::new::<day5::Day5>(5),
+    Day::new::<day6::Day6>(6),
+    Day::new::<day7::Day7>(7),
+    Day::new::<day8::Day8>(8),
+    Day::new::<day9::Day9>(9),
+    Day::new::<day10::Day10>(10),
+    Day::new::<day11::Day11>(11),
+    Day::new::<day12::Day12>(12),
+    Day::new::<day13::Day13>(13),
+    Day::new::<day14::Day14>(14),
+    Day::new::<day15::Day15>(15),
+    Day::new::<day16::Day16>(16),
+    Day::new::<day17::Day17>(17),
+    Day::new::<day18::Day18>(18),
+    Day::new::<day19::Day19>(19),
+    Day::new::<day20::Day20>(20),
+    Day::new::<day21::Day21>(21),
+    Day::new::<day22::Day22>(22),
+    Day::new::<day23::Day23>(23),
+    Day::new::<day24::Day24>(24).with_export(day24::export),
+];
+
+/// Look up a registered day by its number.
+pub fn find(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
@@ -1,11 +1,14 @@
 use nom::{
     character::complete::{digit1, space1},
-    combinator::map_res,
+    combinator::{all_consuming, map_res},
     sequence::separated_pair,
     IResult,
 };
 use std::collections::HashMap;
 
+use crate::error::ParseError;
+use crate::solution::Solution;
+
 fn parse_line(input: &str) -> IResult<&str, (i32, i32)> {
     separated_pair(
         map_res(digit1, str::parse),
@@ -14,43 +17,43 @@ fn parse_line(input: &str) -> IResult<&str, (i32, i32)> {
     )(input)
 }
 
-pub(crate) fn test() {
-    let input = "3   4
-4   3
-2   5
-1   3
-3   9
-3   3";
-    solve(input.to_string()); // 11
-}
+pub(crate) struct Day1;
 
-pub(crate) fn solve(input: String) {
-    let mut left = Vec::new();
-    let mut right = Vec::new();
+impl Solution for Day1 {
+    type Input = (Vec<i32>, Vec<i32>);
 
-    for line in input.lines() {
-        let (_, (a, b)) = parse_line(line).expect("Failed to parse line");
-        left.push(a);
-        right.push(b);
-    }
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut left = Vec::new();
+        let mut right = Vec::new();
 
-    left.sort();
-    right.sort();
+        for line in input.lines() {
+            let (_, (a, b)) = all_consuming(parse_line)(line).map_err(|error| ParseError::from_nom(input, error))?;
+            left.push(a);
+            right.push(b);
+        }
 
-    let part1: i32 = sum_of_differences(&left, &right);
-    let part2: i32 = similarity_score(&left, &right);
+        left.sort();
+        right.sort();
+        Ok((left, right))
+    }
 
-    println!("part1: {}, part2: {}", part1, part2);
+    fn part1((left, right): &Self::Input) -> i32 {
+        sum_of_differences(left, right)
+    }
+
+    fn part2((left, right): &Self::Input) -> i32 {
+        similarity_score(left, right)
+    }
 }
 
-fn sum_of_differences(left: &Vec<i32>, right: &Vec<i32>) -> i32 {
+fn sum_of_differences(left: &[i32], right: &[i32]) -> i32 {
     left.iter()
         .zip(right.iter())
         .map(|(a, b)| (a - b).abs())
         .sum()
 }
 
-fn similarity_score(left: &Vec<i32>, right: &Vec<i32>) -> i32 {
+fn similarity_score(left: &[i32], right: &[i32]) -> i32 {
     let mut frequency_map = HashMap::new();
     for value in right {
         *frequency_map.entry(value).or_insert(0) += 1;
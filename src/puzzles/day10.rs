@@ -1,72 +1,35 @@
-use nom::{
-    character::complete::newline,
-    combinator::{map, map_res},
-    multi::separated_list1,
-    IResult,
-};
 use std::collections::{HashSet, VecDeque};
-use std::time::Instant;
-use timing_util::measure_time;
-
-/// Parse a single line of the map into a vector of integers.
-fn parse_line(input: &str) -> IResult<&str, Vec<u8>> {
-    map(
-        nom::multi::many1(map_res(
-            nom::character::complete::one_of("0123456789"),
-            |c| c.to_string().parse::<u8>(),
-        )),
-        |vec| vec,
-    )(input)
-}
-
-/// Parse the entire input into a 2D grid of integers.
-fn parse_input(input: &str) -> IResult<&str, Vec<Vec<u8>>> {
-    separated_list1(newline, parse_line)(input)
-}
 
-/// Get valid neighbors for a given position in the grid.
-fn get_neighbors(x: usize, y: usize, grid: &[Vec<u8>]) -> Vec<(usize, usize)> {
-    let mut neighbors = Vec::new();
-    let rows = grid.len();
-    let cols = grid[0].len();
+use crate::error::ParseError;
+use crate::grid::{Grid, Point};
+use crate::solution::Solution;
 
-    if x > 0 {
-        neighbors.push((x - 1, y));
-    }
-    if y > 0 {
-        neighbors.push((x, y - 1));
-    }
-    if x + 1 < rows {
-        neighbors.push((x + 1, y));
-    }
-    if y + 1 < cols {
-        neighbors.push((x, y + 1));
-    }
-
-    neighbors
+/// Parse the map into a grid of heights.
+fn parse_input(input: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse(input, |c| c.to_digit(10).map(|d| d as u8))
 }
 
-/// Calculate the score for a trailhead at position (x, y).
-fn calculate_score(x: usize, y: usize, grid: &[Vec<u8>]) -> usize {
+/// Calculate the score for a trailhead: the number of nines it can reach.
+fn calculate_score(start: Point, grid: &Grid<u8>) -> usize {
     let mut queue = VecDeque::new();
     let mut visited = HashSet::new();
     let mut reachable_nines = 0;
 
-    queue.push_back((x, y));
-    visited.insert((x, y));
+    queue.push_back(start);
+    visited.insert(start);
 
-    while let Some((cx, cy)) = queue.pop_front() {
-        for (nx, ny) in get_neighbors(cx, cy, grid) {
-            if visited.contains(&(nx, ny)) {
+    while let Some(current) = queue.pop_front() {
+        for next in grid.neighbours(current) {
+            if visited.contains(&next) {
                 continue;
             }
 
             // Ensure the trail is valid (height must increase by 1).
-            if grid[nx][ny] == grid[cx][cy] + 1 {
-                visited.insert((nx, ny));
-                queue.push_back((nx, ny));
+            if grid[next] == grid[current] + 1 {
+                visited.insert(next);
+                queue.push_back(next);
 
-                if grid[nx][ny] == 9 {
+                if grid[next] == 9 {
                     reachable_nines += 1;
                 }
             }
@@ -77,68 +40,62 @@ fn calculate_score(x: usize, y: usize, grid: &[Vec<u8>]) -> usize {
 }
 
 /// Sum the scores of all trailheads in the grid.
-fn part1(grid: &[Vec<u8>]) -> usize {
-    let mut total_score = 0;
-
-    for (x, row) in grid.iter().enumerate() {
-        for (y, &value) in row.iter().enumerate() {
-            if value == 0 {
-                total_score += calculate_score(x, y, grid);
-            }
-        }
-    }
-
-    total_score
+fn part1(grid: &Grid<u8>) -> usize {
+    grid.iter()
+        .filter(|(_, &height)| height == 0)
+        .map(|(point, _)| calculate_score(point, grid))
+        .sum()
 }
 
-fn calculate_rating(x: usize, y: usize, grid: &[Vec<u8>]) -> usize {
-    fn dfs(x: usize, y: usize, grid: &[Vec<u8>], visited: &mut HashSet<(usize, usize)>) -> usize {
+fn calculate_rating(start: Point, grid: &Grid<u8>) -> usize {
+    fn dfs(current: Point, grid: &Grid<u8>, visited: &mut HashSet<Point>) -> usize {
         // Base case: End the trail if we've reached height 9
-        if grid[x][y] == 9 {
+        if grid[current] == 9 {
             return 1;
         }
 
         let mut count = 0;
-        let current_height = grid[x][y];
-        visited.insert((x, y));
+        let current_height = grid[current];
+        visited.insert(current);
 
         // Explore all valid neighbors
-        for (nx, ny) in get_neighbors(x, y, grid) {
-            if !visited.contains(&(nx, ny)) && grid[nx][ny] == current_height + 1 {
-                count += dfs(nx, ny, grid, visited);
+        for next in grid.neighbours(current) {
+            if !visited.contains(&next) && grid[next] == current_height + 1 {
+                count += dfs(next, grid, visited);
             }
         }
 
-        visited.remove(&(x, y)); // Backtrack
+        visited.remove(&current); // Backtrack
         count
     }
 
     let mut visited = HashSet::new();
-    dfs(x, y, grid, &mut visited)
+    dfs(start, grid, &mut visited)
 }
 
-fn part2(grid: &[Vec<u8>]) -> usize {
-    let mut total_rating = 0;
+fn part2(grid: &Grid<u8>) -> usize {
+    grid.iter()
+        .filter(|(_, &height)| height == 0)
+        .map(|(point, _)| calculate_rating(point, grid))
+        .sum()
+}
 
-    for (x, row) in grid.iter().enumerate() {
-        for (y, &value) in row.iter().enumerate() {
-            if value == 0 {
-                total_rating += calculate_rating(x, y, grid);
-            }
-        }
-    }
+pub(crate) struct Day10;
 
-    total_rating
-}
+impl Solution for Day10 {
+    type Input = Grid<u8>;
 
-pub fn solve(input: String) {
-    let (_, grid) = measure_time!({ parse_input(&input).unwrap() });
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
 
-    let total_score = measure_time!({ part1(&grid) });
-    println!("Part 1: {}", total_score);
+    fn part1(grid: &Self::Input) -> usize {
+        part1(grid)
+    }
 
-    let total_rating = measure_time!({ part2(&grid) });
-    println!("Part 2: {}", total_rating);
+    fn part2(grid: &Self::Input) -> usize {
+        part2(grid)
+    }
 }
 
 #[cfg(test)]
@@ -157,7 +114,7 @@ mod tests {
 7655987
 8765555
 9875555";
-            let (_, grid) = parse_input(input).unwrap();
+            let grid = parse_input(input).unwrap();
             assert_eq!(part2(&grid), 13);
         }
 
@@ -172,7 +129,7 @@ mod tests {
 32019012
 01329801
 10456732";
-            let (_, grid) = parse_input(input).unwrap();
+            let grid = parse_input(input).unwrap();
             assert_eq!(part2(&grid), 81);
         }
     }
@@ -191,7 +148,7 @@ mod tests {
 32019012
 01329801
 10456732";
-            let (_, grid) = parse_input(input).unwrap();
+            let grid = parse_input(input).unwrap();
             assert_eq!(part1(&grid), 36);
         }
     }
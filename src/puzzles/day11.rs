@@ -1,10 +1,13 @@
 use nom::{
     character::complete::{digit1, space1},
+    combinator::map_res,
     multi::separated_list1,
     IResult,
 };
 use std::collections::HashMap;
-use std::time::Instant;
+
+use crate::error::{parse_with, ParseError};
+use crate::solution::{param_value, Config, Configured, ParamError, Params, Solution};
 
 // Parse input into a vector of integers
 fn parse_input(input: &str) -> IResult<&str, Vec<i64>> {
@@ -12,9 +15,7 @@ fn parse_input(input: &str) -> IResult<&str, Vec<i64>> {
 }
 
 fn parse_i64(input: &str) -> IResult<&str, i64> {
-    let (input, num_str) = digit1(input)?;
-    let val = num_str.parse::<i64>().unwrap();
-    Ok((input, val))
+    map_res(digit1, str::parse)(input)
 }
 
 // Apply transformation rules to a single number and update the counts
@@ -63,26 +64,55 @@ fn initialise_counts(stones: &[i64]) -> HashMap<i64, usize> {
     stone_counts
 }
 
-pub fn solve(input: String) {
-    let start = Instant::now();
-    let (_, stones) = parse_input(&input).unwrap();
-    let stone_counts = initialise_counts(&stones);
-    println!("Parsing took: {:?}", start.elapsed());
+/// How many times each part blinks.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Blinks {
+    part1: usize,
+    part2: usize,
+}
+
+impl Default for Blinks {
+    fn default() -> Self {
+        Blinks { part1: 25, part2: 75 }
+    }
+}
+
+impl Config for Blinks {
+    const KEYS: &'static [&'static str] = &["part1", "part2"];
 
-    let start = Instant::now();
-    let part_1 = simulate_blinks(&stone_counts, 25);
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "part1" => self.part1 = param_value(key, value)?,
+            "part2" => self.part2 = param_value(key, value)?,
+            _ => return Err(Self::unknown(key)),
+        }
+        Ok(())
+    }
+}
 
-    println!("Total unique stones after 25 blinks: {}", part_1.len());
-    println!("Total number of stones after 25 blinks: {}", part_1.values().sum::<usize>());
-    println!("Part 1 took: {:?}", start.elapsed());
+pub(crate) struct Day11;
 
-    let start = Instant::now();
-    let part_2 = simulate_blinks(&part_1, 5000);
+impl Solution for Day11 {
+    type Input = Configured<HashMap<i64, usize>, Blinks>;
 
-    println!("Total unique stones after 75 blinks: {}", part_2.len());
-    println!("Total number of stones after 75 blinks: {}", part_2.values().sum::<usize>());
-    println!("Part 2 took: {:?}", start.elapsed());
-    
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let stones = parse_with(input, parse_input)?;
+        Ok(Configured::new(initialise_counts(&stones)))
+    }
+
+    fn configure(input: &mut Self::Input, params: &Params) -> Result<(), ParamError> {
+        input.config.apply(params)
+    }
+
+    /// Total number of stones after 25 blinks, unless configured otherwise.
+    fn part1(Configured { input: stone_counts, config }: &Self::Input) -> usize {
+        simulate_blinks(stone_counts, config.part1).values().sum::<usize>()
+    }
+
+    /// Total number of stones after 75 blinks, unless configured otherwise.
+    fn part2(Configured { input: stone_counts, config }: &Self::Input) -> usize {
+        simulate_blinks(stone_counts, config.part2).values().sum::<usize>()
+    }
 }
 
 #[cfg(test)]
@@ -119,4 +149,11 @@ mod tests {
         let total_after_2_blinks: usize = counts_after_2_blinks.values().sum();
         assert_eq!(total_after_2_blinks, 4);
     }
+
+    #[test]
+    fn test_provided_end_to_end() {
+        let params = Params::new(&[("part1", "6"), ("part2", "25")]);
+        let answers = crate::solution::run_with::<Day11>("125 17", &params).unwrap();
+        assert_eq!((answers.part1.as_deref(), answers.part2.as_deref()), (Some("22"), Some("55312")));
+    }
 }
@@ -1,10 +1,18 @@
 use std::collections::HashSet;
+use log::debug;
+
+use crate::error::ParseError;
+use crate::grid::{Direction, Grid, Point};
+use crate::solution::Solution;
+
+// A boundary segment: (is_horizontal, rank, inner cell, outer cell)
+type Segment = (bool, (isize, isize), (isize, isize), (isize, isize));
 
 // Define a structure to hold the perimeter
 #[derive(Debug)]
 
-struct Perimeter {
-    boundary_segments: Vec<(bool, (isize, isize), (isize, isize), (isize, isize))>, // List of boundary segments
+pub(crate) struct Perimeter {
+    boundary_segments: Vec<Segment>, // List of boundary segments
 }
 
 impl Perimeter {
@@ -28,52 +36,43 @@ impl Perimeter {
 }
 
 // Parse the input into a grid of characters
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|line| line.chars().collect()).collect()
-}
-
-// Check if a cell is within the grid bounds
-fn is_in_bounds(x: usize, y: usize, grid: &[Vec<char>]) -> bool {
-    y < grid.len() && x < grid[0].len()
+fn parse_input(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse(input, Some)
 }
 
 // Flood-fill algorithm to calculate area and perimeter
 fn flood_fill(
-    x: usize,
-    y: usize,
-    grid: &[Vec<char>],
-    visited: &mut HashSet<(usize, usize)>,
+    start: Point,
+    grid: &Grid<char>,
+    visited: &mut HashSet<Point>,
     plant_type: char,
 ) -> (usize, Perimeter) {
-    let mut stack = vec![(x, y)];
+    let mut stack = vec![start];
     let mut area = 0;
     let mut perimeter = Perimeter::new();
-    let directions = [(0, 1), (1, 0), (0, -1), (-1, 0)]; // (dy, dx) Right, Down, Left, Up
+    let directions = [Direction::East, Direction::South, Direction::West, Direction::North];
 
-    while let Some((cx, cy)) = stack.pop() {
-        if !visited.insert((cx, cy)) {
+    while let Some(current) = stack.pop() {
+        if !visited.insert(current) {
             continue;
         }
 
         area += 1;
 
-        for &(dy, dx) in &directions {
-            let nx = cx as isize + dx;
-            let ny = cy as isize + dy;
-
-            if nx >= 0
-                && ny >= 0
-                && is_in_bounds(nx as usize, ny as usize, grid)
-                && grid[ny as usize][nx as usize] == plant_type
-            {
-                if !visited.contains(&(nx as usize, ny as usize)) {
-                    stack.push((nx as usize, ny as usize));
+        for direction in directions {
+            match grid.step(current, direction).filter(|&next| grid[next] == plant_type) {
+                Some(next) => {
+                    if !visited.contains(&next) {
+                        stack.push(next);
+                    }
+                }
+                None => {
+                    // Add a boundary segment for this direction; the outer cell may be off the grid
+                    let (dx, dy) = direction.delta();
+                    let inner = (current.x as isize, current.y as isize);
+                    let outer = (inner.0 + dx, inner.1 + dy);
+                    perimeter.add_segment(inner, outer);
                 }
-            } else {
-                // Add a boundary segment for this direction
-                let inner = (cx as isize, cy as isize);
-                let outer = (cx as isize + dx, cy as isize + dy);
-                perimeter.add_segment((inner.0, inner.1), (outer.0, outer.1));
             }
         }
     }
@@ -87,14 +86,14 @@ fn calculate_sides(perimeter: &Perimeter) -> usize {
     let debug = false;
     // Sort segments by direction and coordinates
     let mut sorted_segments = perimeter.boundary_segments.clone();
-    sorted_segments.sort_by(|a, b| a.cmp(b));
+    sorted_segments.sort();
     if debug {
-        println!("sorted: {:?}", sorted_segments);
+        debug!("sorted: {:?}", sorted_segments);
     }
     // Group and count continuous segments
 
 
-    let mut previous: std::option::Option<(bool, (isize, isize), (isize, isize), (isize, isize))> = None;
+    let mut previous: Option<Segment> = None;
     // the perimeters are described by the coordinates of the two cells either side of the perimeter line
     //    two continuous vertical edges will have
     //     startA.0 == startB.0 && startA.1 + 1 == startB.1
@@ -147,34 +146,41 @@ fn calculate_sides(perimeter: &Perimeter) -> usize {
     sides
 }
 
-// Main function to solve the problem for both parts
-pub(crate) fn solve(input: &str) -> (usize, usize) {
-    let grid = parse_input(input);
+// Flood-fill every region of the garden, returning each region's area and perimeter
+fn find_regions(grid: &Grid<char>) -> Vec<(usize, Perimeter)> {
     let mut visited = HashSet::new();
-    let mut total_cost_part1 = 0;
-    let mut total_cost_part2 = 0;
-
-    for y in 0..grid.len() {
-        for x in 0..grid[0].len() {
-            if !visited.contains(&(x, y)) {
-                let plant_type = grid[y][x];
-                let (area, perimeter) = flood_fill(x, y, &grid, &mut visited, plant_type);
-
-                // Calculate Part 1
-                total_cost_part1 += area * perimeter.boundary_segments.len();
-
-                // Calculate Part 2
-                let sides = calculate_sides(&perimeter);
-                // println!(
-                //     "plant type: {}, area: {}, sides: {}",
-                //     grid[y][x], area, sides
-                // );
-                total_cost_part2 += area * sides;
-            }
+    let mut regions = Vec::new();
+
+    for (point, &plant_type) in grid.iter() {
+        if !visited.contains(&point) {
+            regions.push(flood_fill(point, grid, &mut visited, plant_type));
         }
     }
-    // assert!(total_cost_part2 > 835484);
-    (total_cost_part1, total_cost_part2) //  part2 : 835484 your answer is too low
+    regions
+}
+
+pub(crate) struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<(usize, Perimeter)>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(find_regions(&parse_input(input)?))
+    }
+
+    fn part1(regions: &Self::Input) -> usize {
+        regions
+            .iter()
+            .map(|(area, perimeter)| area * perimeter.boundary_segments.len())
+            .sum()
+    }
+
+    fn part2(regions: &Self::Input) -> usize {
+        regions
+            .iter()
+            .map(|(area, perimeter)| area * calculate_sides(perimeter))
+            .sum() //  part2 : 835484 your answer is too low
+    }
 }
 
 // Test cases
@@ -185,19 +191,19 @@ mod tests {
         use super::*;
         #[test]
         fn test_aa() {
-            let grid = parse_input("aa");
-            assert_eq!(grid, vec![vec!['a', 'a']]);
-            assert_eq!(grid[0][0], 'a');
-            assert_eq!(grid[0][1], 'a');
+            let grid = parse_input("aa").unwrap();
+            assert_eq!(grid.row(0), ['a', 'a']);
+            assert_eq!(grid[Point::new(0, 0)], 'a');
+            assert_eq!(grid[Point::new(1, 0)], 'a');
         }
     }
     mod test_flood_fill {
         use super::*;
         #[test]
         fn test_flood_fill_single_cell() {
-            let input = vec![vec!['A']];
+            let input = parse_input("A").unwrap();
             let mut visited = HashSet::new();
-            let (area, perimeter) = flood_fill(0, 0, &input, &mut visited, 'A');
+            let (area, perimeter) = flood_fill(Point::new(0, 0), &input, &mut visited, 'A');
             let expected = [
                 (false, (0,1), (0, 0), (1, 0)),
                 (true, (0,1), (0, 0), (0, 1)),
@@ -212,9 +218,9 @@ mod tests {
 
         #[test]
         fn test_flood_fill_aa() {
-            let input = vec![vec!['A', 'A']];
+            let input = parse_input("AA").unwrap();
             let mut visited = HashSet::new();
-            let (area, actual) = flood_fill(0, 0, &input, &mut visited, 'A');
+            let (area, actual) = flood_fill(Point::new(0, 0), &input, &mut visited, 'A');
             //    -------------------------------------
             //    |  -1,-1  |  0,-1  |  1,-1  | 2,-1  |
             //    -------------------------------------
@@ -233,7 +239,7 @@ mod tests {
             ];
 
             let mut sorted_segments = actual.boundary_segments.clone();
-            sorted_segments.sort_by(|a, b| a.cmp(b));
+            sorted_segments.sort();
 
             assert_eq!(area, 2); // Two cells
             assert_eq!(sorted_segments, expected); // Four edges
@@ -252,9 +258,9 @@ mod tests {
             //    -------------------------------------
             //    |  -1,2  |  0,2    |  1,2   | 2,2   |
             //    -------------------------------------
-            let input = vec![vec!['A', 'A'], vec!['A', 'A']];
+            let input = parse_input("AA\nAA").unwrap();
             let mut visited = HashSet::new();
-            let (area, perimeter) = flood_fill(0, 0, &input, &mut visited, 'A');
+            let (area, perimeter) = flood_fill(Point::new(0, 0), &input, &mut visited, 'A');
             let expected = [
                 (false,  (0, -1 )   , (0, 0), (-1, 0)), //left top
                 (false,  (0, -1 )   , (0, 1), (-1, 1)), // left bottom
@@ -439,6 +445,11 @@ mod tests {
     }
     mod integration {
         use super::*;
+
+        fn solve(input: &str) -> (usize, usize) {
+            let regions = Day12::parse(input).unwrap();
+            (Day12::part1(&regions), Day12::part2(&regions))
+        }
         #[test]
         fn test_example_abcde() {
             let input = "AAAA\nBBCD\nBBCC\nEEEC";
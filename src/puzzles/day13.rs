@@ -6,10 +6,13 @@ use nom::{
     sequence::{preceded, separated_pair},
     IResult,
 };
-use std::time::Instant;
+use log::debug;
 
-#[derive(Debug)]
-struct Machine {
+use crate::error::{parse_with, ParseError};
+use crate::solution::Solution;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Machine {
     a_dx: i64,
     a_dy: i64,
     b_dx: i64,
@@ -119,14 +122,14 @@ fn solve_machine(machine: &Machine) -> Option<i64> {
         // If no solution, return None.
         // If infinite solutions, you'd need to check the target alignment.
         // For simplicity, just return None here as a common case:
-        println!("Determinant is zero : No solution for machine: {:?}", machine);
+        debug!("Determinant is zero : No solution for machine: {:?}", machine);
         return None;
     }
 
     // Check if the solution divides evenly
     if ((target_x * b_dy - target_y * b_dx) % d != 0) ||
         ((a_dx * target_y - a_dy * target_x) % d != 0) {
-        println!("solution divides evenly : No solution for machine: {:?}", machine);
+        debug!("solution divides evenly : No solution for machine: {:?}", machine);
         return None; // No integer solution.
     }
     //println!("Determinant: {}", d);
@@ -135,7 +138,7 @@ fn solve_machine(machine: &Machine) -> Option<i64> {
     let b_0 = (a_dx * target_y - a_dy * target_x) / d;
     
     if a_0 * a_dx + b_0 * b_dx == target_x && a_0 * a_dy + b_0 * b_dy == target_y && a_0 >= 0 && b_0 >= 0 {
-        println!("Particular solution accepted: {:?}, A: {}, B: {}, Cost: {}", machine, a_0, b_0, 3 * a_0 + b_0);
+        debug!("Particular solution accepted: {:?}, A: {}, B: {}, Cost: {}", machine, a_0, b_0, 3 * a_0 + b_0);
         // Compute cost
         let cost = 3 * a_0 + b_0;
         return Some(cost);
@@ -146,29 +149,61 @@ fn solve_machine(machine: &Machine) -> Option<i64> {
 fn part2(machines: &[Machine]) -> i64 {
     machines.iter().filter_map(solve_machine).sum()
 }
-// Parse and solve the puzzle
-pub fn solve(input: String) -> (i64, i64) {
-    let start = Instant::now();
-    let (_, mut machines) = parse_input(&input).unwrap();
-    let ans_part1 = part1(&machines); // Assuming part1 is already implemented
-    machines.iter_mut().for_each(update_prize_coordinates);
-    let parse_duration = start.elapsed();
+pub(crate) struct Day13;
 
-    let start_solve = Instant::now();
+impl Solution for Day13 {
+    type Input = Vec<Machine>;
 
-    let ans_part2 = part2(&machines);
-    let solve_duration = start_solve.elapsed();
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_with(input, parse_input)
+    }
 
-    println!("Part 1: {}", ans_part1);
-    println!("Part 2: {}", ans_part2);
-    println!("Parsing took: {} microseconds", parse_duration.as_micros());
-    println!("Solving took: {} microseconds", solve_duration.as_micros());
+    fn part1(machines: &Self::Input) -> i64 {
+        part1(machines)
+    }
 
-    (ans_part1, ans_part2)
+    fn part2(machines: &Self::Input) -> i64 {
+        let mut machines = machines.clone();
+        machines.iter_mut().for_each(update_prize_coordinates);
+        part2(&machines)
+    }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::check;
+    use crate::generate::Rng;
+
+    /// `[a_dx, a_dy, b_dx, b_dy]` and `[a presses, b presses, nudge]`: the prize is where the
+    /// presses take the claw, moved `nudge` along X. Shrinking the presses keeps it winnable.
+    type Case = ([i64; 4], [i64; 3]);
+
+    fn machine(&([a_dx, a_dy, b_dx, b_dy], [a, b, nudge]): &Case) -> Machine {
+        Machine { a_dx, a_dy, b_dx, b_dy, target_x: a * a_dx + b * b_dx + nudge, target_y: a * a_dy + b * b_dy }
+    }
+
+    fn random_machine(rng: &mut Rng) -> Case {
+        let buttons = [(); 4].map(|_| rng.between(1, 99) as i64);
+        let nudge = if rng.chance(30) { rng.between(1, 3) as i64 } else { 0 };
+        (buttons, [rng.between(0, 100) as i64, rng.between(0, 100) as i64, nudge])
+    }
+
+    /// Both buttons move the claw forward, not along the same line, and the prize is near enough
+    /// that no way of winning takes more than the 100 presses the brute force tries.
+    fn puzzle_shaped(case: &Case) -> bool {
+        let Machine { a_dx, a_dy, b_dx, b_dy, target_x, target_y } = machine(case);
+        [a_dx, a_dy, b_dx, b_dy].iter().all(|&delta| delta > 0)
+            && a_dx * b_dy != a_dy * b_dx
+            && (0..=100 * a_dx.min(b_dx)).contains(&target_x)
+            && (0..=100 * a_dy.min(b_dy)).contains(&target_y)
+    }
+
+    #[test]
+    fn solve_machine_agrees_with_brute_force_test() {
+        check::agree(2000, random_machine, puzzle_shaped, |case| min_cost_to_win(&machine(case)), |case| {
+            solve_machine(&machine(case))
+        });
+    }
 
     #[test]
     fn test_parse() {
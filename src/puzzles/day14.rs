@@ -1,10 +1,14 @@
 use std::str::FromStr;
 use nom::bytes::complete::tag;
-use nom::character::complete::digit1;
+use nom::character::complete::{digit1, multispace0};
 use nom::combinator::{map_res, opt, recognize};
 use nom::IResult;
 use nom::multi::separated_list1;
 use nom::sequence::{preceded, separated_pair};
+use log::debug;
+
+use crate::error::{parse_with, ParseError};
+use crate::solution::{param_value, Config, Configured, ParamError, Params, Solution};
 
 #[derive(Debug, PartialEq)]
 struct Point {
@@ -18,7 +22,7 @@ struct Velocity {
 }
 
 #[derive(Debug, PartialEq)]
-struct Robot {
+pub(crate) struct Robot {
     start: Point,
     velocity: Velocity
 }
@@ -52,8 +56,8 @@ fn parse_robot(input: &str) -> IResult<&str, Robot>  {
         tag("p="), separated_pair(parse_point, tag(" v="), parse_velocity))(input)?;
     Ok((i, Robot {start: result.0, velocity: result.1}))
 }
-fn parse(input: String) -> Vec<Robot> {
-    separated_list1(tag("\n"), parse_robot)(input.trim()).unwrap().1
+fn parse(input: &str) -> Result<Vec<Robot>, ParseError> {
+    parse_with(input, preceded(multispace0, separated_list1(tag("\n"), parse_robot)))
 }
 fn robot_move(robot: &Robot, width: usize, height: usize, time: usize) -> Point {
     Point {
@@ -77,11 +81,11 @@ fn get_quadrant(point: &Point, width: usize, height: usize) -> Option<usize> {
     }
 }
 
-fn print_grid(points: &Vec<Point>, width: usize, height: usize) {
+fn log_grid(points: &[Point], width: usize, height: usize) {
     let mut grid = vec!{0; height * width};
     points.iter().for_each(| Point {x,y}| grid[*y * width + *x] +=1);
     for y in 0..height {
-        println!(
+        debug!(
             "{}",
             grid[y * width..(y + 1) * width]
                 .iter()
@@ -97,21 +101,21 @@ fn print_grid(points: &Vec<Point>, width: usize, height: usize) {
         );
     }
 }
-fn get_safety_factor(input: &Vec<usize>) -> i32 {
-    let mut factors = vec!{0; 4};
+fn get_safety_factor(input: &[usize]) -> i32 {
+    let mut factors = [0; 4];
     for quadrant in input {
             factors[*quadrant] += 1;
     }
     factors.iter().product()
 }
 
-fn part_1(robots: &Vec<Robot>, width: usize, height: usize, time: usize) -> i32 {
+fn part_1(robots: &[Robot], width: usize, height: usize, time: usize) -> i32 {
     get_safety_factor(  &robots.iter().map(|robot| robot_move(robot, width, height, time))
         .filter_map(|point: Point |get_quadrant(&point,width,height))
-                            .collect())    
+                            .collect::<Vec<_>>())    
 }
 
-fn part_2(robots: &Vec<Robot>, width: usize, height: usize) -> usize {
+fn part_2(robots: &[Robot], width: usize, height: usize) -> usize {
     let mut time = 0;
     let mut min_tree = 243724976;
     let mut min_tree_time = 0;
@@ -120,31 +124,73 @@ fn part_2(robots: &Vec<Robot>, width: usize, height: usize) -> usize {
         let points = robots.iter().map(|robot| robot_move(robot, width, height, time));
         let tree = get_safety_factor(  &points
             .filter_map(|point: Point |get_quadrant(&point,width,height))
-            .collect());
+            .collect::<Vec<_>>());
 
         if tree < min_tree {
             min_tree = tree;
             min_tree_time = time;
-            println!("min found: {} {} {}", robots.len(), time, min_tree);
-            print_grid(&robots.iter().map(|robot| robot_move(robot, width, height, time)).collect(), width as usize, height as usize);
+            debug!("min found: {} {} {}", robots.len(), time, min_tree);
+            log_grid(&robots.iter().map(|robot| robot_move(robot, width, height, time)).collect::<Vec<_>>(), width, height);
         }
         time += 1;
         if time > 10000 {
-            println!("returning out of time {}", time );
+            debug!("returning out of time {}", time );
             return min_tree_time;
         }
     }
 }
 
-pub(crate) fn solve(input: String) -> (i32, i32) {
-    let robots = parse(input);
-    println!(" {}", &robots.len());
+/// The size of the bathroom and how long part 1 waits; the example's is 11 by 7.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Bathroom {
+    width: usize,
+    height: usize,
+    seconds: usize,
+}
 
-    let part_2_result = part_2(&robots, 101, 103);
-    print_grid(&robots.iter().map(|robot| robot_move(robot, 101, 103, part_2_result)).collect(), 101, 103);
-    println!("part 2: {}", part_2_result);
-    
-    (part_1(&robots, 101, 103, 100), part_2_result as i32)
+impl Default for Bathroom {
+    fn default() -> Self {
+        Bathroom { width: 101, height: 103, seconds: 100 }
+    }
+}
+
+impl Config for Bathroom {
+    const KEYS: &'static [&'static str] = &["width", "height", "seconds"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "width" => self.width = param_value(key, value)?,
+            "height" => self.height = param_value(key, value)?,
+            "seconds" => self.seconds = param_value(key, value)?,
+            _ => return Err(Self::unknown(key)),
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct Day14;
+
+impl Solution for Day14 {
+    type Input = Configured<Vec<Robot>, Bathroom>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input).map(Configured::new)
+    }
+
+    fn configure(input: &mut Self::Input, params: &Params) -> Result<(), ParamError> {
+        input.config.apply(params)
+    }
+
+    fn part1(Configured { input: robots, config }: &Self::Input) -> i32 {
+        part_1(robots, config.width, config.height, config.seconds)
+    }
+
+    fn part2(Configured { input: robots, config }: &Self::Input) -> usize {
+        let Bathroom { width, height, .. } = *config;
+        let part_2_result = part_2(robots, width, height);
+        log_grid(&robots.iter().map(|robot| robot_move(robot, width, height, part_2_result)).collect::<Vec<_>>(), width, height);
+        part_2_result
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -210,12 +256,45 @@ p=1,5 v=4,-4
                     Robot {start: Point {x: 0, y: 4}, velocity: Velocity {x: 3, y: -3}},
                     Robot {start: Point {x: 1, y: 5}, velocity: Velocity {x: 4, y: -4}}
                 ];
-                assert_eq!(parse(input.to_string()), expected);
+                assert_eq!(parse(input).unwrap(), expected);
+            }
+            #[test]
+            fn parse_malformed_test() {
+                let input = "\
+p=0,4 v=3,-3
+p=1,5 v=4,x
+";
+                let error = parse(input).unwrap_err();
+                assert_eq!((error.line, error.column, error.text.as_str()), (2, 1, "p=1,5 v=4,x"));
             }
         }
     }
     mod part_1_tests {
         use super::*;
+        use crate::solution::run_with;
+        #[test]
+        fn provided_test() {
+            let input = "\
+p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3
+";
+            let answers = run_with::<Day14>(input, &Params::new(&[("width", "11"), ("height", "7")])).unwrap();
+            assert_eq!(answers.part1.as_deref(), Some("12"));
+            let error = run_with::<Day14>(input, &Params::new(&[("depth", "3")])).unwrap_err();
+            assert_eq!(error.to_string(), "unknown parameter `depth`; this day takes width, height, seconds");
+            let error = run_with::<Day14>(input, &Params::new(&[("width", "wide")])).unwrap_err();
+            assert_eq!(error.to_string(), "`wide` is not a valid value for `width`");
+        }
         mod robot_move_tests {
             use super::*;
             fn get_robot() -> Robot {
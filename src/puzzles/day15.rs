@@ -1,16 +1,22 @@
 use nom::bytes::complete::tag;
-use nom::character::complete::{line_ending, one_of};
-use nom::combinator::map;
+use nom::character::complete::{anychar, line_ending, one_of};
+use nom::combinator::{map_opt, verify};
 use nom::multi::{many1, separated_list1};
 use nom::sequence::separated_pair;
 use nom::IResult;
 
+use crate::error::{parse_with, ParseError};
+use crate::grid::{Direction, Grid, Point};
+use crate::solution::Solution;
+
 #[derive(Debug, Clone, PartialEq, Default)]
 struct Path {
     distance: [Option<i32>; 4],
     location: Point,
     start: bool,
     bx: bool,
+    /// Which way the other half of a wide box lies.
+    partner: Option<Direction>,
     neighbours: [Option<Point>; 4],
 }
 impl Path {
@@ -20,6 +26,7 @@ impl Path {
             location,
             start: false,
             bx: false,
+            partner: None,
             neighbours: [None, None, None, None],
         }
     }
@@ -29,6 +36,7 @@ impl Path {
             location,
             start: true,
             bx: false,
+            partner: None,
             neighbours: [None, None, None, None],
         }
     }
@@ -38,128 +46,74 @@ impl Path {
             location,
             start: false,
             bx: true,
+            partner: None,
             neighbours: [None, None, None, None],
         }
     }
-}
-#[derive(Debug, Clone, PartialEq, Default, Eq, Hash)]
-struct Point {
-    x: usize,
-    y: usize,
-}
-impl Point {
-    fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
-    }
-}
-#[derive(Debug, PartialEq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-impl Direction {
-    fn delta(&self) -> (i32, i32) {
-        match self {
-            Direction::Up => (0, -1),
-            Direction::Down => (0, 1),
-            Direction::Left => (-1, 0),
-            Direction::Right => (1, 0),
-        }
-    }
-
-    fn neighbour(&self) -> usize {
-        match self {
-            Direction::Up => 0,    // N
-            Direction::Right => 1, // E
-            Direction::Down => 2,  // S
-            Direction::Left => 3,  // W
+    fn wide_bx(location: Point, partner: Direction) -> Self {
+        Self {
+            partner: Some(partner),
+            ..Self::bx(location)
         }
     }
 }
 fn parse_arrows(input: &str) -> IResult<&str, Vec<Vec<Direction>>> {
-    separated_list1(
-        line_ending,
-        many1(map(one_of("<>^v"), |c| match c {
-            '<' => Direction::Left,
-            '>' => Direction::Right,
-            '^' => Direction::Up,
-            'v' => Direction::Down,
-            _ => unreachable!(),
-        })),
-    )(input)
+    separated_list1(line_ending, many1(map_opt(anychar, Direction::from_arrow)))(input)
 }
-fn parse_map(input: &str) -> IResult<&str, Vec<Vec<char>>> {
-    separated_list1(line_ending, many1(one_of("#.@O")))(input)
+/// The warehouse, which has just the one robot in it.
+fn parse_map(input: &str) -> IResult<&str, Grid<char>> {
+    let one_robot = |grid: &Grid<char>| grid.iter().filter(|&(_, &c)| c == '@').count() == 1;
+    verify(map_opt(separated_list1(line_ending, many1(one_of("#.@O"))), Grid::from_rows), one_robot)(input)
 }
-fn parse_input(input: &str) -> IResult<&str, (Vec<Vec<char>>, Vec<Direction>)> {
+fn parse_input(input: &str) -> IResult<&str, (Grid<char>, Vec<Direction>)> {
     let (input, (grid, moves)) = separated_pair(parse_map, tag("\n\n"), parse_arrows)(input)?;
     let (input, _) = line_ending(input)?;
     Ok((input, (grid, moves.into_iter().flatten().collect())))
 }
-fn map_input(input: Vec<Vec<char>>) -> (Vec<Vec<Option<Path>>>, Point) {
-    let mut paths = Vec::new();
+fn map_input(input: &Grid<char>) -> (Grid<Option<Path>>, Point) {
+    let mut paths = Grid::new(input.width(), input.height(), None);
     let mut start: Point = Default::default();
-    for (y, row) in input.iter().enumerate() {
-        let mut path_row = Vec::new();
-        for (x, cell) in row.iter().enumerate() {
-            let path = match cell {
-                '#' => None,
-                '.' => Some(Path::new(Point::new(x, y))),
-                '@' => Some(Path::start(Point::new(x, y))),
-                'O' => Some(Path::bx(Point::new(x, y))),
-                _ => panic!("Invalid cell"),
-            };
-            if path.is_some() && path.as_ref().unwrap().start {
-                start = path.as_ref().unwrap().location.clone();
-            }
-            path_row.push(path);
+    for (point, cell) in input.iter() {
+        let path = match cell {
+            '#' => None,
+            '.' => Some(Path::new(point)),
+            '@' => Some(Path::start(point)),
+            'O' => Some(Path::bx(point)),
+            '[' => Some(Path::wide_bx(point, Direction::East)),
+            ']' => Some(Path::wide_bx(point, Direction::West)),
+            _ => unreachable!("parse_map only accepts #.@O and widen adds []"),
+        };
+        if let Some(path) = path.as_ref().filter(|path| path.start) {
+            start = path.location;
         }
-        paths.push(path_row);
+        paths[point] = path;
     }
     (paths, start)
 }
-fn populate_neighbours(maze: &mut Vec<Vec<Option<Path>>>) {
-    let neighbours: Vec<(isize, isize)> = vec![(0, -1), (1, 0), (0, 1), (-1, 0)]; // N,E,S,W
-    let maze_copy = maze.clone();
-    for (y, row) in maze.iter_mut().enumerate() {
-        for (x, cell) in row.iter_mut().enumerate() {
-            if let Some(cell) = cell {
-                let neighbour_cells = neighbours
-                    .iter()
-                    .map(|(dx, dy)| {
-                        &maze_copy[(y as isize + dy) as usize][(x as isize + dx) as usize]
-                    })
-                    .map(|cell| cell.as_ref().map(|c| c.location.clone()))
-                    .collect::<Vec<Option<Point>>>();
-                cell.neighbours = neighbour_cells.try_into().unwrap();
-            }
+fn populate_neighbours(maze: &mut Grid<Option<Path>>) {
+    let points: Vec<Point> = maze.points().collect();
+    for point in points {
+        let neighbours = Direction::ALL.map(|direction| maze.step(point, direction).filter(|&next| maze[next].is_some())); // N,E,S,W
+        if let Some(cell) = &mut maze[point] {
+            cell.neighbours = neighbours;
         }
     }
 }
-fn part_1(input: &String) -> usize {
-    let (_, (grid, moves)) = parse_input(&input).unwrap();
+fn part_1(grid: &Grid<char>, moves: &[Direction]) -> usize {
     let (mut paths, start) = map_input(grid);
     populate_neighbours(&mut paths);
 
     let mut current = start;
     for move_ in moves {
         let (target, next, pushing) = {
-            let mut position = current.clone();
-            let mut path = paths[position.y][position.x].as_ref().unwrap();
+            let mut position = current;
+            let mut path = paths[position].as_ref().unwrap();
             let mut pushing = false;
-            let next = {
-                if path.neighbours[move_.neighbour()].is_some() {
-                    Some(path.neighbours[move_.neighbour()].as_ref().unwrap().clone())
-                } else {
-                    None
-                }
-            };
-            while next.is_some() && path.neighbours[move_.neighbour()].is_some() && (path.bx || position == current) {
+            let next = path.neighbours[move_.index()];
+            while next.is_some() && path.neighbours[move_.index()].is_some() && (path.bx || position == current) {
                 // while we can move in the direction and we are on a box
-                position = path.neighbours[move_.neighbour()].as_ref().unwrap().clone();
-                path = paths[position.y][position.x].as_ref().unwrap();
+                position = path.neighbours[move_.index()].unwrap();
+                path = paths[position].as_ref().unwrap();
                 pushing = pushing || path.bx;
             }
 
@@ -175,28 +129,110 @@ fn part_1(input: &String) -> usize {
         }
         current = next.unwrap();
         if pushing {
-            paths[current.y][current.x].as_mut().unwrap().bx = false;
-            let Point { x, y } = target.unwrap();
-            paths[y][x].as_mut().unwrap().bx = true;
+            paths[current].as_mut().unwrap().bx = false;
+            paths[target.unwrap()].as_mut().unwrap().bx = true;
         }
     }
     let mut sum = 0;
-    for row in paths.iter() {
-        for cell in row.iter().flatten() {
-            if cell.bx {
-                let Point { x, y } = cell.location;
-                sum += y * 100 + x;
-            }
+    for (_, cell) in paths.iter() {
+        if let Some(cell) = cell.as_ref().filter(|cell| cell.bx) {
+            let Point { x, y } = cell.location;
+            sum += y * 100 + x;
         }
     }
     sum
 }
 
-pub(crate) fn solve(input: String) -> (i32, i32) {
-    (part_1(&input) as i32, 0)
+/// Double the warehouse's width: walls and floor double up, the robot gains floor to its east and boxes become `[]`.
+fn widen(grid: &Grid<char>) -> Grid<char> {
+    let rows = grid
+        .rows()
+        .map(|row| {
+            row.iter()
+                .flat_map(|cell| match cell {
+                    '#' => ['#', '#'],
+                    'O' => ['[', ']'],
+                    '.' => ['.', '.'],
+                    '@' => ['@', '.'],
+                    _ => unreachable!("parse_map only accepts #.@O"),
+                })
+                .collect()
+        })
+        .collect();
+    Grid::from_rows(rows).unwrap()
+}
+/// Move the robot one step, pushing every box in the way, unless something would hit a wall.
+/// Returns where the robot ends up.
+fn push(paths: &mut Grid<Option<Path>>, robot: Point, move_: Direction) -> Point {
+    // every box cell that moves, found by following the push out from the robot
+    let mut moving: Vec<Point> = Vec::new();
+    let mut frontier = vec![robot];
+    while let Some(position) = frontier.pop() {
+        let Some(next) = paths[position].as_ref().unwrap().neighbours[move_.index()] else {
+            return robot;
+        };
+        let path = paths[next].as_ref().unwrap();
+        if !path.bx || moving.contains(&next) {
+            continue;
+        }
+        moving.push(next);
+        frontier.push(next);
+        // a wide box moves as one, so its other half pushes too
+        if let Some(other) = path.partner.and_then(|partner| next.step(partner)) {
+            if !moving.contains(&other) {
+                moving.push(other);
+                frontier.push(other);
+            }
+        }
+    }
+    let boxes: Vec<(Point, Option<Direction>)> = moving
+        .iter()
+        .map(|&position| (position, paths[position].as_ref().unwrap().partner))
+        .collect();
+    for &(position, _) in &boxes {
+        let path = paths[position].as_mut().unwrap();
+        path.bx = false;
+        path.partner = None;
+    }
+    for (position, partner) in boxes {
+        let target = position.step(move_).unwrap();
+        let path = paths[target].as_mut().unwrap();
+        path.bx = true;
+        path.partner = partner;
+    }
+    robot.step(move_).unwrap()
+}
+fn part_2(grid: &Grid<char>, moves: &[Direction]) -> usize {
+    let (mut paths, start) = map_input(&widen(grid));
+    populate_neighbours(&mut paths);
+
+    moves.iter().fold(start, |robot, &move_| push(&mut paths, robot, move_));
+    paths
+        .iter()
+        .filter_map(|(_, cell)| cell.as_ref())
+        // a wide box is measured from its left edge
+        .filter(|cell| cell.bx && cell.partner != Some(Direction::West))
+        .map(|cell| cell.location.y * 100 + cell.location.x)
+        .sum()
 }
 
+pub(crate) struct Day15;
 
+impl Solution for Day15 {
+    type Input = (Grid<char>, Vec<Direction>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_with(input, parse_input)
+    }
+
+    fn part1((grid, moves): &Self::Input) -> usize {
+        part_1(grid, moves)
+    }
+
+    fn part2((grid, moves): &Self::Input) -> usize {
+        part_2(grid, moves)
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -214,7 +250,7 @@ mod tests {
             assert_eq!(remaining, "");
             assert_eq!(
                 grid,
-                vec![
+                Grid::from_rows(vec![
                     vec!['#', '#', '#', '#', '#', '#', '#', '#'],
                     vec!['#', '.', '.', 'O', '.', 'O', '.', '#'],
                     vec!['#', '#', '@', '.', 'O', '.', '.', '#'],
@@ -223,70 +259,104 @@ mod tests {
                     vec!['#', '.', '.', '.', 'O', '.', '.', '#'],
                     vec!['#', '.', '.', '.', '.', '.', '.', '#'],
                     vec!['#', '#', '#', '#', '#', '#', '#', '#'],
-                ]
+                ])
+                .unwrap()
             );
             assert_eq!(
                 moves,
                 vec![
-                    Direction::Left,
-                    Direction::Up,
-                    Direction::Up,
-                    Direction::Right,
-                    Direction::Right,
-                    Direction::Right,
-                    Direction::Down,
-                    Direction::Down,
-                    Direction::Left,
-                    Direction::Down,
-                    Direction::Right,
-                    Direction::Right,
-                    Direction::Down,
-                    Direction::Left,
-                    Direction::Left
+                    Direction::West,
+                    Direction::North,
+                    Direction::North,
+                    Direction::East,
+                    Direction::East,
+                    Direction::East,
+                    Direction::South,
+                    Direction::South,
+                    Direction::West,
+                    Direction::South,
+                    Direction::East,
+                    Direction::East,
+                    Direction::South,
+                    Direction::West,
+                    Direction::West
                 ]
             );
         }
-    }
-    fn get_smaller_input() -> String {
-        "\
-########
-#..O.O.#
-##@.O..#
-#...O..#
-#.#.O..#
-#...O..#
-#......#
-########
 
-<^^>>>vv<v>>
-v<<
+        #[test]
+        fn widen_test() {
+            let (_, (grid, _)) = parse_input(&get_wide_input()).unwrap();
+            assert_eq!(
+                widen(&grid).to_string(),
+                "\
+##############
+##......##..##
+##..........##
+##....[][]@.##
+##....[]....##
+##..........##
+##############
 "
-        .to_string()
+            );
+        }
+
+        #[test]
+        fn parse_robot_test() {
+            let error = Day15::parse("####\n#.O#\n####\n\n<\n").unwrap_err();
+            assert_eq!((error.line, error.column, error.kind), (1, 1, nom::error::ErrorKind::Verify));
+            assert!(Day15::parse("####\n#@@#\n####\n\n<\n").is_err());
+            assert!(Day15::parse("####\n#@O#\n####\n\n<\n").is_ok());
+        }
     }
-    fn get_input() -> String {
-        "\
-##########
-#..O..O.O#
-#......O.#
-#.OO..O.O#
-#..O@..O.#
-#O#..O...#
-#O..O..O.#
-#.OO.O.OO#
-#....O...#
-##########
+    mod part_1_tests {
+        use super::*;
 
-<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
-vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
-><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
-<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
-^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
-^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
->^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
-<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
-^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
-v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
-"
-        .to_string()
+        #[test]
+        fn provided_smaller() {
+            let (_, (grid, moves)) = parse_input(&get_smaller_input()).unwrap();
+            assert_eq!(part_1(&grid, &moves), 2028);
+        }
+
+        #[test]
+        fn provided() {
+            let (_, (grid, moves)) = parse_input(&get_input()).unwrap();
+            assert_eq!(part_1(&grid, &moves), 10092);
+        }
+    }
+    mod part_2_tests {
+        use super::*;
+
+        #[test]
+        fn provided_wide() {
+            let (_, (grid, moves)) = parse_input(&get_wide_input()).unwrap();
+            assert_eq!(part_2(&grid, &moves), 105 + 207 + 306);
+        }
+
+        #[test]
+        fn provided() {
+            let (_, (grid, moves)) = parse_input(&get_input()).unwrap();
+            assert_eq!(part_2(&grid, &moves), 9021);
+        }
+
+        #[test]
+        fn push_blocked_by_wall_test() {
+            // the left-hand box is under a wall, so pushing up into its right half moves nothing
+            let grid = Grid::parse("######\n#.#..#\n#.OO.#\n#..@.#\n######\n", Some).unwrap();
+            let (mut paths, start) = map_input(&widen(&grid));
+            populate_neighbours(&mut paths);
+            let robot = push(&mut paths, start, Direction::West);
+            let robot = push(&mut paths, robot, Direction::North);
+            assert_eq!(robot, start.step(Direction::West).unwrap());
+        }
+    }
+    fn get_wide_input() -> String {
+        include_str!("../../examples/day15/wide.txt").to_string()
+    }
+    fn get_smaller_input() -> String {
+        include_str!("../../examples/day15/small.txt").to_string()
+    }
+    fn get_input() -> String {
+        include_str!("../../examples/day15/large.txt").to_string()
     }
 }
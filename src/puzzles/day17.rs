@@ -3,12 +3,18 @@ use day14::parse_unsigned;
 use nom::{
     bytes::complete::tag,
     character::complete::{line_ending, space0},
+    combinator::verify,
     multi::separated_list1,
     sequence::preceded,
     IResult,
 };
-use std::time::{Duration, Instant};
-use timing_util::measure_time;
+use std::fmt;
+use std::time::Duration;
+use log::{debug, log_enabled, Level};
+
+use crate::error::ParseError;
+use crate::puzzles::day17_vm::{compile, disassemble, Combo, Instruction, Register, Vm, VmError};
+use crate::solution::Solution;
 
 /// Parse one line of the form: `Register X: 1234`
 fn parse_register_line<'a>(input: &'a str, reg_name: &str) -> IResult<&'a str, u64> {
@@ -34,9 +40,13 @@ fn parse_program_line(input: &str) -> IResult<&str, Vec<u8>> {
     let (input, _) = tag("\nProgram: ")(input)?;
 
     // separated_list1 will parse a list of items separated by a comma
-    let (input, nums) = separated_list1(
-        tag(","),                         // delimiter
-        preceded(space0, parse_unsigned), // each integer, potentially preceded by optional spaces
+    let (input, nums) = verify(
+        separated_list1(
+            tag(","),                                                 // delimiter
+            verify(preceded(space0, parse_unsigned), |&n| n < 8), // each 3-bit number, potentially preceded by optional spaces
+        ),
+        // every instruction must decode, e.g. no reserved combo operand 7
+        |nums: &[usize]| disassemble(&nums.iter().map(|&x| x as u8).collect::<Vec<u8>>()).is_ok(),
     )(input)?;
 
     Ok((input, nums.iter().map(|&x| x as u8).collect()))
@@ -47,194 +57,22 @@ fn parse_program_line(input: &str) -> IResult<&str, Vec<u8>> {
 // -----------------------------------------------------------
 
 #[derive(Debug, PartialEq, Clone)]
-struct Register {
-    a: u64,
-    b: u64,
-    c: u64,
-}
-
-#[derive(Debug, PartialEq, Clone)]
-struct Computer {
+pub(crate) struct Computer {
     store: Register,
     program: Vec<u8>,
 }
 
-use Instruction::*;
-enum Instruction {
-    Adv(u8),
-    Bxl(u8),
-    Bst(u8),
-    Jnz(u8),
-    Bxc(()),
-    Out(u8),
-    Bdv(u8),
-    Cdv(u8),
-}
-type InstrFn = Box<dyn Fn(&Register, usize) -> (Register, usize, u8)>;
-
-impl Instruction {
-    #[inline(always)]
-    fn new(instruction: u8, operand: u8) -> Self {
-        match instruction {
-            0 => Adv(operand),
-            1 => Bxl(operand),
-            2 => Bst(operand),
-            3 => Jnz(operand),
-            4 => Bxc(()),
-            5 => Out(operand),
-            6 => Bdv(operand),
-            7 => Cdv(operand),
-            _ => panic!("Unknown instruction: {}", instruction),
-        }
-    }
-
-    fn get_combo(operand: u8, register: &Register) -> u64 {
-        match operand {
-            0 => 0,
-            1 => 1,
-            2 => 2,
-            3 => 3,
-            4 => register.a,
-            5 => register.b,
-            6 => register.c,
-            7 => panic!("Invalid operand: {}", operand),
-            _ => panic!("Unknown instruction: {}", operand),
-        }
-    }
-    #[inline(always)]
-    fn act(&self) -> InstrFn {
-        match self {
-            Adv(operand) => {
-                let op = *operand;
-                Box::new(move |reg, ptr| {
-                    (
-                        Register {
-                            a: reg.a / (1 << Instruction::get_combo(op, reg) as usize),
-                            b: reg.b,
-                            c: reg.c,
-                        },
-                        ptr + 2,
-                        8,
-                    )
-                })
-            }
-            Bxl(operand) => {
-                let op = *operand;
-                Box::new(move |reg, ptr| {
-                    (
-                        Register {
-                            a: reg.a,
-                            b: reg.b ^ op as u64,
-                            c: reg.c,
-                        },
-                        ptr + 2,
-                        8,
-                    )
-                })
-            }
-            Bst(operand) => {
-                let op = *operand;
-                Box::new(move |reg, ptr| {
-                    (
-                        Register {
-                            a: reg.a,
-                            b: Self::get_combo(op, reg) & 7,
-                            c: reg.c,
-                        },
-                        ptr + 2,
-                        8,
-                    )
-                })
-            }
-            Jnz(operand) => {
-                let op = *operand;
-                Box::new(move |reg, ptr| {
-                    (
-                        Register {
-                            a: reg.a,
-                            b: reg.b,
-                            c: reg.c,
-                        },
-                        {
-                            if reg.a != 0 {
-                                op as usize
-                            } else {
-                                ptr + 2
-                            }
-                        },
-                        8,
-                    )
-                })
-            }
-
-            Bxc(_) => Box::new(move |reg, ptr| {
-                (
-                    Register {
-                        a: reg.a,
-                        b: reg.b ^ reg.c,
-                        c: reg.c,
-                    },
-                    ptr + 2,
-                    8,
-                )
-            }),
-            Out(operand) => {
-                let op = *operand;
-                Box::new(move |reg, ptr| {
-                    (
-                        Register {
-                            a: reg.a,
-                            b: reg.b,
-                            c: reg.c,
-                        },
-                        ptr + 2,
-                        (Instruction::get_combo(op, reg) & 7) as u8,
-                    )
-                })
-            }
-            Bdv(operand) => {
-                let op = *operand;
-                Box::new(move |reg, ptr| {
-                    (
-                        Register {
-                            a: reg.a,
-                            b: reg.a / (1 << Self::get_combo(op, reg) as usize),
-                            c: reg.c,
-                        },
-                        ptr + 2,
-                        8,
-                    )
-                })
-            }
-            Cdv(operand) => {
-                let op = *operand;
-                Box::new(move |reg, ptr| {
-                    (
-                        Register {
-                            a: reg.a,
-                            b: reg.b,
-                            c: reg.a / (1 << Self::get_combo(op, reg) as usize),
-                        },
-                        ptr + 2,
-                        8,
-                    )
-                })
-            }
-        }
-    }
-}
-
 /// Parse the entire multi-line input into a `Computer`.
 fn parse_input(input: &str) -> IResult<&str, Computer> {
     // 1) Parse the three registers
     let (input, (a, b, c)) = parse_registers(input)?;
     // There should be a line break after `Register C: <val>`
     let (input, _) = line_ending(input)?;
-    println!("Registers: {a}, {b}, {c}");
+    debug!("Registers: {a}, {b}, {c}");
 
     // 2) Parse the "Program: ..." line
     let (input, program) = parse_program_line(input)?;
-    println!("Program: {program:?}");
+    debug!("Program: {program:?}");
     // Construct the final Computer object
     let computer = Computer {
         store: Register { a, b, c },
@@ -243,6 +81,7 @@ fn parse_input(input: &str) -> IResult<&str, Computer> {
     Ok((input, computer))
 }
 // A helper to format a Duration in a friendlier, more readable way:
+#[allow(dead_code)]
 fn format_friendly_duration(dur: Duration) -> String {
     let secs = dur.as_secs();
     let millis = dur.subsec_millis();
@@ -260,351 +99,191 @@ fn format_friendly_duration(dur: Duration) -> String {
         format!("{}.{:03}s", secs, millis)
     }
 }
-//inline
-
-fn run_program(program: &[u8], r: &mut Register) -> Vec<u8> {
-    let mut ptr = 0;
-    let mut output = Vec::new();
-
-    while ptr < program.len() && program.len() >= output.len() &&  program[0..output.len()] == output[..] {
-        let opcode = program[ptr];
-        let operand = program[ptr + 1];
-        match opcode {
-            0 => { // ADV
-                let shift = Instruction::get_combo(operand, &r);
-                r.a >>= shift;
-                ptr += 2;
-            }
-            1 => { // BXL
-                r.b ^= operand as u64;
-                ptr += 2;
-            }
-            2 => { // BST
-                r.b = Instruction::get_combo(operand, &r) & 7;
-                ptr += 2;
-            }
-            3 => { // JNZ
-                if r.a != 0 {
-                    ptr = operand as usize;
-                } else {
-                    ptr += 2;
-                }
-            }
-            4 => { // BXC
-                r.b ^= r.c;
-                ptr += 2;
-            }
-            5 => {
-                // OUT
-                let val = Instruction::get_combo(operand, &r) & 7;
-                output.push(val as u8);
-                ptr += 2;
-            }
-            6 => { // BDV
-                let shift = Instruction::get_combo(operand, &r);
-                r.b = r.a >> shift;
-                ptr += 2;
-            }
-            7 => { // CDV
-                let shift = Instruction::get_combo(operand, &r);
-                r.c = r.a >> shift;
-                ptr += 2;
+/// Why [`reverse_solve`] cannot handle a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SolveError {
+    Invalid(VmError),
+    /// The program must be one loop: its only jump is a final `jnz 0`.
+    NotALoop,
+    /// Each pass must shift A right by a fixed number of bits, with one `adv` and a literal operand.
+    Shift(String),
+    /// Each pass must output exactly one digit; this is how many `out`s there were.
+    Outputs(usize),
+    /// The register is read before the pass sets it, so a pass depends on the one before.
+    CarriedOver(char),
+    NoSolution,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Invalid(error) => write!(f, "the program is invalid: {}", error),
+            SolveError::NotALoop => f.write_str("the program must end with its only jump, `jnz 0`"),
+            SolveError::Shift(found) => write!(f, "each pass must shift A by a fixed amount with one `adv <literal>`, found {}", found),
+            SolveError::Outputs(count) => write!(f, "each pass must output exactly one digit, found {} `out`s", count),
+            SolveError::CarriedOver(register) => {
+                write!(f, "register {} is read before it is set, so each pass depends on the one before", register)
             }
-            // etc...
-            _ => {}
+            SolveError::NoSolution => f.write_str("no value of A makes the program output itself"),
         }
     }
+}
 
-    output
+impl std::error::Error for SolveError {}
+
+/// A program of the shape every puzzle input has: one pass per output digit, shifting A right
+/// by `shift` bits each time, until A is 0.
+#[derive(Debug, PartialEq)]
+struct Loop<'a> {
+    /// The program without its final `jnz 0`.
+    body: &'a [u8],
+    shift: u32,
 }
 
-/// Returns all possible `a` values at iteration i=0 that yield `digits` 
-/// by the time we get to i=N (N digits).
-fn backward_solve(digits: &[u8]) -> Vec<u64> {
-    // We'll keep a "set of possible a-values" after the i-th digit (counting from the right).
-    // Start with i = N: after all digits have been produced, a might be anything, 
-    // but let's say we start with {0} as a baseline if the loop ended with a=0 
-    // (or we might allow a range).
-    //
-    // Actually, if the loop ended naturally, `a` could be zero (the while condition breaks). 
-    // But if you want an exact chain of length N, you can allow any final `a`.
-    // Let’s just do a BFS or back-propagation for one possibility: final a = 0.
-    //
-    use std::collections::HashSet;
-
-    let mut possible_as = HashSet::new();
-    // Suppose the final a is 0 after producing digits[N-1].
-    // You could also store multiple possible final `a`s if needed.
-    possible_as.insert(0u64);
-
-    // We'll go backwards from i = N-1 down to i = 0
-    let operations = &get_operations(digits);
-    for (i,&digit) in digits.iter().enumerate().rev() {
-        let program = digits[i..].to_vec();
-        println!("{} Program: {program:?}",possible_as.len());
-
-
-        let mut new_possible_as = HashSet::new();
-        for &a_next in &possible_as {
-            // We'll find all a_current that produce `digit` and yield `a_next` after >>3.
-            // a_current must be in [a_next<<3 .. a_next<<3+7].
-            let base = a_next << 3;
-            let top = 1 << 3;
-            // println!("Base: {base}, Top: {top} = {}", base+top);
-            for low3 in 0..top {
-                let a_candidate = base + low3;
-                let (output, _register) =
-                    part_1(&program, &Register { a: a_candidate, b: 0, c: 0 }, operations, true);
-                if output == program {
-                    new_possible_as.insert(a_candidate);
-                    // println!("Match!: {program:?} -> {a_candidate} ({} -> {})", possible_as.len(), new_possible_as.len());
+/// Check that `program` is a [`Loop`], explaining which assumption fails when it is not.
+fn analyse(program: &[u8]) -> Result<Loop<'_>, SolveError> {
+    let instructions = (0..program.len())
+        .step_by(2)
+        .filter_map(|address| Instruction::at(program, address).transpose())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(SolveError::Invalid)?;
+    let Some((Instruction::Jnz(0), body)) = instructions.split_last() else {
+        return Err(SolveError::NotALoop);
+    };
+    if body.iter().any(|instruction| matches!(instruction, Instruction::Jnz(_))) {
+        return Err(SolveError::NotALoop);
+    }
+
+    let shifts: Vec<&Instruction> = body.iter().filter(|instruction| matches!(instruction, Instruction::Adv(_))).collect();
+    let shift = match shifts[..] {
+        [Instruction::Adv(Combo::Literal(shift))] if *shift > 0 => *shift as u32,
+        [] => return Err(SolveError::Shift("no `adv`".to_string())),
+        [adv] => return Err(SolveError::Shift(format!("`{}`", adv))),
+        _ => return Err(SolveError::Shift(format!("{} `adv`s", shifts.len()))),
+    };
+
+    let outputs = body.iter().filter(|instruction| matches!(instruction, Instruction::Out(_))).count();
+    if outputs != 1 {
+        return Err(SolveError::Outputs(outputs));
+    }
+
+    // B and C must be set from A in each pass before they are used
+    let (mut b_set, mut c_set) = (false, false);
+    for instruction in body {
+        let (reads, writes) = match *instruction {
+            Instruction::Adv(combo) | Instruction::Bst(combo) | Instruction::Out(combo) => (Some(combo), None),
+            Instruction::Bdv(combo) => (Some(combo), Some(Combo::B)),
+            Instruction::Cdv(combo) => (Some(combo), Some(Combo::C)),
+            Instruction::Bxl(_) => (Some(Combo::B), None),
+            Instruction::Bxc => {
+                if !c_set {
+                    return Err(SolveError::CarriedOver('C'));
                 }
+                (Some(Combo::B), None)
             }
+            Instruction::Jnz(_) => (None, None),
+        };
+        match reads {
+            Some(Combo::B) if !b_set => return Err(SolveError::CarriedOver('B')),
+            Some(Combo::C) if !c_set => return Err(SolveError::CarriedOver('C')),
+            _ => {}
         }
-        possible_as = new_possible_as;
-        if possible_as.is_empty() {
-            // No solutions for this chain
-            break;
+        match (instruction, writes) {
+            (Instruction::Bst(_), _) | (_, Some(Combo::B)) => b_set = true,
+            (_, Some(Combo::C)) => c_set = true,
+            _ => {}
         }
     }
-
-    // After the loop, `possible_as` contains all a-values that, if we run the forward loop,
-    // produce exactly `digits`.
-    possible_as.into_iter().collect()
+    Ok(Loop { body: &program[..program.len() - 2], shift })
 }
 
-fn run_my_program(program: &[u8], r: &mut Register) -> Vec<u8> {
-    let mut output = Vec::with_capacity(program.len());
-    let Register {mut a, mut b, mut c} = r;
-    while a != 0 && program.len() >= output.len() &&  program[0..output.len()] == output[..] {
-        b = a & 7 ^ 7;
-        c = a >> b;
-        a >>= 3;
-        b ^= c ^ 7;
-        output.push((b & 7) as u8);
+/// The lowest positive A for which `program` outputs a copy of itself.
+///
+/// Working from the last digit, each pass adds `shift` low bits to A; trying the low bits in
+/// ascending order means the first A found is the smallest.
+fn reverse_solve(program: &[u8]) -> Result<u64, SolveError> {
+    let Loop { body, shift } = analyse(program)?;
+    // without its jump the body runs straight through, one step per instruction
+    let body = compile(body).map_err(SolveError::Invalid)?.with_step_limit(body.len() / 2);
+    let pass = |a: u64| body.run(Register { a, b: 0, c: 0 }).map(|(output, _)| output);
+
+    fn extend(digits: &[u8], a: u64, shift: u32, pass: &impl Fn(u64) -> Result<Vec<u8>, VmError>) -> Option<u64> {
+        let Some((&digit, earlier)) = digits.split_last() else {
+            return Some(a);
+        };
+        let high = a.checked_mul(1 << shift)?;
+        (0..1 << shift)
+            .map(|low| high | low)
+            // A reaching 0 ends the loop, so no pass but the one after the last can start with it
+            .filter(|&candidate| candidate != 0)
+            .filter(|&candidate| pass(candidate) == Ok(vec![digit]))
+            .find_map(|candidate| extend(earlier, candidate, shift, pass))
     }
-    output
-}
-fn part_2(computer: &Computer) -> Vec<u64> {
-    let program = computer.program.clone();
-    let start = Instant::now();
-    let expected = program.clone();
-    println!("Expected: {expected:?}");
-    // let mut a =  86167781376;
-    // while expected != run_my_program(&computer.program,  &mut Register { a, b: 0, c: 0 }) {
-    //     if a & 0xFFFFFFFF == 0 {
-    //         let elapsed = Instant::now().duration_since(start);
-    //         println!("{} A: {a}", format_friendly_duration(elapsed));
-    //     }
-    //     a += 1;
-    // }
-    let a = backward_solve(&expected);
-    println!("A: {a:?}");
-    a
-}
-fn get_operations(program: &[u8]) -> Vec<InstrFn> {
-    let mut operations: Vec<InstrFn> = Vec::new();
-    let mut ptr = 0;
-    while ptr < program.len() {
-        let instruction = Instruction::new(program[ptr], program[ptr + 1]);
-        operations.push(instruction.act());
-        ptr += 2;
+
+    let a = extend(program, 0, shift, &pass).ok_or(SolveError::NoSolution)?;
+    debug!("A: {a}");
+    // the passes were checked one at a time; make sure they chain up as assumed
+    let passes = program.len() * (program.len() / 2);
+    match Vm::new(program, Register { a, b: 0, c: 0 }).with_step_limit(passes).run() {
+        Ok(output) if output == program => Ok(a),
+        _ => Err(SolveError::NoSolution),
     }
-    operations
 }
-fn part_1(program: &[u8], register: &Register, operations: &Vec<InstrFn>, match_program: bool) -> (Vec<u8>, Register) {
-    let mut ptr = 0;
-    let mut output: Vec<u8> = Vec::new();
-    let mut register = register.clone();
-    while ptr < operations.len() * 2 && (!match_program || (program.len() >= output.len() &&  program[0..output.len()] == output[..])) {
-        let instruction_output: u8;
-        (register, ptr, instruction_output) = operations[ptr / 2](&register, ptr);
-        if instruction_output != 8 {
-            output.push(instruction_output);
-        }
+
+/// Run the computer until it halts, returning its output and final registers.
+///
+/// With debug logging on, every step is logged with the registers it leaves behind; otherwise
+/// the program is compiled first.
+fn part_1(computer: &Computer) -> Result<(Vec<u8>, Register), VmError> {
+    if !log_enabled!(Level::Debug) {
+        return compile(&computer.program)?.run(computer.store);
     }
-    (output, register)
+    let mut vm = Vm::new(&computer.program, computer.store);
+    let steps = vm.trace()?;
+    steps.iter().for_each(|step| debug!("{}", step));
+    Ok((steps.iter().filter_map(|step| step.output).collect(), vm.register))
 }
 
-pub(crate) fn solve(input: String) -> (String, u64) {
-    match parse_input(&*input) {
-        Ok((remaining, computer)) => {
-            println!("Parsed Computer: {computer:?}");
-            println!("Remaining: {remaining:?}");
-            let mut part_2 = measure_time!(part_2(&computer));
-            part_2.sort();
-            (
-                part_1(
-                    &computer.program,
-                    &computer.store,
-                    &get_operations(&computer.program),
-                    false,
-                )
-                .0
-                .iter()
-                .map(|x| x.to_string())
-                .collect::<Vec<String>>()
-                .join(","),
-                part_2[0],
-            ) //part_1 4,6,5,4,4,3,7,5,3 is not correct...
-        }
-        Err(err) => {
-            eprintln!("Error parsing input: {err}");
-            ("".to_string(), 0)
-        }
+pub(crate) struct Day17;
+
+impl Solution for Day17 {
+    type Input = Computer;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let (remaining, computer) = parse_input(input).map_err(|error| ParseError::from_nom(input, error))?;
+        debug!("Parsed Computer: {computer:?}");
+        debug!("Remaining: {remaining:?}");
+        Ok(computer)
+    }
+
+    fn part1(computer: &Self::Input) -> Result<String, VmError> {
+        debug!("Program:\n{}", disassemble(&computer.program).unwrap_or_default());
+        let (output, _) = part_1(computer)?;
+        Ok(output
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join(",")) //part_1 4,6,5,4,4,3,7,5,3 is not correct...
+    }
+
+    /// The lowest A that makes the program output itself, or why the program is not one this can solve.
+    fn part2(computer: &Self::Input) -> Result<u64, SolveError> {
+        reverse_solve(&computer.program)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use timing_util::measure_time;
-    //     Adv(i32),
-    #[test]
-    fn adv_combo_test() {
-        let instruction = Instruction::new(0, 5);
-        let register = Register { a: 64, b: 5, c: 9 };
-        let (register, pointer, output) = instruction.act()(&register, 0);
-        assert_eq!(register, Register { a: 2, b: 5, c: 9 });
-        assert_eq!(pointer, 2);
-        assert_eq!(output, 8);
-    }
-    #[test]
-    fn adv_literal_test() {
-        let instruction = Instruction::new(0, 3);
-        let register = Register { a: 64, b: 5, c: 9 };
-        let (register, pointer, output) = instruction.act()(&register, 0);
-        assert_eq!(register, Register { a: 8, b: 5, c: 9 });
-        assert_eq!(pointer, 2);
-        assert_eq!(output, 8);
-    }
-    //     Bxl(i32),
-    #[test]
-    fn bxl_test() {
-        let instruction = Instruction::new(1, 6);
-        let register = Register { a: 64, b: 6, c: 9 };
-        let (register, pointer, output) = instruction.act()(&register, 0);
-
-        assert_eq!(register, Register { a: 64, b: 0, c: 9 });
-        assert_eq!(pointer, 2);
-        assert_eq!(output, 8);
-    }
-    //     Bst(i32),
-    #[test]
-    fn bst_test() {
-        let instruction = Instruction::new(2, 6);
-        let register = Register { a: 4, b: 1, c: 9 };
-        let (register, pointer, output) = instruction.act()(&register, 0);
-        assert_eq!(register, Register { a: 4, b: 1, c: 9 });
-        assert_eq!(pointer, 2);
-        assert_eq!(output, 8);
-    }
-    //     Jnz(i32),
-    #[test]
-    fn jnz_test() {
-        let instruction = Instruction::new(3, 6);
-        let register = &Register { a: 4, b: 0, c: 9 };
-        let (register, pointer, output) = instruction.act()(&register, 0);
-
-        assert_eq!(register, Register { a: 4, b: 0, c: 9 });
-        assert_eq!(pointer, 6);
-        assert_eq!(output, 8);
-    }
-    #[test]
-    fn jnz_0_test() {
-        let instruction = Instruction::new(3, 6);
-        let register = &Register { a: 0, b: 0, c: 9 };
-        let (register, pointer, output) = instruction.act()(&register, 2);
-
-        assert_eq!(register, Register { a: 0, b: 0, c: 9 });
-        assert_eq!(pointer, 4);
-        assert_eq!(output, 8);
-    }
-    //     Bxc(()),
-    #[test]
-    fn bxc_test() {
-        let instruction = Instruction::new(4, 6);
-        let register = &Register { a: 0, b: 16, c: 14 };
-        let (register, pointer, output) = instruction.act()(&register, 2);
-
-        assert_eq!(register, Register { a: 0, b: 30, c: 14 });
-        assert_eq!(pointer, 4);
-        assert_eq!(output, 8);
-    }
-    #[test]
-    fn bxc_2_test() {
-        let instruction = Instruction::new(4, 6);
-        let register = &Register { a: 0, b: 12, c: 10 };
-        let (register, pointer, output) = instruction.act()(&register, 2);
-
-        assert_eq!(register, Register { a: 0, b: 6, c: 10 });
-        assert_eq!(pointer, 4);
-        assert_eq!(output, 8);
-    }
-    //     Out(i32),
-    #[test]
-    fn out_combo_test() {
-        let instruction = Instruction::new(5, 6);
-        let register = Register { a: 12, b: 5, c: 64 };
-        let (register, pointer, output) = instruction.act()(&register, 0);
-
-        assert_eq!(register, Register { a: 12, b: 5, c: 64 });
-        assert_eq!(pointer, 2);
-        assert_eq!(output, 0);
-    }
-    #[test]
-    fn out_literal_test() {
-        let instruction = Instruction::new(5, 3);
-        let register = Register { a: 64, b: 5, c: 9 };
-        let (register, pointer, output) = instruction.act()(&register, 0);
-
-        assert_eq!(register, Register { a: 64, b: 5, c: 9 });
-        assert_eq!(pointer, 2);
-        assert_eq!(output, 3);
-    }
-    //     Bdv(i32),
-    #[test]
-    fn bdv_combo_test() {
-        let instruction = Instruction::new(6, 6);
-        let register = Register { a: 64, b: 10, c: 5 };
-        let (register, pointer, output) = instruction.act()(&register, 0);
-
-        assert_eq!(register, Register { a: 64, b: 2, c: 5 });
-        assert_eq!(pointer, 2);
-        assert_eq!(output, 8);
-    }
-    #[test]
-    fn bdv_literal_test() {
-        let instruction = Instruction::new(6, 3);
-        let register = Register { a: 64, b: 64, c: 9 };
-        let (register, pointer, output) = instruction.act()(&register, 0);
-
-        assert_eq!(register, Register { a: 64, b: 8, c: 9 });
-        assert_eq!(pointer, 2);
-        assert_eq!(output, 8);
-    }
-    //     Cdv(i32),
-    #[test]
-    fn cdv_combo_test() {
-        let instruction = Instruction::new(7, 5);
-        let register = Register { a: 64, b: 5, c: 64 };
-        let (register, pointer, output) = instruction.act()(&register, 0);
-
-        assert_eq!(register, Register { a: 64, b: 5, c: 2 });
-        assert_eq!(pointer, 2);
-        assert_eq!(output, 8);
-    }
+    use timing_util::{bench, measure_time};
+
     #[test]
-    fn cdv_literal_test() {
-        let instruction = Instruction::new(7, 3);
-        let register = Register { a: 64, b: 5, c: 64 };
-        let (register, pointer, output) = instruction.act()(&register, 0);
-
-        assert_eq!(register, Register { a: 64, b: 5, c: 8 });
-        assert_eq!(pointer, 2);
-        assert_eq!(output, 8);
+    fn parse_reserved_operand_test() {
+        let input = "Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,7\n";
+        let error = Day17::parse(input).unwrap_err();
+        assert_eq!((error.line, error.column, error.kind), (5, 10, nom::error::ErrorKind::Verify));
+        assert!(Day17::parse("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4\n").is_ok());
     }
-
     //     If register A contains 10, the program 5,0,5,1,5,4 would output 0,1,2.
     #[test]
     fn computer_test() {
@@ -612,8 +291,7 @@ mod tests {
             store: Register { a: 10, b: 0, c: 0 },
             program: vec![5, 0, 5, 1, 5, 4],
         };
-        let (output, _register) =
-            part_1(&computer.program, &computer.store, &get_operations(&computer.program), false);
+        let (output, _register) = part_1(&computer).unwrap();
         assert_eq!(output, [0, 1, 2]);
     }
     // If register A contains 2024, the program 0,1,5,4,3,0 would output 4,2,5,6,7,7,7,7,3,1,0 and leave 0 in register A.
@@ -627,8 +305,7 @@ mod tests {
             },
             program: vec![0, 1, 5, 4, 3, 0],
         };
-        let (output, register) =
-            part_1(&computer.program, &computer.store, &get_operations(&computer.program), false);
+        let (output, register) = part_1(&computer).unwrap();
         assert_eq!(output, [4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
         assert_eq!(register.a, 0);
     }
@@ -639,8 +316,7 @@ mod tests {
             store: Register { a: 0, b: 29, c: 0 },
             program: vec![1, 7],
         };
-        let (output, register) =
-            part_1(&computer.program, &computer.store, &get_operations(&computer.program), false);
+        let (output, register) = part_1(&computer).unwrap();
         assert_eq!(output, []);
         assert_eq!(register.b, 26);
     }
@@ -655,8 +331,7 @@ mod tests {
             },
             program: vec![4, 0],
         };
-        let (output, register) =
-            part_1(&computer.program, &computer.store, &get_operations(&computer.program), false);
+        let (output, register) = part_1(&computer).unwrap();
         assert_eq!(output, []);
         assert_eq!(register.b, 44354);
     }
@@ -671,8 +346,7 @@ mod tests {
             store: Register { a: 729, b: 0, c: 0 },
             program: vec![0, 1, 5, 4, 3, 0],
         };
-        let (output, _register) =
-            part_1(&computer.program, &computer.store, &get_operations(&computer.program), false);
+        let (output, _register) = part_1(&computer).unwrap();
         assert_eq!(output, [4, 6, 3, 5, 6, 3, 5, 2, 1, 0]);
     }
     // Register A: 2024
@@ -691,11 +365,11 @@ mod tests {
             },
             program: vec![0, 3, 5, 4, 3, 0],
         };
-        let (output, _register) =
-            part_1(&computer.program, &computer.store, &get_operations(&computer.program), false);
+        let (output, _register) = part_1(&computer).unwrap();
         assert_eq!(output, [0, 3, 5, 4, 3, 0]);
     } 
     
+    #[allow(dead_code)]
     fn testing_part_1_using_part_2_2_provided_test() {
         let computer = Computer {
             store: Register {
@@ -705,8 +379,7 @@ mod tests {
             },
             program: vec![0, 3, 5, 4, 3, 0],
         };
-        let (output, _register) =
-            part_1(&computer.program, &computer.store, &get_operations(&computer.program), false);
+        let (output, _register) = part_1(&computer).unwrap();
         assert_eq!(output, [0, 3, 5, 4, 3, 0]);
     }
     #[test]
@@ -719,8 +392,75 @@ mod tests {
             },
             program: vec![0, 3, 5, 4, 3, 0],
         };
-        let mut result = measure_time!(part_2(&computer));
-        result.sort();
-        assert_eq!(result[0], 117440);
+        assert_eq!(measure_time!(reverse_solve(&computer.program)), Ok(117440));
+    }
+    // bst A, bxl 1, cdv B, bxc, adv 3, bxl 6, out B, jnz 0: the shape of a real input
+    #[test]
+    fn reverse_solve_input_shape_test() {
+        let program = [2, 4, 1, 1, 7, 5, 4, 0, 0, 3, 1, 6, 5, 5, 3, 0];
+        let a = reverse_solve(&program).unwrap();
+        assert_eq!(Vm::new(&program, Register { a, b: 0, c: 0 }).run().unwrap(), program);
+        // no A with one fewer digit can print all 16 digits
+        assert!(a >= 1 << 45);
+    }
+    #[test]
+    fn analyse_test() {
+        assert_eq!(analyse(&[0, 3, 5, 4, 3, 0]), Ok(Loop { body: &[0, 3, 5, 4], shift: 3 }));
+        assert_eq!(analyse(&[0, 1, 5, 4, 3, 0]).map(|found| found.shift), Ok(1));
+    }
+    #[test]
+    fn analyse_errors_test() {
+        assert_eq!(analyse(&[0, 3, 5, 4]), Err(SolveError::NotALoop));
+        assert_eq!(analyse(&[0, 3, 5, 4, 3, 2]), Err(SolveError::NotALoop));
+        assert_eq!(analyse(&[0, 3, 3, 0, 5, 4, 3, 0]), Err(SolveError::NotALoop));
+        assert_eq!(analyse(&[0, 3, 0, 1, 5, 4, 3, 0]), Err(SolveError::Shift("2 `adv`s".to_string())));
+        assert_eq!(analyse(&[0, 5, 5, 4, 3, 0]), Err(SolveError::Shift("`adv B`".to_string())));
+        assert_eq!(analyse(&[0, 0, 5, 4, 3, 0]), Err(SolveError::Shift("`adv 0`".to_string())));
+        assert_eq!(analyse(&[5, 4, 3, 0]), Err(SolveError::Shift("no `adv`".to_string())));
+        assert_eq!(analyse(&[0, 3, 3, 0]), Err(SolveError::Outputs(0)));
+        assert_eq!(analyse(&[0, 3, 5, 4, 5, 4, 3, 0]), Err(SolveError::Outputs(2)));
+        assert_eq!(analyse(&[0, 3, 5, 5, 3, 0]), Err(SolveError::CarriedOver('B')));
+        assert_eq!(analyse(&[2, 4, 4, 0, 0, 3, 5, 5, 3, 0]), Err(SolveError::CarriedOver('C')));
+        assert_eq!(analyse(&[0, 7, 5, 4, 3, 0]), Err(SolveError::Invalid(VmError::ReservedOperand { address: 0 })));
+    }
+    #[test]
+    fn solve_error_display_test() {
+        assert_eq!(
+            SolveError::CarriedOver('B').to_string(),
+            "register B is read before it is set, so each pass depends on the one before"
+        );
+    }
+    #[test]
+    fn unsolvable_parts_are_run_errors_test() {
+        use crate::solution::{run_with, Params, Part, RunError};
+        // a valid program with a part 1 answer, but no A outputs the program itself
+        let input = "Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0\n";
+        let error = run_with::<Day17>(input, &Params::default()).unwrap_err();
+        assert_eq!(error, RunError::Solve { part: Part::Two, message: SolveError::NoSolution.to_string() });
+        assert_eq!(Day17::part1(&Day17::parse(input).unwrap()).unwrap(), "4,2,5,6,7,7,7,7,3,1,0");
+        // `jnz 0` with A never changing runs until the step limit
+        let input = "Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,0\n";
+        let error = run_with::<Day17>(input, &Params::default()).unwrap_err();
+        let limit = crate::puzzles::day17_vm::DEFAULT_STEP_LIMIT;
+        assert_eq!(error, RunError::Solve { part: Part::One, message: VmError::StepLimit { limit }.to_string() });
+    }
+    /// Compare the interpreter, the compiled program and `part_1` over a range of A values.
+    ///
+    /// `cargo test --release day17_bench -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn day17_bench() {
+        let program = [2, 4, 1, 1, 7, 5, 4, 0, 0, 3, 1, 6, 5, 5, 3, 0];
+        let starts = (1u64 << 45..).step_by(7919).take(1000);
+        let compiled = compile(&program).unwrap();
+        let interpret = |a| Vm::new(&program, Register { a, b: 0, c: 0 }).run().unwrap();
+        let run_compiled = |a| compiled.run(Register { a, b: 0, c: 0 }).unwrap().0;
+        let solve = |a| part_1(&Computer { store: Register { a, b: 0, c: 0 }, program: program.to_vec() }).unwrap().0;
+
+        let interpreted = bench!(starts.clone().map(interpret).collect::<Vec<_>>());
+        let compiled = bench!(starts.clone().map(run_compiled).collect::<Vec<_>>());
+        let solved = bench!(starts.clone().map(solve).collect::<Vec<_>>());
+        let speedup = |stats: timing_util::Stats| interpreted.median.as_secs_f64() / stats.median.as_secs_f64();
+        println!("compiled: {:.1}x the interpreter's speed, part_1: {:.1}x", speedup(compiled), speedup(solved));
     }
 }
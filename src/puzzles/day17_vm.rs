@@ -0,0 +1,545 @@
+//! The 3-bit computer from day 17: an instruction decoder, a disassembler, an
+//! interpreter that can be stepped, traced, and stopped after a number of instructions,
+//! and a compiler to basic blocks for when only the output matters.
+//!
+//! ```text
+//!  0: bst A
+//!  2: bxl 3
+//!  4: cdv B
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub(crate) struct Register {
+    pub(crate) a: u64,
+    pub(crate) b: u64,
+    pub(crate) c: u64,
+}
+
+/// A combo operand: 0 to 3 stand for themselves and 4 to 6 for registers A to C.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Combo {
+    Literal(u8),
+    A,
+    B,
+    C,
+}
+
+impl Combo {
+    fn decode(operand: u8) -> Option<Self> {
+        match operand {
+            0..=3 => Some(Combo::Literal(operand)),
+            4 => Some(Combo::A),
+            5 => Some(Combo::B),
+            6 => Some(Combo::C),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn value(self, register: &Register) -> u64 {
+        match self {
+            Combo::Literal(value) => value as u64,
+            Combo::A => register.a,
+            Combo::B => register.b,
+            Combo::C => register.c,
+        }
+    }
+}
+
+impl fmt::Display for Combo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Combo::Literal(value) => write!(f, "{}", value),
+            Combo::A => f.write_str("A"),
+            Combo::B => f.write_str("B"),
+            Combo::C => f.write_str("C"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Instruction {
+    /// A = A >> combo
+    Adv(Combo),
+    /// B = B ^ literal
+    Bxl(u8),
+    /// B = combo & 7
+    Bst(Combo),
+    /// jump to literal unless A is 0
+    Jnz(u8),
+    /// B = B ^ C; the operand is ignored
+    Bxc,
+    /// output combo & 7
+    Out(Combo),
+    /// B = A >> combo
+    Bdv(Combo),
+    /// C = A >> combo
+    Cdv(Combo),
+}
+
+impl Instruction {
+    /// Decode the instruction at `address`, or `None` when the program halts there.
+    pub(crate) fn at(program: &[u8], address: usize) -> Result<Option<Self>, VmError> {
+        match program.get(address..address + 2) {
+            Some(&[opcode, operand]) => Self::decode(opcode, operand, address).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn decode(opcode: u8, operand: u8, address: usize) -> Result<Self, VmError> {
+        let combo = || Combo::decode(operand).ok_or(VmError::ReservedOperand { address });
+        let literal = || if operand < 8 { Ok(operand) } else { Err(VmError::InvalidOperand { address, operand }) };
+        Ok(match opcode {
+            0 => Instruction::Adv(combo()?),
+            1 => Instruction::Bxl(literal()?),
+            2 => Instruction::Bst(combo()?),
+            3 => Instruction::Jnz(literal()?),
+            4 => Instruction::Bxc,
+            5 => Instruction::Out(combo()?),
+            6 => Instruction::Bdv(combo()?),
+            7 => Instruction::Cdv(combo()?),
+            _ => return Err(VmError::UnknownOpcode { address, opcode }),
+        })
+    }
+
+    /// Run the instruction at `address`, returning the next address and any output.
+    pub(crate) fn execute(self, register: &mut Register, address: usize) -> (usize, Option<u8>) {
+        let next = address + 2;
+        match self {
+            Instruction::Adv(combo) => register.a = shift_right(register.a, combo.value(register)),
+            Instruction::Bxl(literal) => register.b ^= literal as u64,
+            Instruction::Bst(combo) => register.b = combo.value(register) & 7,
+            Instruction::Jnz(literal) => {
+                if register.a != 0 {
+                    return (literal as usize, None);
+                }
+            }
+            Instruction::Bxc => register.b ^= register.c,
+            Instruction::Out(combo) => return (next, Some((combo.value(register) & 7) as u8)),
+            Instruction::Bdv(combo) => register.b = shift_right(register.a, combo.value(register)),
+            Instruction::Cdv(combo) => register.c = shift_right(register.a, combo.value(register)),
+        }
+        (next, None)
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Adv(combo) => write!(f, "adv {}", combo),
+            Instruction::Bxl(literal) => write!(f, "bxl {}", literal),
+            Instruction::Bst(combo) => write!(f, "bst {}", combo),
+            Instruction::Jnz(literal) => write!(f, "jnz {}", literal),
+            Instruction::Bxc => f.write_str("bxc"),
+            Instruction::Out(combo) => write!(f, "out {}", combo),
+            Instruction::Bdv(combo) => write!(f, "bdv {}", combo),
+            Instruction::Cdv(combo) => write!(f, "cdv {}", combo),
+        }
+    }
+}
+
+/// `value / 2^shift`, which is 0 once the shift passes the width of `value`.
+fn shift_right(value: u64, shift: u64) -> u64 {
+    u32::try_from(shift).ok().and_then(|shift| value.checked_shr(shift)).unwrap_or(0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VmError {
+    UnknownOpcode { address: usize, opcode: u8 },
+    InvalidOperand { address: usize, operand: u8 },
+    /// Combo operand 7 is reserved and does not appear in valid programs.
+    ReservedOperand { address: usize },
+    StepLimit { limit: usize },
+}
+
+impl fmt::Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VmError::UnknownOpcode { address, opcode } => write!(f, "unknown opcode {} at {}", opcode, address),
+            VmError::InvalidOperand { address, operand } => write!(f, "operand {} at {} is not 3 bits", operand, address),
+            VmError::ReservedOperand { address } => write!(f, "reserved combo operand 7 at {}", address + 1),
+            VmError::StepLimit { limit } => write!(f, "still running after {} instructions", limit),
+        }
+    }
+}
+
+impl std::error::Error for VmError {}
+
+/// A listing of the program, one `address: instruction` line per pair of values.
+pub(crate) fn disassemble(program: &[u8]) -> Result<String, VmError> {
+    let mut listing = String::new();
+    for address in (0..program.len()).step_by(2) {
+        if let Some(instruction) = Instruction::at(program, address)? {
+            listing.push_str(&format!("{:2}: {}\n", address, instruction));
+        }
+    }
+    Ok(listing)
+}
+
+/// Enough for any puzzle input, which loops once per 3 bits of A.
+pub(crate) const DEFAULT_STEP_LIMIT: usize = 1_000_000;
+
+/// One executed instruction, with the registers either side of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Step {
+    pub(crate) address: usize,
+    pub(crate) instruction: Instruction,
+    pub(crate) before: Register,
+    pub(crate) after: Register,
+    pub(crate) output: Option<u8>,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Register { a, b, c } = self.after;
+        write!(f, "{:2}: {:<6} A={} B={} C={}", self.address, self.instruction.to_string(), a, b, c)?;
+        if let Some(output) = self.output {
+            write!(f, " -> {}", output)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct Vm<'a> {
+    program: &'a [u8],
+    pub(crate) register: Register,
+    ip: usize,
+    steps: usize,
+    step_limit: usize,
+}
+
+impl<'a> Vm<'a> {
+    pub(crate) fn new(program: &'a [u8], register: Register) -> Self {
+        Vm { program, register, ip: 0, steps: 0, step_limit: DEFAULT_STEP_LIMIT }
+    }
+
+    /// Give up with [`VmError::StepLimit`] rather than execute more than `limit` instructions.
+    pub(crate) fn with_step_limit(self, limit: usize) -> Self {
+        Vm { step_limit: limit, ..self }
+    }
+
+    /// Execute one instruction, or return `None` once the program has halted.
+    pub(crate) fn step(&mut self) -> Result<Option<Step>, VmError> {
+        let Some(instruction) = Instruction::at(self.program, self.ip)? else {
+            return Ok(None);
+        };
+        if self.steps == self.step_limit {
+            return Err(VmError::StepLimit { limit: self.step_limit });
+        }
+        self.steps += 1;
+        let (address, before) = (self.ip, self.register);
+        let output;
+        (self.ip, output) = instruction.execute(&mut self.register, address);
+        Ok(Some(Step { address, instruction, before, after: self.register, output }))
+    }
+
+    /// Run until the program halts, returning its output.
+    pub(crate) fn run(&mut self) -> Result<Vec<u8>, VmError> {
+        let mut output = Vec::new();
+        while let Some(step) = self.step()? {
+            output.extend(step.output);
+        }
+        Ok(output)
+    }
+
+    /// Run until the program halts, recording every step.
+    pub(crate) fn trace(&mut self) -> Result<Vec<Step>, VmError> {
+        let mut steps = Vec::new();
+        while let Some(step) = self.step()? {
+            steps.push(step);
+        }
+        Ok(steps)
+    }
+}
+
+/// Where control goes once a [`Block`] has run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Exit {
+    Halt,
+    /// Run straight on into another block, because it starts where this one stops.
+    Next(usize),
+    /// `jnz`: go to `taken` unless A is 0, otherwise to `otherwise`.
+    Jump { taken: Option<usize>, otherwise: Option<usize> },
+}
+
+/// A run of instructions that is only ever entered at the top, with no jumps inside it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Block {
+    instructions: Vec<Instruction>,
+    exit: Exit,
+}
+
+impl Block {
+    /// The instructions that count towards a step limit, the `jnz` included.
+    fn steps(&self) -> usize {
+        self.instructions.len() + matches!(self.exit, Exit::Jump { .. }) as usize
+    }
+}
+
+/// A program decoded once into basic blocks whose jumps go straight to the next block,
+/// so running it needs no decoding or address arithmetic. It gives the same output as [`Vm`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Compiled {
+    /// Block 0 is the entry; empty when the program is.
+    blocks: Vec<Block>,
+    step_limit: usize,
+}
+
+/// Split the reachable part of `program` into [`Block`]s.
+///
+/// A block starts at address 0, at a `jnz` target, or just after a `jnz`. Targets need not be
+/// even, so a program may be read as two different instruction streams. Unlike [`Vm`], an
+/// invalid instruction is an error here even if no run would ever reach it.
+pub(crate) fn compile(program: &[u8]) -> Result<Compiled, VmError> {
+    let halts = |address: usize| address + 2 > program.len();
+    // first find where every block starts
+    let mut starts = BTreeSet::new();
+    let mut pending = vec![0];
+    while let Some(start) = pending.pop() {
+        if halts(start) || !starts.insert(start) {
+            continue;
+        }
+        let mut address = start;
+        while let Some(instruction) = Instruction::at(program, address)? {
+            if let Instruction::Jnz(target) = instruction {
+                pending.extend([target as usize, address + 2]);
+                break;
+            }
+            address += 2;
+        }
+    }
+
+    // then decode each one up to its jump or the start of the next
+    let index: HashMap<usize, usize> = starts.iter().enumerate().map(|(index, &start)| (start, index)).collect();
+    let block_at = |address: usize| index.get(&address).copied();
+    let blocks = starts
+        .iter()
+        .map(|&start| {
+            let mut instructions = Vec::new();
+            let mut address = start;
+            let exit = loop {
+                match Instruction::at(program, address)? {
+                    None => break Exit::Halt,
+                    Some(Instruction::Jnz(target)) => {
+                        break Exit::Jump { taken: block_at(target as usize), otherwise: block_at(address + 2) }
+                    }
+                    Some(instruction) => instructions.push(instruction),
+                }
+                address += 2;
+                if let Some(next) = block_at(address) {
+                    break Exit::Next(next);
+                }
+            };
+            Ok(Block { instructions, exit })
+        })
+        .collect::<Result<_, VmError>>()?;
+    Ok(Compiled { blocks, step_limit: DEFAULT_STEP_LIMIT })
+}
+
+impl Compiled {
+    /// Give up with [`VmError::StepLimit`] rather than execute more than `limit` instructions.
+    pub(crate) fn with_step_limit(self, limit: usize) -> Self {
+        Compiled { step_limit: limit, ..self }
+    }
+
+    /// Run from `register` until the program halts, returning its output and final registers.
+    pub(crate) fn run(&self, mut register: Register) -> Result<(Vec<u8>, Register), VmError> {
+        let mut output = Vec::new();
+        let mut steps = 0;
+        let mut current = (!self.blocks.is_empty()).then_some(0);
+        while let Some(block) = current.map(|index| &self.blocks[index]) {
+            // a block either runs to its end or not at all, so check the limit once per block
+            steps += block.steps();
+            if steps > self.step_limit {
+                return Err(VmError::StepLimit { limit: self.step_limit });
+            }
+            for instruction in &block.instructions {
+                output.extend(instruction.execute(&mut register, 0).1);
+            }
+            current = match block.exit {
+                Exit::Halt => None,
+                Exit::Next(next) => Some(next),
+                Exit::Jump { taken, otherwise } => if register.a != 0 { taken } else { otherwise },
+            };
+        }
+        Ok((output, register))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check;
+    use crate::generate::Rng;
+
+    /// Execute a single instruction at `address`.
+    fn execute(opcode: u8, operand: u8, register: Register, address: usize) -> (Register, usize, Option<u8>) {
+        let mut register = register;
+        let (next, output) = Instruction::decode(opcode, operand, address).unwrap().execute(&mut register, address);
+        (register, next, output)
+    }
+
+    #[test]
+    fn adv_test() {
+        assert_eq!(execute(0, 5, Register { a: 64, b: 5, c: 9 }, 0), (Register { a: 2, b: 5, c: 9 }, 2, None));
+        assert_eq!(execute(0, 3, Register { a: 64, b: 5, c: 9 }, 0), (Register { a: 8, b: 5, c: 9 }, 2, None));
+        // shifting by the width of A or more empties it rather than overflowing
+        assert_eq!(execute(0, 5, Register { a: 64, b: 70, c: 9 }, 0).0.a, 0);
+    }
+    #[test]
+    fn bxl_test() {
+        assert_eq!(execute(1, 6, Register { a: 64, b: 6, c: 9 }, 0), (Register { a: 64, b: 0, c: 9 }, 2, None));
+    }
+    #[test]
+    fn bst_test() {
+        assert_eq!(execute(2, 6, Register { a: 4, b: 1, c: 9 }, 0), (Register { a: 4, b: 1, c: 9 }, 2, None));
+    }
+    #[test]
+    fn jnz_test() {
+        assert_eq!(execute(3, 6, Register { a: 4, b: 0, c: 9 }, 0), (Register { a: 4, b: 0, c: 9 }, 6, None));
+        assert_eq!(execute(3, 6, Register { a: 0, b: 0, c: 9 }, 2), (Register { a: 0, b: 0, c: 9 }, 4, None));
+    }
+    #[test]
+    fn bxc_test() {
+        assert_eq!(execute(4, 6, Register { a: 0, b: 16, c: 14 }, 2), (Register { a: 0, b: 30, c: 14 }, 4, None));
+        assert_eq!(execute(4, 6, Register { a: 0, b: 12, c: 10 }, 2), (Register { a: 0, b: 6, c: 10 }, 4, None));
+    }
+    #[test]
+    fn out_test() {
+        assert_eq!(execute(5, 6, Register { a: 12, b: 5, c: 64 }, 0), (Register { a: 12, b: 5, c: 64 }, 2, Some(0)));
+        assert_eq!(execute(5, 3, Register { a: 64, b: 5, c: 9 }, 0), (Register { a: 64, b: 5, c: 9 }, 2, Some(3)));
+    }
+    #[test]
+    fn bdv_test() {
+        assert_eq!(execute(6, 6, Register { a: 64, b: 10, c: 5 }, 0), (Register { a: 64, b: 2, c: 5 }, 2, None));
+        assert_eq!(execute(6, 3, Register { a: 64, b: 64, c: 9 }, 0), (Register { a: 64, b: 8, c: 9 }, 2, None));
+    }
+    #[test]
+    fn cdv_test() {
+        assert_eq!(execute(7, 5, Register { a: 64, b: 5, c: 64 }, 0), (Register { a: 64, b: 5, c: 2 }, 2, None));
+        assert_eq!(execute(7, 3, Register { a: 64, b: 5, c: 64 }, 0), (Register { a: 64, b: 5, c: 8 }, 2, None));
+    }
+
+    #[test]
+    fn decode_test() {
+        assert_eq!(Instruction::at(&[0, 3, 5, 4], 2), Ok(Some(Instruction::Out(Combo::A))));
+        assert_eq!(Instruction::at(&[0, 3, 5], 2), Ok(None));
+        assert_eq!(Instruction::at(&[0, 3], 2), Ok(None));
+        assert_eq!(Instruction::at(&[1, 7, 5, 7], 2), Err(VmError::ReservedOperand { address: 2 }));
+        assert_eq!(Instruction::at(&[8, 0], 0), Err(VmError::UnknownOpcode { address: 0, opcode: 8 }));
+    }
+
+    #[test]
+    fn disassemble_test() {
+        assert_eq!(disassemble(&[0, 3, 5, 4, 3, 0]).unwrap(), " 0: adv 3\n 2: out A\n 4: jnz 0\n");
+        assert_eq!(disassemble(&[2, 4, 1, 7, 7, 5, 4, 2, 6, 5]).unwrap(), " 0: bst A\n 2: bxl 7\n 4: cdv B\n 6: bxc\n 8: bdv B\n");
+        assert_eq!(disassemble(&[5, 7]), Err(VmError::ReservedOperand { address: 0 }));
+    }
+
+    #[test]
+    fn run_test() {
+        // If register A contains 2024, the program 0,1,5,4,3,0 would output 4,2,5,6,7,7,7,7,3,1,0 and leave 0 in register A.
+        let mut vm = Vm::new(&[0, 1, 5, 4, 3, 0], Register { a: 2024, b: 0, c: 0 });
+        assert_eq!(vm.run(), Ok(vec![4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]));
+        assert_eq!(vm.register.a, 0);
+    }
+
+    #[test]
+    fn trace_test() {
+        let mut vm = Vm::new(&[0, 1, 5, 4, 3, 0], Register { a: 4, b: 0, c: 0 });
+        let trace = vm.trace().unwrap();
+        let lines: Vec<String> = trace.iter().map(|step| step.to_string()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                " 0: adv 1  A=2 B=0 C=0",
+                " 2: out A  A=2 B=0 C=0 -> 2",
+                " 4: jnz 0  A=2 B=0 C=0",
+                " 0: adv 1  A=1 B=0 C=0",
+                " 2: out A  A=1 B=0 C=0 -> 1",
+                " 4: jnz 0  A=1 B=0 C=0",
+                " 0: adv 1  A=0 B=0 C=0",
+                " 2: out A  A=0 B=0 C=0 -> 0",
+                " 4: jnz 0  A=0 B=0 C=0",
+            ]
+        );
+        assert_eq!(trace[0].before, Register { a: 4, b: 0, c: 0 });
+    }
+
+    #[test]
+    fn step_limit_test() {
+        // jnz 0 with A never reaching 0 loops forever
+        let mut vm = Vm::new(&[3, 0], Register { a: 1, b: 0, c: 0 }).with_step_limit(100);
+        assert_eq!(vm.run(), Err(VmError::StepLimit { limit: 100 }));
+        // a program that halts within the limit is unaffected
+        let mut vm = Vm::new(&[5, 4], Register { a: 1, b: 0, c: 0 }).with_step_limit(1);
+        assert_eq!(vm.run(), Ok(vec![1]));
+    }
+
+    #[test]
+    fn compile_test() {
+        let compiled = compile(&[0, 3, 5, 4, 3, 0]).unwrap();
+        let body = vec![Instruction::Adv(Combo::Literal(3)), Instruction::Out(Combo::A)];
+        assert_eq!(compiled.blocks, vec![Block { instructions: body, exit: Exit::Jump { taken: Some(0), otherwise: None } }]);
+        // jumping into the middle splits the program where the jump lands
+        let compiled = compile(&[2, 4, 0, 1, 5, 5, 3, 2]).unwrap();
+        assert_eq!(compiled.blocks.len(), 2);
+        assert_eq!(compiled.blocks[0].exit, Exit::Next(1));
+        assert_eq!(compiled.blocks[1].exit, Exit::Jump { taken: Some(1), otherwise: None });
+        assert_eq!(compile(&[]).unwrap().blocks, vec![]);
+        assert_eq!(compile(&[3, 4, 5, 7]), Err(VmError::ReservedOperand { address: 2 }));
+    }
+
+    #[test]
+    fn compiled_matches_vm_test() {
+        let programs: [&[u8]; 5] = [
+            &[0, 1, 5, 4, 3, 0],
+            &[2, 4, 1, 1, 7, 5, 4, 0, 0, 3, 1, 6, 5, 5, 3, 0],
+            // jnz 3 reads the program from its odd addresses: bxc, jnz 1, bxl 5, bxc, ...
+            &[0, 1, 5, 4, 3, 3, 1, 2, 3, 1],
+            &[2, 4, 0, 1, 5, 5, 3, 2],
+            &[5, 4],
+        ];
+        for program in programs {
+            let compiled = compile(program).unwrap();
+            for a in 0..300 {
+                let register = Register { a, b: 0, c: 0 };
+                let mut vm = Vm::new(program, register).with_step_limit(1000);
+                let expected = vm.run().map(|output| (output, vm.register));
+                assert_eq!(compiled.clone().with_step_limit(1000).run(register), expected, "{:?} with A={}", program, a);
+            }
+        }
+    }
+
+    /// Random programs from random registers; only those that compile are compared, as `compile`
+    /// rejects a reserved operand anywhere while the interpreter only minds one it reaches.
+    #[test]
+    fn compiled_agrees_with_vm_test() {
+        let random_program = |rng: &mut Rng| {
+            let program = (0..rng.between(0, 16)).map(|_| rng.below(8) as u8).collect();
+            (program, [(); 3].map(|_| rng.below(1 << 20) as u64))
+        };
+        let valid = |(program, _): &(Vec<u8>, [u64; 3])| program.iter().all(|&digit| digit < 8) && compile(program).is_ok();
+        let register = |&[a, b, c]: &[u64; 3]| Register { a, b, c };
+        check::agree(
+            2000,
+            random_program,
+            valid,
+            |(program, registers)| {
+                let mut vm = Vm::new(program, register(registers)).with_step_limit(1000);
+                vm.run().map(|output| (output, vm.register))
+            },
+            |(program, registers)| compile(program).unwrap().with_step_limit(1000).run(register(registers)),
+        );
+    }
+
+    #[test]
+    fn compiled_step_limit_test() {
+        let compiled = compile(&[3, 0]).unwrap().with_step_limit(100);
+        assert_eq!(compiled.run(Register { a: 1, b: 0, c: 0 }), Err(VmError::StepLimit { limit: 100 }));
+        let compiled = compile(&[5, 4]).unwrap().with_step_limit(1);
+        assert_eq!(compiled.run(Register { a: 1, b: 0, c: 0 }), Ok((vec![1], Register { a: 1, b: 0, c: 0 })));
+    }
+}
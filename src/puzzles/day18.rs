@@ -1,32 +1,22 @@
 // --- Day 18: Reindeer Maze ---
 
-use core::fmt;
 use nom::bytes::complete::tag;
 use nom::character::complete::{digit1, line_ending};
 use nom::combinator::{map_res, opt, recognize};
+use nom::error::{Error, ErrorKind};
 use nom::multi::separated_list1;
 use nom::sequence::{preceded, separated_pair};
 use nom::IResult;
+use std::fmt;
 use std::str::FromStr;
 
-#[derive(Eq, Hash,Debug, Clone, PartialEq, Default)]
-struct Point {
-    x: usize,
-    y: usize,
-}
-impl Point {
-    fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
-    }
-}
-impl fmt::Display for Point {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_fmt(format_args!("{},{}", self.x, self.y))
-    }
-}
+use crate::error::{parse_with, ParseError};
+use crate::grid::{Direction, Grid, Point};
+use crate::search;
+use crate::solution::{param_value, Config, Configured, ParamError, Params, Solution};
+
 #[derive(Debug, Clone, PartialEq, Default)]
 struct Path {
-    distance: Option<i32>,
     location: Point,
     start: bool,
     end: bool,
@@ -35,7 +25,6 @@ struct Path {
 impl Path {
     fn new(location: Point) -> Self {
         Self {
-            distance: None,
             location,
             start: false,
             end: false,
@@ -43,103 +32,55 @@ impl Path {
         }
     }
 }
+/// The memory space, its start and end, and the last byte to fall, if any did.
 #[derive(Debug, Clone, PartialEq, Default)]
-struct Grid (Vec<Vec<Option<Path>>>, Point, Point, Point);
-impl Grid {
+struct MemorySpace (Grid<Option<Path>>, Point, Point, Option<Point>);
+impl MemorySpace {
     fn new(input: &[Point], width: usize, height: usize) -> Self {
         let start = Point::new(0, 0);
         let end = Point::new(width - 1, height - 1);
-        let mut grid: Vec<Vec<Option<Path>>> = vec! {vec! {Default::default(); width}; height};
-        for y in 0..height {
-            for x in 0..width {
-                let point = Point::new(x, y);
-                let is_start = point == start;
-                let is_end = point == end;
-                let mut cell = Path::new(point);
-                cell.distance = if is_start { Some(0) } else { Some(i32::MAX)};
-                cell.start = is_start;
-                cell.end = is_end;
-                grid[y][x] = Some(cell);
-            }
+        let mut grid: Grid<Option<Path>> = Grid::new(width, height, None);
+        for point in grid.points().collect::<Vec<_>>() {
+            let is_start = point == start;
+            let is_end = point == end;
+            let mut cell = Path::new(point);
+            cell.start = is_start;
+            cell.end = is_end;
+            grid[point] = Some(cell);
         }
-        input.iter().for_each(|point| {
-            grid[point.y][point.x] = None;
+        input.iter().for_each(|&point| {
+            grid[point] = None;
         });
         
         Self::populate_neighbours(&mut grid);
-        Self(grid, start, end, input.last().unwrap().clone())
+        Self(grid, start, end, input.last().copied())
     }
-    fn populate_neighbours(maze: &mut Vec<Vec<Option<Path>>>) {
-        let neighbours: Vec<(isize, isize)> = vec![(0, -1), (1, 0), (0, 1), (-1, 0)]; // N,E,S,W
-        let maze_copy = maze.clone();
-        let width = maze_copy[0].len() as isize;
-        let height = maze_copy.len() as isize;
-        for (y, row) in maze.iter_mut().enumerate() {
-            for (x, cell) in row.iter_mut().enumerate() {
-                if let Some(cell) = cell {
-                    let neighbour_cells: Vec<Option<Point>> = neighbours
-                        .iter()
-                        .map(|(dx, dy)| {
-                            let nx = x as isize + dx;
-                            let ny = y as isize + dy;
-                            if nx >= 0 && ny >= 0 && nx < width && ny < height && maze_copy[ny as usize][nx as usize].is_some() {
-                                Some(Point::new(nx as usize, ny as usize))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();// Trait `FromIterator<()>` is not implemented for `Vec<Option<Point>>` 
-                    cell.neighbours = neighbour_cells.try_into().unwrap();
-                }
+    fn populate_neighbours(maze: &mut Grid<Option<Path>>) {
+        let points: Vec<Point> = maze.points().collect();
+        for point in points {
+            let neighbours = Direction::ALL.map(|direction| maze.step(point, direction).filter(|&next| maze[next].is_some())); // N,E,S,W
+            if let Some(cell) = &mut maze[point] {
+                cell.neighbours = neighbours;
             }
         }
     }
-    fn find_shortest_path(&mut self) -> i32 {
-        // let mut visited: HashSet<Point> = HashSet::new();
-        let mut queue: Vec<Point> = Vec::new();
-        let end = { self.2.clone() };
-        let grid = &mut self.0;
-        queue.push(self.1.clone());
-        while let Some(current) = queue.pop() {
-
-            // if visited.contains(&current) {
-            //     continue;
-            // }
-            // visited.insert(current.clone());
-            
-            let ( neighbours, current_distance) = {
-                let current_path = &grid[current.y][current.x].as_ref().unwrap();
-                (current_path.neighbours.clone(),
-                 current_path.distance)
-            };
-            for neighbour in neighbours.into_iter().flatten() {
-                let neighbour_path = &mut grid[neighbour.y][neighbour.x].as_mut().unwrap();
-                let distance = current_distance.unwrap_or(0) + 1;
-                if  neighbour_path.distance.unwrap_or(i32::MAX) > distance {
-                    neighbour_path.distance = Some(distance);
-                    queue.push(neighbour.clone());
-                }
-            }
+    fn find_shortest_path(&self) -> i32 {
+        let (grid, start, end) = (&self.0, self.1, self.2);
+        if grid[start].is_none() {
+            return i32::MAX;
         }
-        grid[end.y][end.x].as_ref().unwrap().distance.unwrap()
+        let neighbours = |point: &Point| grid[*point].as_ref().unwrap().neighbours.into_iter().flatten().map(|next| (next, 1));
+        search::astar(start, neighbours, |point| point.manhattan(end) as i32, |&point| point == end)
+            .map_or(i32::MAX, |(steps, _)| steps)
     }
+    #[cfg(test)]
     fn print(&self) {
-        for row in &self.0 {
-            for cell in row {
-                if let Some(cell) = cell {
-                    if cell.start {
-                        print!("S");
-                    } else if cell.end {
-                        print!("E");
-                    } else {
-                        print!(".");
-                    }
-                } else {
-                    print!("#");
-                }
-            }
-            println!();
-        }
+        print!("{}", self.0.render(|cell| match cell {
+            Some(cell) if cell.start => 'S',
+            Some(cell) if cell.end => 'E',
+            Some(_) => '.',
+            None => '#',
+        }));
     }
 }
 
@@ -150,9 +91,14 @@ fn parse_unsigned(input: &str) -> IResult<&str, usize> {
 
     Ok((i, number))
 }
+/// A byte's coordinates, which lie in the puzzle's memory space.
 fn parse_point(input: &str) -> IResult<&str, Point> {
     let (i, pair) = separated_pair(parse_unsigned, tag(","), parse_unsigned)(input)?;
-    Ok((i, Point {x: pair.0, y: pair.1}))
+    if pair.0 >= SIDE || pair.1 >= SIDE {
+        // a failure, so the byte is reported rather than ending the list early
+        return Err(nom::Err::Failure(Error::new(input, ErrorKind::Verify)));
+    }
+    Ok((i, Point::new(pair.0, pair.1)))
 }
 fn parse_input(input: &str) -> IResult<&str, Vec<Point>> {
     let (input, grid) = separated_list1(line_ending, parse_point)(input)?;
@@ -160,23 +106,85 @@ fn parse_input(input: &str) -> IResult<&str, Vec<Point>> {
     Ok((input, grid))
 }
 
-pub(crate) fn solve(input: String) -> (i32, String) {
-    let (_, input_bytes) = parse_input(&*input).unwrap();
-    let width = 71;
-    let height = 71;
-    let mut grid = Grid::new(&input_bytes[..1024], width, height);
-    let part_1_result = grid.find_shortest_path();
-
-    let mut result = 0;
-    let mut bytes = 1025;
-    let mut last = String::new();
-    while result < i32::MAX {
-        grid = Grid::new(&input_bytes[..bytes], width, height);
-        last = grid.3.to_string();
-        result = grid.find_shortest_path();
-        bytes += 1;
+/// The side of the puzzle's memory space, which no byte falls outside.
+const SIDE: usize = 71;
+
+/// The size of the memory space and how many bytes have fallen by part 1; the example's is
+/// 7 by 7 after 12 bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Fall {
+    width: usize,
+    height: usize,
+    bytes: usize,
+}
+
+impl Default for Fall {
+    fn default() -> Self {
+        Fall { width: SIDE, height: SIDE, bytes: 1024 }
+    }
+}
+
+impl Config for Fall {
+    const KEYS: &'static [&'static str] = &["width", "height", "bytes"];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "width" => self.width = param_value(key, value)?,
+            "height" => self.height = param_value(key, value)?,
+            "bytes" => self.bytes = param_value(key, value)?,
+            _ => return Err(Self::unknown(key)),
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct Day18;
+
+impl Solution for Day18 {
+    type Input = Configured<Vec<Point>, Fall>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_with(input, parse_input).map(Configured::new)
+    }
+
+    /// The space must hold every byte, and no more bytes can fall by part 1 than the input has.
+    fn configure(Configured { input: input_bytes, config }: &mut Self::Input, params: &Params) -> Result<(), ParamError> {
+        config.apply(params)?;
+        let invalid = |key: &str, value: usize| Err(ParamError::Invalid { key: key.to_string(), value: value.to_string() });
+        if config.width == 0 || input_bytes.iter().any(|point| point.x >= config.width) {
+            return invalid("width", config.width);
+        }
+        if config.height == 0 || input_bytes.iter().any(|point| point.y >= config.height) {
+            return invalid("height", config.height);
+        }
+        if config.bytes > input_bytes.len() {
+            return invalid("bytes", config.bytes);
+        }
+        Ok(())
+    }
+
+    fn part1(Configured { input: input_bytes, config }: &Self::Input) -> i32 {
+        let grid = MemorySpace::new(&input_bytes[..config.bytes], config.width, config.height);
+        grid.find_shortest_path()
+    }
+
+    fn part2(Configured { input: input_bytes, config }: &Self::Input) -> Result<Point, NeverBlocked> {
+        let Fall { width, height, .. } = *config;
+        (config.bytes + 1..=input_bytes.len())
+            .find(|&bytes| MemorySpace::new(&input_bytes[..bytes], width, height).find_shortest_path() == i32::MAX)
+            .map(|bytes| input_bytes[bytes - 1])
+            .ok_or(NeverBlocked)
+    }
+}
+
+/// Part 2's failure: the exit is still reachable once every byte has fallen.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NeverBlocked;
+
+impl fmt::Display for NeverBlocked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("no byte cuts off the exit")
     }
-    (part_1_result, last)
 }
 #[cfg(test)]
 mod tests {
@@ -184,13 +192,20 @@ mod tests {
 
     mod integration {
         use super::*;
+        use crate::solution::{run_with, Part, RunError};
+        #[test]
+        fn test_provided_end_to_end() {
+            let params = Params::new(&[("width", "7"), ("height", "7"), ("bytes", "12")]);
+            let answers = run_with::<Day18>(&get_input(), &params).unwrap();
+            assert_eq!((answers.part1.as_deref(), answers.part2.as_deref()), (Some("22"), Some("6,1")));
+        }
         #[test]
         fn test_provided() {
             let input = get_input();
-            let (_, grid) = parse_input(&*input).unwrap();
+            let (_, grid) = parse_input(&input).unwrap();
             let width = 7;
             let height = 7;
-            let mut grid = Grid::new(&grid[..12], width, height);
+            let grid = MemorySpace::new(&grid[..12], width, height);
             let result = grid.find_shortest_path();
             grid.print();
             assert_eq!(result,22);
@@ -198,49 +213,49 @@ mod tests {
         #[test]
         fn test_provided_2() {
             let input = get_input();
-            let (_, input_bytes) = parse_input(&*input).unwrap();
+            let (_, input_bytes) = parse_input(&input).unwrap();
             let width = 7;
             let height = 7;
-            let mut grid = Grid::default();
+            let mut grid = MemorySpace::default();
             let mut result = 0;
             let mut bytes = 13;
             while result < i32::MAX {
-                grid = Grid::new(&input_bytes[..bytes], width, height);
+                grid = MemorySpace::new(&input_bytes[..bytes], width, height);
                 result = grid.find_shortest_path();
                 bytes += 1;
             }
             grid.print();
-            assert_eq!(grid.3, Point::new(6, 1));
+            assert_eq!(grid.3, Some(Point::new(6, 1)));
+        }
+        #[test]
+        fn test_invalid_input() {
+            let input = get_input();
+            let error = Day18::parse(&format!("{}71,0\n", input)).unwrap_err();
+            assert_eq!((error.line, error.column, error.kind), (26, 1, ErrorKind::Verify));
+            for (key, value) in [("width", "0"), ("height", "0"), ("width", "6"), ("bytes", "26")] {
+                let params = Params::new(&[("width", "7"), ("height", "7"), ("bytes", "12"), (key, value)]);
+                assert_eq!(
+                    run_with::<Day18>(&input, &params).unwrap_err(),
+                    RunError::Param(ParamError::Invalid { key: key.to_string(), value: value.to_string() })
+                );
+            }
+        }
+        #[test]
+        fn test_never_blocked() {
+            let params = Params::new(&[("width", "7"), ("height", "7"), ("bytes", "0")]);
+            // with nothing fallen the way is clear
+            assert_eq!(run_with::<Day18>(&get_input(), &params).unwrap().part1.as_deref(), Some("12"));
+            // a byte on the start blocks it from the first
+            let answers = run_with::<Day18>("0,0\n", &params).unwrap();
+            assert_eq!(answers.part2.as_deref(), Some("0,0"));
+            assert_eq!(
+                run_with::<Day18>("1,1\n2,2\n", &params).unwrap_err(),
+                RunError::Solve { part: Part::Two, message: "no byte cuts off the exit".to_string() }
+            );
         }
 
         fn get_input() -> String {
-            "\
-5,4
-4,2
-4,5
-3,0
-2,1
-6,3
-2,4
-1,5
-0,6
-3,3
-2,6
-5,1
-1,2
-5,5
-2,5
-6,5
-1,4
-0,4
-6,4
-1,1
-6,1
-1,0
-0,5
-1,6
-2,0
-".to_string()
+            include_str!("../../examples/day18/example.txt").to_string()
         }
     }
 }
\ No newline at end of file
@@ -1,19 +1,22 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::alpha1;
 use nom::multi::separated_list1;
 use nom::sequence::separated_pair;
 
-fn parse(input: String) -> (Vec<String>, Vec<String>) {
-    let (_input, (available, desired) ) = separated_pair(
-        separated_list1(alt((tag(", \n"),tag(", "))), alpha1::<&str, nom::error::Error<&str>>),
+use crate::error::{parse_with, ParseError};
+use crate::solution::Solution;
+
+fn parse(input: &str) -> Result<(Vec<String>, Vec<String>), ParseError> {
+    let (available, desired) = parse_with(input, separated_pair(
+        separated_list1(alt((tag(", \n"),tag(", "))), alpha1),
         tag("\n \n"),
         separated_list1(tag("\n"), alpha1)
-    )(input.as_str()).unwrap();
+    ))?;
     let available = available.into_iter().map(|x| x.to_string()).collect();
     let desired = desired.into_iter().map(|x| x.to_string()).collect();
-    (available, desired)
+    Ok((available, desired))
 }
 
 fn is_possible(available: &[String], desired: &str) -> bool {
@@ -34,18 +37,35 @@ fn is_possible(available: &[String], desired: &str) -> bool {
         if desired.is_empty() {
             return true;
         }
-        for x in available {
-            if desired.starts_with(x) {
-                queue.push(&desired[x.len()..]);
-            }
-        }
+        queue.extend(remainders(available, desired));
     }
     false
 }
 
-fn part_2(available: &[String], desired: &[String]) -> String {
+/// What is left of `desired` after each available towel that it starts with.
+fn remainders<'a>(available: &'a [String], desired: &'a str) -> impl Iterator<Item = &'a str> {
+    available.iter().filter_map(move |x| desired.strip_prefix(x.as_str()))
+}
+
+/// The number of ways to build `desired` from the available towels, memoised by suffix.
+fn count_arrangements<'a>(available: &'a [String], desired: &'a str, memo: &mut HashMap<&'a str, u64>) -> u64 {
+    if desired.is_empty() {
+        return 1;
+    }
+    if let Some(&count) = memo.get(desired) {
+        return count;
+    }
+    let count = remainders(available, desired)
+        .map(|rest| count_arrangements(available, rest, memo))
+        .sum();
+    memo.insert(desired, count);
+    count
+}
 
-    "bob".to_string()
+fn part_2(available: &[String], desired: &[String]) -> String {
+    // suffixes are shared between designs, so one memo serves them all
+    let mut memo = HashMap::new();
+    desired.iter().map(|x| count_arrangements(available, x, &mut memo)).sum::<u64>().to_string()
 }
 
 fn part_1(available: &[String], desired: &[String]) -> String {
@@ -53,12 +73,22 @@ fn part_1(available: &[String], desired: &[String]) -> String {
     desired_list.iter().filter(|&x| is_possible(available, x)).count().to_string()
 }
 
-pub(crate) fn solve(input: String) -> (String, String) {
-    let (available, desired) = parse(input);
-    let part_1_result = part_1(&available, &desired);
-    let part_2_result = part_2(&available, &desired);
+pub(crate) struct Day19;
+
+impl Solution for Day19 {
+    type Input = (Vec<String>, Vec<String>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1((available, desired): &Self::Input) -> String {
+        part_1(available, desired)
+    }
 
-    (part_1_result.to_string(), part_2_result.to_string())
+    fn part2((available, desired): &Self::Input) -> String {
+        part_2(available, desired)
+    }
 }
 
 
@@ -66,17 +96,7 @@ pub(crate) fn solve(input: String) -> (String, String) {
 mod tests {
     use super::*;
     fn input () -> String {
-        "r, wr, b, g, 
-bwu, rb, gb, br
- 
-brwrr
-bggr
-gbbr
-rrbgbr
-ubwu
-bwurrg
-bbrgwb
-brgr".to_string()
+        include_str!("../../examples/day19/example.txt").to_string()
     }
     fn awkward_input() -> String {
         "rug, uugbb, bubbbr, bbubw, wwb, wbw, gwbbuw, ubg, ruwug, bbwuww, wubwrw, ug, ugu, ggrbg, ruw, rbur, uuubbuu, wb, wurrbgr, rwruwwuu, rwg, rwbb, bwugugg, bur, ggwub, brgbr, guuu, wgg, bbw, ugrbrb, ggrw, wbgu, rwgbg, ggwu, wurwuw, gruub, gugw, bgu, wrr, rbrg, bww, uggwrr, rgg, uugb, bbrwuru, rw, urbu, ggggg, ru, wrrgwrrb, uurrgur, bgrw, ubuwr, gwwgug, bggb, uwub, gwu, wrwbgw, rrbb, uuwrggb, uuugw, bbbubg, bg, ggr, rgw, ugburuw, ggbbww, rrgbru, wurg, wugu, rbuu, wgrg, ubugu, uuwuur, ur, wgbbg, wubu, gbrrwgu, bwbu, wbgguwg, rrwbwru, buggggg, bwu, uw, grubg, bggbw, ugwwgwu, rbubgwg, wgwuu, rrr, uubwwgrr, bbwguw, uuru, wgww, grbwu, gbgww, uuu, gruwwg, urgw, ubu, ggbgb, buwur, bbgb, bub, wbru, gbug, wgrruub, wrwwru, wuwgg, uwg, uuwgw, rrruwb, rbr, grrrugwb, rbgb, wwbr, ggbugg, ggg, buu, uwr, guug, wg, wbwu, wgb, rbbb, rub, wru, bubr, rgruwrgr, gwr, urru, wuw, uuwg, bbr, ruwg, urwgu, bwbugbb, wgbgrwgg, grwb, rwgg, uugg, uww, w, urwgrgb, gw, ubrb, grbrrbrw, wgwgrr, rggurb, buuww, bgrrb, wbrg, ggburu, rgwgb, wrw, bw, wubbb, bbwww, ubbg, bbg, ruururru, rbg, wgug, gur, rrugw, gwurbg, gr, bwwgrr, gub, wrgwrw, gbrrb, rr, rgbu, bbrur, ubgbu, wuwrw, guub, guu, uwbww, gguuww, ubuw, uuggu, wgwg, grwrr, bwg, uwgrb, grwu, gwbw, rwu, buubrb, ggbubw, wrgw, wwu, uuubwrgb, urwgbwu, rwggwrg, uuuw, gbrwb, bbuu, urgbgb, www, guw, uuw, wbubg, brw, gubruu, uwrg, bbbggg, bwr, wgbg, wguw, rbbgruru, uubgggr, ugw, grgugr, uugrrbb, rwgwg, ubgu, burgwgbw, gwrubbu, bwuug, bburur, burwu, wbb, ub, wr, ggubww, grw, ww, buw, urbru, gbgr, burwgur, ugr, gwwbgb, urr, wuwg, bbubrrgu, wuuwb, brrwg, g, gbgurrw, rgu, ubb, urggug, wub, gwubu, bgrr, wrbwwb, guuuuug, rrb, brugwu, wgrgwu, bru, wrbgugur, ubww, uur, uuub, wubwrb, uubggur, bbu, urb, rrggwuw, brgw, urbrrg, uwuur, bubw, bbwuu, gbu, rrg, rrgbw, gbr, wbu, ubrw, rww, ubw, wugr, rrur, bggu, uurrbwr, br, ggu, bgww, gwbbubgu, grwbw, uu, wguwg, urwubg, rruwg, wur, rgbwg, wbrgbg, ugrr, wbbggu, gww, bugb, wrbr, grurrg, gbrgwb, rur, gubbr, gbb, gggubwgu, gwbbbwru, rbbbu, rg, gwg, bgg, rgbg, uubbrwb, uwur, rwr, uwu, wbg, wwwbgurr, grb, gbg, rugg, wgub, bb, bgb, ubwbrww, gugbr, wwgwwwug, brb, rbb, uwgg, ubbbw, rwwr, urrw, ubuuub, wbub, ugbrr, ubrr, bgwr, gwb, wuuw, ggbuwb, wug, wgugr, urgrb, uwbbwr, gwgb, uug, gbur, wwr, gug, rgbubw, ruu, gbbw, grg, ggruwgb, urwggu, rrw, rgguuu, bgwg, uub, rbu, bu, grrr, r, ugb, bbrb, gwwbr, bwwur, wwg, grurrgb, wrguuu, rgggw, ugbwg, rwb, wubgw, grrww, uwb, bgw, bggrw, rgr, rbwg, buugbbb, bgr, gbgrgr, wbr, wgr, wbbr, gg, gru, urg, bguu, bbwbgur, bburgw, ubr, wrg, gu, rbw, rwbrur, bwgru, ubwrugb, uggubw, rgbr, bbgg, wrb, wwuug, bwbuw, rb, gwugbr, uuwrw, u, rburg, wguwgwu, bwrru, uguwgb, wuu, ugg, bgrg, wrrw, brg, rwwru, buuwg, ubuuwwr, ruwr, bbb, guwuwuug, rrbgg, ugwub, rrwwugb, bug, wgw, urw, brr, bwuwrw, bbug, rru, brbubb, bwb, ggbr, rgb, gwrwb, grbgw, rrwuruw, wruuwgrb, brruubu, bgwbu, grr, ubru, rbrr, wubwuub, grbr
@@ -93,17 +113,21 @@ wwgwuurwwrbrwbruugbrbubwrwgbbrruwurrbbugwbr
     }
     #[test]
     fn parse_test() {
-        let (available, desired) = parse(input());
+        let (available, desired) = parse(&input()).unwrap();
         assert_eq!(available, vec!["r", "wr", "b", "g", "bwu", "rb", "gb", "br"]);
         assert_eq!(desired, vec!["brwrr", "bggr", "gbbr", "rrbgbr", "ubwu", "bwurrg", "bbrgwb", "brgr"]);
     }
     #[test]
+    fn parse_trailing_line_test() {
+        let error = parse(&format!("{}4bw\n", input())).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (12, 1, "4bw"));
+    }
+    #[test]
     fn awkward_input_parse_test() {
-        let (available, desired) = parse(awkward_input());
+        let (available, desired) = parse(&awkward_input()).unwrap();
         assert_eq!(available.len(), 447);
-        assert_eq!(desired.len(), 15);
-        println!("awkward_input_parse_test available: {:#?}", available);
-        println!("awkward_input_parse_test desired: {:#?}", desired);
+        // the eight designs after the line holding a lone space
+        assert_eq!(desired.len(), 8);
     }
     mod is_possible_tests {
         use super::*;
@@ -111,33 +135,33 @@ wwgwuurwwrbrwbruugbrbubwrwgbbrruwurrbbugwbr
         fn possible_simple_test() {
             let available: Vec<String> = vec!["r", "wr"].into_iter().map(|x| x.to_string()).collect();
             let desired = "rwrr";
-            assert_eq!(is_possible(&available, desired), true);
+            assert!(is_possible(&available, desired));
         }
 
         #[test]
         fn possible_test() {
             let available: Vec<String> = vec!["r", "wr", "b", "g", "bwu", "rb", "gb", "br"].into_iter().map(|x| x.to_string()).collect();
             let desired = "brwrrbwurbgb";
-            assert_eq!(is_possible(&available, desired), true);
+            assert!(is_possible(&available, desired));
         }
 
         #[test]
         fn impossible() {
             let available: Vec<String> = vec!["r", "wr", "b", "g", "bwu", "rb", "gb", "br"].into_iter().map(|x| x.to_string()).collect();
             let desired = "brwrrrbwb";
-            assert_eq!(is_possible(&available, desired), false);
+            assert!(!is_possible(&available, desired));
         }
 
         #[test]
         fn exact_match() {
             let available: Vec<String> = vec!["rbwr"].into_iter().map(|x| x.to_string()).collect();
             let desired = "rbwr";
-            assert_eq!(is_possible(&available, desired), true);
+            assert!(is_possible(&available, desired));
         }
 
         #[test]
         fn awkward() {
-            let (available, desired) = parse(awkward_input());
+            let (available, desired) = parse(&awkward_input()).unwrap();
             assert_eq!(part_1(&available,&desired), "7");
         }
     }
@@ -145,8 +169,33 @@ wwgwuurwwrbrwbruugbrbubwrwgbbrruwurrbbugwbr
         use super::*;
         #[test]
         fn part_1_test() {
-            let (available, desired) = parse(input());
+            let (available, desired) = parse(&input()).unwrap();
             assert_eq!(part_1(&available, &desired), "6");
         }
     }
+    mod part_2_tests {
+        use super::*;
+        #[test]
+        fn count_arrangements_test() {
+            let (available, _) = parse(&input()).unwrap();
+            let mut memo = HashMap::new();
+            assert_eq!(count_arrangements(&available, "brwrr", &mut memo), 2);
+            assert_eq!(count_arrangements(&available, "gbbr", &mut memo), 4);
+            assert_eq!(count_arrangements(&available, "rrbgbr", &mut memo), 6);
+            assert_eq!(count_arrangements(&available, "ubwu", &mut memo), 0);
+        }
+        #[test]
+        fn part_2_test() {
+            let (available, desired) = parse(&input()).unwrap();
+            assert_eq!(part_2(&available, &desired), "16");
+        }
+        #[test]
+        fn awkward() {
+            let (available, desired) = parse(&awkward_input()).unwrap();
+            let mut memo = HashMap::new();
+            let possible = desired.iter().filter(|x| count_arrangements(&available, x, &mut memo) > 0).count();
+            assert_eq!(possible, 7);
+            assert_eq!(part_2(&available, &desired), "7214675862718");
+        }
+    }
 }
@@ -1,9 +1,14 @@
 use nom::{
     character::complete::{digit1, space1},
-    combinator::map_res,
+    combinator::{all_consuming, map_res},
     multi::separated_list1,
     IResult,
 };
+use log::debug;
+
+use crate::error::ParseError;
+use crate::solution::Solution;
+
 fn parse_number(input: &str) -> IResult<&str, i32> {
     map_res(digit1, |digit_str: &str| digit_str.parse::<i32>())(input)
 }
@@ -11,73 +16,59 @@ fn parse_line(input: &str) -> IResult<&str, Vec<i32>> {
     separated_list1(space1, parse_number)(input)
 }
 
-pub(crate) fn test() {
-    let input = "7 6 4 2 1
-1 2 7 8 9
-9 7 6 2 1
-1 3 2 4 5
-8 6 4 4 1
-1 3 6 7 9";
-    solve(input.to_string());
-    solve("100 1 2 3 4 5 6".to_string());
-    solve("1 1 2 3 4 5 6".to_string());
-    solve("1 2 3 3 4 5 6".to_string());
-    solve("1 2 3 4 5 6 0".to_string());
-    solve("1 2 3 7 5 6".to_string());
-}
+pub(crate) struct Day2;
 
-pub(crate) fn solve(input: String) {
-    let mut count_part1 = 0;
-    let mut count_part2 = 0;
-    for line in input.lines() {
-        let (_, numbers) = parse_line(line).expect("Failed to parse line");
-        if safe_part1(&numbers) {
-            count_part1 += 1;
-        }
-        if safe_part2(&numbers) {
-            count_part2 += 1;
-        }
+impl Solution for Day2 {
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input
+            .lines()
+            .map(|line| all_consuming(parse_line)(line).map(|(_, numbers)| numbers).map_err(|error| ParseError::from_nom(input, error)))
+            .collect()
     }
 
-    let part1: i32 = count_part1;
-    let part2: i32 = count_part2;
+    fn part1(reports: &Self::Input) -> usize {
+        reports.iter().filter(|numbers| safe_part1(numbers)).count()
+    }
 
-    println!("part1: {}, part2: {}", part1, part2);
+    fn part2(reports: &Self::Input) -> usize {
+        reports.iter().filter(|numbers| safe_part2(numbers)).count()
+    }
 }
 
-fn safe_part1(numbers: &Vec<i32>) -> bool {
+fn safe_part1(numbers: &[i32]) -> bool {
     let mut index = 1;
 
     let mut difference = (numbers[index] - numbers[index - 1]).abs();
-    let increasing = list_increasing(&numbers);
+    let increasing = list_increasing(numbers);
     let mut direction = numbers[index] > numbers[index - 1];
     while difference <= 3 && difference > 0 && direction == increasing && index < numbers.len() {
         difference = (numbers[index] - numbers[index - 1]).abs();
         direction = numbers[index] > numbers[index - 1];
         index += 1;
     }
-    let is_safe = difference <= 3 && difference > 0 && direction == increasing;
-    is_safe
+    difference <= 3 && difference > 0 && direction == increasing
 }
 
-fn safe_part2(numbers: &Vec<i32>) -> bool {
-    if safe_part1(&numbers) {
+fn safe_part2(numbers: &[i32]) -> bool {
+    if safe_part1(numbers) {
         return true;
     }
 
     for i in 0..numbers.len() {
-        let mut modified_numbers = numbers.clone();
+        let mut modified_numbers = numbers.to_vec();
         modified_numbers.remove(i);
         if safe_part1(&modified_numbers) {
             return true;
         }
     }
 
-    println!("unsafe: {:?}", numbers);
+    debug!("unsafe: {:?}", numbers);
     false
 }
 
-fn list_increasing(numbers: &Vec<i32>) -> bool {
+fn list_increasing(numbers: &[i32]) -> bool {
     let mut increasing = 0;
     let mut decreasing = 0;
     let mut i = 1;
@@ -1,10 +1,15 @@
-use nom::character::complete::{line_ending, one_of};
-use nom::multi::{many1, separated_list1};
-use nom::IResult;
 use num::abs;
+use log::debug;
+
+use nom::error::ErrorKind;
+
+use crate::error::ParseError;
+use crate::grid::{text_at, Direction, Grid, Point};
+use crate::search;
+use crate::solution::{param_value, Config, Configured, ParamError, Params, Solution};
 
 #[derive(Debug, Clone, PartialEq, Default)]
-struct Path {
+pub(crate) struct Path {
     distance: i32,
     location: Point,
     start: bool,
@@ -40,115 +45,67 @@ impl Path {
         }
     }
 }
-#[derive(Debug, Clone, PartialEq, Default)]
-#[derive(Eq, Hash)]
-struct Point {
-    x: usize,
-    y: usize,
-}
-impl Point {
-    fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
-    }
-}
-
-fn parse_input(input: &str) -> IResult<&str, Vec<Vec<char>>> {
-    let (input, grid) = separated_list1(line_ending, many1(one_of("#.ES")))(input)?;
-    let (input, _) = line_ending(input)?;
-    Ok((input, grid))
+fn parse_input(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse(input, |c| matches!(c, '#' | '.' | 'E' | 'S').then_some(c))
 }
 
-fn map_input(input: Vec<Vec<char>>) -> (Vec<Vec<Option<Path>>>, Point, Point) {
-    let mut paths = Vec::new();
+fn map_input(input: &Grid<char>) -> (Grid<Option<Path>>, Point, Point) {
+    let mut paths = Grid::new(input.width(), input.height(), None);
     let mut start: Point = Default::default();
     let mut end: Point = Default::default();
-    for (y, row) in input.iter().enumerate() {
-        let mut path_row = Vec::new();
-        for (x, cell) in row.iter().enumerate() {
-            let path = match cell {
-                '#' => None,
-                '.' => Some(Path::new(Point::new(x, y))),
-                'E' => Some(Path::end(Point::new(x, y))),
-                'S' => Some(Path::start(Point::new(x, y))),
-                _ => panic!("Invalid cell"),
-            };
-            if path.is_some() && path.as_ref().unwrap().start {
-                start = path.as_ref().unwrap().location.clone();
-            }
-            if path.is_some() && path.as_ref().unwrap().end {
-                end = path.as_ref().unwrap().location.clone();
-            }
-            path_row.push(path);
+    for (point, cell) in input.iter() {
+        let path = match cell {
+            '#' => None,
+            '.' => Some(Path::new(point)),
+            'E' => Some(Path::end(point)),
+            'S' => Some(Path::start(point)),
+            _ => unreachable!("parse_input only accepts #.ES"),
+        };
+        if let Some(path) = path.as_ref().filter(|path| path.start) {
+            start = path.location;
         }
-        paths.push(path_row);
+        if let Some(path) = path.as_ref().filter(|path| path.end) {
+            end = path.location;
+        }
+        paths[point] = path;
     }
     (paths, start, end)
 }
-fn populate_neighbours(maze: &mut Vec<Vec<Option<Path>>>) {
-    let neighbours: Vec<(isize, isize)> = vec![(0,-1),(1,0),(0,1),(-1,0)]; // N,E,S,W
-    let maze_copy = maze.clone();
-    for (y, row) in maze.iter_mut().enumerate() {
-        for (x, cell) in row.iter_mut().enumerate() {
-            if let Some(cell) = cell {
-                let neighbour_cells = neighbours
-                    .iter()
-                    .map(|(dx, dy)|&maze_copy[(y as isize + dy) as usize][(x as isize + dx) as usize] )
-                    .map(|cell| cell.as_ref().map(|c| c.location.clone()))
-                    .collect::<Vec<Option<Point>>>();
-                cell.neighbours = neighbour_cells.try_into().unwrap();
-            }
+fn populate_neighbours(maze: &mut Grid<Option<Path>>) {
+    let points: Vec<Point> = maze.points().collect();
+    for point in points {
+        let neighbours = Direction::ALL.map(|direction| maze.step(point, direction).filter(|&next| maze[next].is_some())); // N,E,S,W
+        if let Some(cell) = &mut maze[point] {
+            cell.neighbours = neighbours;
         }
     }
 }
-fn find_shortest_path( grid: &mut Vec<Vec<Option<Path>>>, start:Point, end :Point) -> i32 {
-    // let mut visited: HashSet<Point> = HashSet::new();
-    let mut queue: Vec<Point> = Vec::new();
-    queue.push(start);
-    while let Some(current) = queue.pop() {
-        
-        let ( neighbours, current_distance) = {
-            let current_path = &grid[current.y][current.x].as_ref().unwrap();
-            (current_path.neighbours.clone(),
-             current_path.distance)
-        };
-        for neighbour in neighbours.into_iter().flatten() {
-            let neighbour_path = &mut grid[neighbour.y][neighbour.x].as_mut().unwrap();
-            let distance = current_distance + 1;
-            if  neighbour_path.distance > distance {
-                neighbour_path.distance = distance;
-                queue.push(neighbour.clone());
-            }
-        }
+fn find_shortest_path(grid: &mut Grid<Option<Path>>, start: Point, end: Point) -> i32 {
+    let steps = search::bfs(start, |&point| grid[point].as_ref().unwrap().neighbours.into_iter().flatten());
+    for (point, distance) in steps {
+        grid[point].as_mut().unwrap().distance = distance as i32;
     }
-    grid[end.y][end.x].as_ref().unwrap().distance
+    grid[end].as_ref().unwrap().distance
 }
 
 
-fn part_1_deprecated(maze: &[Vec<Option<Path>>], required_saving: i32) -> i32 {
-    let neighbours: Vec<(isize, isize)> = vec![(0, -1), (1, 0), (0, 1), (-1, 0)]; // N,E,S,W
-    let width = maze[0].len() as isize;
-    let height = maze.len() as isize;
+#[allow(dead_code)]
+fn part_1_deprecated(maze: &Grid<Option<Path>>, required_saving: i32) -> i32 {
     let mut count = 0;
-    for (y, row) in maze.iter().enumerate() {
-        for (x, cell) in row.iter().enumerate() {
-            if cell.is_none() {
-                for i in 0..3 {
-                    for j in (i + 1)..4 {
-                        let ny = y as isize + neighbours[i].1;
-                        let nx = x as isize + neighbours[i].0;
-                        let ny2 = y as isize + neighbours[j].1;
-                        let nx2 = x as isize + neighbours[j].0;
-
-                        if ny >= 0 && nx >= 0 && ny < height && nx < width && ny2 >= 0 && nx2 >= 0 && ny2 < height && nx2 < width {
-                            let cell1 = &maze[ny as usize][nx as usize];
-                            let cell2 = &maze[ny2 as usize][nx2 as usize];
-                            if cell1.is_some() && cell2.is_some() {
-                                let distance1 = &maze[ny as usize][nx as usize].as_ref().unwrap().distance;
-                                let distance2 = &maze[ny2 as usize][nx2 as usize].as_ref().unwrap().distance;
-                                if abs(distance1 - distance2) > required_saving + 1{
-                                    count += 1;
-                                }
-                            }
+    for (point, cell) in maze.iter() {
+        if cell.is_none() {
+            for i in 0..3 {
+                for j in (i + 1)..4 {
+                    let cell1 = maze.step(point, Direction::ALL[i]).and_then(|next| maze[next].as_ref());
+                    let cell2 = maze.step(point, Direction::ALL[j]).and_then(|next| maze[next].as_ref());
+                    if let (Some(cell1), Some(cell2)) = (cell1, cell2) {
+                        // a diagonal cheat can pass through either corner; count it at only one of them
+                        let other_corner = maze.step(cell1.location, Direction::ALL[j]).filter(|&corner| corner != point);
+                        if other_corner.is_some_and(|corner| maze[corner].is_none() && corner < point) {
+                            continue;
+                        }
+                        if abs(cell1.distance - cell2.distance) > required_saving + 1{
+                            count += 1;
                         }
                     }
                 }
@@ -157,44 +114,37 @@ fn part_1_deprecated(maze: &[Vec<Option<Path>>], required_saving: i32) -> i32 {
     }
     count
 }
-fn part_1(maze: &[Vec<Option<Path>>], required_saving: i32) -> i32 {
+fn part_1(maze: &Grid<Option<Path>>, required_saving: i32) -> i32 {
     part_2(maze, required_saving, 2)
 }
-fn part_2(maze: &[Vec<Option<Path>>], required_saving: i32, cheats: isize) -> i32 {
-    let width = maze[0].len();
-    let height = maze.len();
-    let mut visited = vec!{vec!{false; width}; height};
+fn part_2(maze: &Grid<Option<Path>>, required_saving: i32, cheats: isize) -> i32 {
+    let mut visited = Grid::new(maze.width(), maze.height(), false);
     let mut count = 0;
-    for (y, row) in maze.iter().enumerate() {
-        for (x, cell) in row.iter().enumerate() {
-            if cell.is_some() {
-                let path = cell.as_ref().unwrap();
-                visited[y][x] = true;
-                for dx in -cheats..=cheats {
-                    for dy in -cheats..=cheats {
-                        if dx == 0 && dy == 0 {
-                            continue;
-                        }
-                        if dx.abs() + dy.abs() > cheats {
+    for (point, cell) in maze.iter() {
+        if let Some(path) = cell {
+            visited[point] = true;
+            for dx in -cheats..=cheats {
+                for dy in -cheats..=cheats {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    if dx.abs() + dy.abs() > cheats {
+                        continue;
+                    }
+                    let Some(next) = point.offset(dx, dy) else {
+                        continue;
+                    };
+                    if let Some(Some(cell)) = maze.get(next) {
+                        if visited[next] {
                             continue;
                         }
-                        let nx = x as isize + dx;
-                        let ny = y as isize + dy;
-                        if nx >= 0 && ny >= 0 && nx < width as isize && ny < height as isize {
-                            let cell = &maze[ny as usize][nx as usize];
-                            if cell.is_some() {
-                                if visited[ny as usize][nx as usize] {
-                                    continue;
-                                }
-                                
-                                let distance = cell.as_ref().unwrap().distance;
-                                let saving = abs(distance - path.distance);
-                                let cost: i32 = (dx.abs() + dy.abs()) as i32;
-                                if saving - cost >= required_saving {
-                                    count += 1;
-                                    // println!("{} saving: {} cost: {} dx: {} dy: {} x: {} y: {} nx: {} ny: {}", count, saving, cost, dx, dy, x, y, nx, ny);
-                                }
-                            }
+
+                        let distance = cell.distance;
+                        let saving = abs(distance - path.distance);
+                        let cost = point.manhattan(next) as i32;
+                        if saving - cost >= required_saving {
+                            count += 1;
+                            // println!("{} saving: {} cost: {} dx: {} dy: {} x: {} y: {} nx: {} ny: {}", count, saving, cost, dx, dy, x, y, nx, ny);
                         }
                     }
                 }
@@ -203,26 +153,132 @@ fn part_2(maze: &[Vec<Option<Path>>], required_saving: i32, cheats: isize) -> i3
     }
     count
 }
-fn prep_input(input: &str) -> Vec<Vec<Option<Path>>> {
-    let (_, grid) = parse_input(&input).unwrap();
-    let (maze, start, end) = map_input(grid);
+/// The racetrack with each cell's distance from the start; it needs a start and an end, with
+/// a way from one to the other.
+fn prep_input(input: &str) -> Result<Grid<Option<Path>>, ParseError> {
+    let grid = parse_input(input)?;
+    if grid.position(|&c| c == 'S').is_none() || grid.position(|&c| c == 'E').is_none() {
+        return Err(ParseError::at(input, &input[input.len()..], ErrorKind::Char));
+    }
+    let (maze, start, end) = map_input(&grid);
     let mut maze = maze;
     populate_neighbours(&mut maze);
-    println!("shortest: {}",find_shortest_path(&mut maze, start, end));
-    maze
+    let shortest = find_shortest_path(&mut maze, start, end);
+    if shortest == i32::MAX {
+        return Err(ParseError::at(input, text_at(input, end), ErrorKind::Verify));
+    }
+    debug!("shortest: {}", shortest);
+    Ok(maze)
+}
+/// The least a cheat must save to count, and how long a part 2 cheat may last.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Cheats {
+    saving: i32,
+    cheat: isize,
+}
+
+impl Default for Cheats {
+    fn default() -> Self {
+        Cheats { saving: 100, cheat: 20 }
+    }
 }
-pub fn solve(input: String) -> (i32, i32) {
 
-    let maze = prep_input(&input);
-    let part_1_result = part_1(&maze, 100);
-    let part_2_result = part_2(&maze, 100, 20); // 7 647 is too low. // 582991 is too low. // 555710 :(
+impl Config for Cheats {
+    const KEYS: &'static [&'static str] = &["saving", "cheat"];
 
-    (part_1_result, part_2_result)
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "saving" => self.saving = param_value(key, value)?,
+            "cheat" => self.cheat = param_value(key, value)?,
+            _ => return Err(Self::unknown(key)),
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct Day20;
+
+impl Solution for Day20 {
+    type Input = Configured<Grid<Option<Path>>, Cheats>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        prep_input(input).map(Configured::new)
+    }
+
+    fn configure(input: &mut Self::Input, params: &Params) -> Result<(), ParamError> {
+        input.config.apply(params)
+    }
+
+    fn part1(Configured { input: maze, config }: &Self::Input) -> i32 {
+        part_1(maze, config.saving)
+    }
+
+    fn part2(Configured { input: maze, config }: &Self::Input) -> i32 {
+        part_2(maze, config.saving, config.cheat) // 7 647 is too low. // 582991 is too low. // 555710 :(
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::check;
+    use crate::generate::Rng;
+
+    /// Which cells are open, row by row, inside a wall all round, and the least saving to count.
+    /// The race starts at the first open cell and ends at the last.
+    type Case = (Vec<Vec<bool>>, u8);
+
+    fn random_track(rng: &mut Rng) -> Case {
+        let (width, height) = (rng.between(2, 8), rng.between(2, 8));
+        let open = (0..height).map(|_| (0..width).map(|_| rng.chance(65)).collect()).collect();
+        (open, rng.between(1, 10) as u8)
+    }
+
+    fn open_cells(open: &[Vec<bool>]) -> Grid<bool> {
+        Grid::from_rows(open.to_vec()).expect("rows are the same length")
+    }
+
+    /// A rectangle whose open cells, at least two of them, are all connected.
+    fn valid((open, saving): &Case) -> bool {
+        let Some(cells) = open.first().filter(|row| !row.is_empty()).and_then(|_| Grid::from_rows(open.clone())) else {
+            return false;
+        };
+        let Some(start) = cells.position(|&cell| cell) else {
+            return false;
+        };
+        let reached = search::bfs(start, |&point| cells.neighbours(point).filter(|&next| cells[next]).collect::<Vec<_>>());
+        *saving > 0 && reached.len() > 1 && reached.len() == cells.iter().filter(|(_, &cell)| cell).count()
+    }
+
+    fn maze(open: &[Vec<bool>]) -> Grid<Option<Path>> {
+        let cells = open_cells(open);
+        let track: Vec<Point> = cells.points().filter(|&point| cells[point]).collect();
+        let mut text = Grid::new(cells.width() + 2, cells.height() + 2, '#');
+        for &point in &track {
+            let tile = if point == track[0] { 'S' } else if Some(&point) == track.last() { 'E' } else { '.' };
+            text[Point::new(point.x + 1, point.y + 1)] = tile;
+        }
+        prep_input(&text.to_string()).unwrap()
+    }
+
+    #[test]
+    fn part_1_agrees_with_part_1_deprecated_test() {
+        check::agree(
+            1500,
+            random_track,
+            valid,
+            |(open, saving)| part_1_deprecated(&maze(open), *saving as i32),
+            |(open, saving)| part_1(&maze(open), *saving as i32),
+        );
+    }
+
+    #[test]
+    fn parse_track_test() {
+        let error = prep_input("#####\n#S#E#\n#####\n").unwrap_err();
+        assert_eq!((error.line, error.column, error.kind), (2, 4, ErrorKind::Verify));
+        assert_eq!(prep_input("####\n#S.#\n####\n").unwrap_err().kind, ErrorKind::Char);
+        assert_eq!(prep_input("####\n#.E#\n####\n").unwrap_err().kind, ErrorKind::Char);
+    }
     
     mod part_1_tests {
         use super::*;
@@ -232,43 +288,43 @@ mod tests {
             let mut grid;
             let start:Point;
             let end:Point;
-            let (_, chars) = parse_input(input).unwrap();
-            (grid, start, end) = map_input( chars);
+            let chars = parse_input(input).unwrap();
+            (grid, start, end) = map_input(&chars);
             populate_neighbours(&mut grid);
             let shortest_path= find_shortest_path(&mut grid, start, end);
             assert_eq!(shortest_path, 84);
         }
         #[test]
         fn provided_1_64_test() {
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             let part_1_result = part_1(&maze, 64);
             assert_eq!(part_1_result, 1);
         }
 
         #[test]
         fn provided_2_40_test() {
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             let part_1_result = part_1(&maze, 40);
             assert_eq!(part_1_result, 2);
         }
 
         #[test]
         fn provided_5_20_test() {
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             let part_1_result = part_1(&maze, 20);
             assert_eq!(part_1_result, 5);
         }
 
         #[test]
         fn provided_10_10_test() {
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             let part_1_result = part_1(&maze, 10);
             assert_eq!(part_1_result, 10);
         }
 
         #[test]
         fn provided_16_6_test() {
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             let part_1_result = part_1(&maze, 6);
             assert_eq!(part_1_result, 16);
         }
@@ -287,13 +343,13 @@ mod tests {
 
         #[test]
         fn provided_lots_4_test() {
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             assert_eq!(part_1(&maze, 4), 30); // 63?
         }
         
         #[test]
         fn provided_lots_2_test() {
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             assert_eq!(part_1(&maze, 2), 44); // 63?
         }
     }
@@ -301,59 +357,48 @@ mod tests {
         use super::*;
         #[test]
         fn provided_76_6_1_test() { // This six-picosecond cheat saves 76 picoseconds
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             assert_eq!(part_2(&maze, 76, 6), 1);
         }
         #[test]
         fn provided_76_20_3_test() { 
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             assert_eq!(part_2(&maze, 76, 20), 3);
         }
         #[test]
         fn provided_74_20_7_test() { 
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             assert_eq!(part_2(&maze, 74, 20), 3 + 4);
         }
         #[test]
         fn provided_72_20_29_test() { 
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             assert_eq!(part_2(&maze, 72, 20), 3 + 4 + 22);
         }
         #[test]
         fn provided_70_20_41_test() {
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             assert_eq!(part_2(&maze, 70, 20), 3 + 4 + 22 + 12);
         }
         #[test]
         fn provided_68_20_test() {
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             assert_eq!(part_2(&maze, 68, 20), 3 + 4 + 22 + 12 + 14);
         }
         #[test]
         fn provided_66_20_test() {
-            let maze = prep_input(&get_input());
+            let maze = prep_input(get_input()).unwrap();
             assert_eq!(part_2(&maze, 66, 20), 3 + 4 + 22 + 12 + 14 + 12);
         }
     }
+    #[test]
+    fn provided_end_to_end_test() {
+        let answers = crate::solution::run_with::<Day20>(get_input(), &Params::new(&[("saving", "64")])).unwrap();
+        assert_eq!((answers.part1.as_deref(), answers.part2.as_deref()), (Some("1"), Some("86")));
+    }
     
 
     fn get_input() -> &'static str {
-        "\
-###############
-#...#...#.....#
-#.#.#.#.#.###.#
-#S#...#.#.#...#
-#######.#.#.###
-#######.#.#...#
-#######.#.###.#
-###..E#...#...#
-###.#######.###
-#...###...#...#
-#.#####.#.###.#
-#.#...#.#.#...#
-#.#.#.#.#.#.###
-#...#...#...###
-###############
-"
+        include_str!("../../examples/day20/example.txt")
     }
 }
\ No newline at end of file
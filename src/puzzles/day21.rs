@@ -5,11 +5,15 @@ use std::fmt;
 use std::str::FromStr;
 use lazy_static::lazy_static;
 use nom::bytes::complete::tag;
-use nom::character::complete::digit1;
-use nom::combinator::{map_res, opt, recognize};
+use nom::character::complete::{digit1, multispace0};
+use nom::combinator::{map_res, opt, recognize, verify};
 use nom::IResult;
 use nom::multi::separated_list1;
 use nom::sequence::preceded;
+use log::debug;
+
+use crate::error::{parse_with, ParseError};
+use crate::solution::Solution;
 use DirectionPad::{Up, Down, Left, Right, Push};
 use KeyPad::{Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, KeyA};
 //     +---+---+
@@ -17,7 +21,7 @@ use KeyPad::{Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, KeyA};
 // +---+---+---+
 // | < | v | > |
 // +---+---+---+
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 enum DirectionPad {
     Up,
     Down,
@@ -26,49 +30,15 @@ enum DirectionPad {
     Push
 }
 impl DirectionPad {
-    fn new() -> Self {
-        Push
-    }
-    fn push(&mut self, target: DirectionPad) -> Box<[DirectionPad]> {
-        let moves = match self {
-            Up => match target {
-                Up => vec![],
-                Down => vec![Down],
-                Left => vec![Down, Left],
-                Right => vec![Down, Right],
-                Push => vec![Right],
-            },
-            Down => match target {
-                Up => vec![Up],
-                Down => vec![],
-                Left => vec![Left],
-                Right => vec![Right],
-                Push => vec![Up, Right],
-            },
-            Left => match target {
-                Up => vec![Right, Up],
-                Down => vec![Right],
-                Left => vec![],
-                Right => vec![Right, Right],
-                Push => vec![Right, Right, Up],
-            },
-            Right => match target {
-                Up => vec![Left, Up],
-                Down => vec![Left],
-                Left => vec![Left, Left],
-                Right => vec![],
-                Push => vec![Up],
-            },
-            Push => match target {
-                Up => vec![Left],
-                Down => vec![Left, Down],
-                Left => vec![Down, Left, Left],
-                Right => vec![Down],
-                Push => vec![],
-            },
-        };
-        *self = target;
-        Vec::from_iter(moves.into_iter().chain(std::iter::once(Push))).into_boxed_slice()
+    /// Column and row on the pad; the gap is at (0, 0).
+    fn position(self) -> (i8, i8) {
+        match self {
+            Up => (1, 0),
+            Push => (2, 0),
+            Left => (0, 1),
+            Down => (1, 1),
+            Right => (2, 1),
+        }
     }
 }
 impl fmt::Display for DirectionPad {
@@ -107,158 +77,103 @@ enum KeyPad {
     Key9,
 }
 impl KeyPad {
-    fn new() -> Self {
-        KeyPad::KeyA
+    /// Column and row on the pad; the gap is at (0, 3).
+    fn position(self) -> (i8, i8) {
+        match self {
+            Key7 => (0, 0),
+            Key8 => (1, 0),
+            Key9 => (2, 0),
+            Key4 => (0, 1),
+            Key5 => (1, 1),
+            Key6 => (2, 1),
+            Key1 => (0, 2),
+            Key2 => (1, 2),
+            Key3 => (2, 2),
+            Key0 => (1, 3),
+            KeyA => (2, 3),
+        }
     }
-    
-    fn push(&mut self, target: KeyPad) -> Box<[DirectionPad]> {
-        let moves: Vec<DirectionPad> = match self {
-            Key1 => match target {
-                Key1 => vec![],
-                Key2 => vec![Right],
-                Key3 => vec![Right, Right],
-                Key4 => vec![Up],
-                Key5 => vec![Up, Right],
-                Key6 => vec![Up, Right, Right],
-                Key7 => vec![Up, Up],
-                Key8 => vec![Up, Up, Right],
-                Key9 => vec![Up, Up, Right, Right],
-                Key0 => vec![Right, Down],
-                KeyA => vec![Right, Right, Down],
-            },
-            Key2 => match target {
-                Key1 => vec![Left],
-                Key2 => vec![],
-                Key3 => vec![Right],
-                Key4 => vec![Left, Up],
-                Key5 => vec![Up],
-                Key6 => vec![Up, Right],
-                Key7 => vec![Left, Up, Up],
-                Key8 => vec![Up, Up],
-                Key9 => vec![Up, Up, Right],
-                Key0 => vec![Down],
-                KeyA => vec![Right, Down],
-            },
-            Key3 => match target {
-                Key1 => vec![Left, Left],
-                Key2 => vec![Left],
-                Key3 => vec![],
-                Key4 => vec![Left, Left, Up],
-                Key5 => vec![Left, Up],
-                Key6 => vec![Up],
-                Key7 => vec![Left, Left,Up, Up],
-                Key8 => vec![Left, Up, Up, ],
-                Key9 => vec![Up, Up],
-                Key0 => vec![Down, Left],
-                KeyA => vec![Down],
-            },
-            Key4 => match target {
-                Key1 => vec![Down],
-                Key2 => vec![Right, Down],
-                Key3 => vec![Right, Right, Down],
-                Key4 => vec![],
-                Key5 => vec![Right],
-                Key6 => vec![Right, Right],
-                Key7 => vec![Up],
-                Key8 => vec![Up, Right],
-                Key9 => vec![Up, Right, Right],
-                Key0 => vec![Right, Down,  Down],
-                KeyA => vec![Right, Right, Down, Down],
-            },
-            Key5 => match target {
-                Key1 => vec![Down, Left],
-                Key2 => vec![Down],
-                Key3 => vec![Right, Down],
-                Key4 => vec![Left],
-                Key5 => vec![],
-                Key6 => vec![Right],
-                Key7 => vec![Left, Up],
-                Key8 => vec![Up],
-                Key9 => vec![Up, Right],
-                Key0 => vec![Down, Down],
-                KeyA => vec![Right, Down, Down],
-            },
-            Key6 => match target {
-                Key1 => vec![Down, Left, Left],
-                Key2 => vec![Down, Left],
-                Key3 => vec![Down],
-                Key4 => vec![Left, Left],
-                Key5 => vec![Left],
-                Key6 => vec![],
-                Key7 => vec![ Left, Left, Up],
-                Key8 => vec![Left, Up],
-                Key9 => vec![Up],
-                Key0 => vec![Down, Down, Left],
-                KeyA => vec![Down, Down],
-            },
-            Key7 => match target {
-                Key1 => vec![Down, Down],
-                Key2 => vec![Right, Down, Down],
-                Key3 => vec![Right, Right, Down, Down, ],
-                Key4 => vec![Down],
-                Key5 => vec![Right, Down, ],
-                Key6 => vec![ Right, Right, Down,],
-                Key7 => vec![],
-                Key8 => vec![Right],
-                Key9 => vec![Right, Right],
-                Key0 => vec![Right, Down,  Down, Down],
-                KeyA => vec![Right, Right, Down, Down, Down],
-            },
-            Key8 => match target {
-                Key1 => vec![Down, Down, Left],
-                Key2 => vec![Down, Down],
-                Key3 => vec![Right, Down, Down],
-                Key4 => vec![Down, Left],
-                Key5 => vec![Down],
-                Key6 => vec![Right, Down],
-                Key7 => vec![Left],
-                Key8 => vec![],
-                Key9 => vec![Right],
-                Key0 => vec![Down, Down, Down],
-                KeyA => vec![Right, Down, Down, Down],
-            },
-            Key9 => match target {
-                Key1 => vec![Down, Down, Left, Left],
-                Key2 => vec![Down, Down, Left],
-                Key3 => vec![Down, Down],
-                Key4 => vec![Down, Left, Left],
-                Key5 => vec![Down, Left],
-                Key6 => vec![Down],
-                Key7 => vec![Left, Left],
-                Key8 => vec![Left],
-                Key9 => vec![],
-                Key0 => vec![Down, Down, Down, Left],
-                KeyA => vec![Down, Down, Down],
-            },
-            Key0 => match target {
-                Key1 => vec![Up, Left],
-                Key2 => vec![Up],
-                Key3 => vec![Up, Right],
-                Key4 => vec![Up, Up, Left],
-                Key5 => vec![Up, Up],
-                Key6 => vec![Up, Up, Right],
-                Key7 => vec![Up, Up, Up, Left],
-                Key8 => vec![Up, Up, Up],
-                Key9 => vec![Up, Up, Up, Right],
-                Key0 => vec![],
-                KeyA => vec![Right],
-            },
-            KeyA => match target {
-                Key1 => vec![Up, Left, Left],
-                Key2 => vec![Left, Up],
-                Key3 => vec![Up],
-                Key4 => vec![Up, Up, Left, Left],
-                Key5 => vec![Left, Up, Up],
-                Key6 => vec![Up, Up],
-                Key7 => vec![Up, Up, Up, Left, Left],
-                Key8 => vec![Left, Up, Up, Up],
-                Key9 => vec![Up, Up, Up],
-                Key0 => vec![Left],
-                KeyA => vec![],
-            },
-        };
-        *self = target;
-        Vec::from_iter(moves.into_iter().chain(std::iter::once(Push))).into_boxed_slice()
+}
+
+/// Every way to move an arm from `from` to `to` in as few moves as possible without passing over `gap`.
+fn orderings(from: (i8, i8), to: (i8, i8), gap: (i8, i8)) -> Vec<Vec<DirectionPad>> {
+    if from == gap {
+        return vec![];
+    }
+    if from == to {
+        return vec![vec![]];
+    }
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let steps = [
+        (dx > 0, Right, (1, 0)),
+        (dx < 0, Left, (-1, 0)),
+        (dy > 0, Down, (0, 1)),
+        (dy < 0, Up, (0, -1)),
+    ];
+    steps
+        .into_iter()
+        .filter(|&(towards, _, _)| towards)
+        .flat_map(|(_, step, (sx, sy))| {
+            orderings((from.0 + sx, from.1 + sy), to, gap).into_iter().map(move |mut rest| {
+                rest.insert(0, step);
+                rest
+            })
+        })
+        .collect()
+}
+
+/// The number of buttons you press to get arms through `robots` directional keypads, memoised per layer.
+struct CostModel {
+    robots: usize,
+    memo: HashMap<(DirectionPad, DirectionPad, usize), u64>,
+}
+impl CostModel {
+    fn new(robots: usize) -> Self {
+        Self { robots, memo: HashMap::new() }
+    }
+    /// The cost of typing `keys` on a directional keypad `depth` layers away from you, starting on `A`.
+    fn sequence_cost(&mut self, keys: &[DirectionPad], depth: usize) -> u64 {
+        let mut from = Push;
+        let mut cost = 0;
+        for &key in keys {
+            cost += self.press_cost(from, key, depth);
+            from = key;
+        }
+        cost
+    }
+    /// The cost of moving an arm `depth` layers away from you from `from` to `to` and pressing it.
+    fn press_cost(&mut self, from: DirectionPad, to: DirectionPad, depth: usize) -> u64 {
+        if depth == 0 {
+            // you press it yourself
+            return 1;
+        }
+        if let Some(&cost) = self.memo.get(&(from, to, depth)) {
+            return cost;
+        }
+        let cost = self.cheapest(orderings(from.position(), to.position(), (0, 0)), depth - 1);
+        self.memo.insert((from, to, depth), cost);
+        cost
+    }
+    /// The cheapest of `orderings`, each followed by a push, typed `depth` layers away.
+    fn cheapest(&mut self, orderings: Vec<Vec<DirectionPad>>, depth: usize) -> u64 {
+        orderings
+            .into_iter()
+            .map(|mut keys| {
+                keys.push(Push);
+                self.sequence_cost(&keys, depth)
+            })
+            .min()
+            .unwrap()
+    }
+    /// The fewest presses that type `keys` on the door, starting on `A`.
+    fn door_cost(&mut self, keys: &[KeyPad]) -> u64 {
+        let mut from = KeyA;
+        let mut cost = 0;
+        for &key in keys {
+            cost += self.cheapest(orderings(from.position(), key.position(), (0, 3)), self.robots);
+            from = key;
+        }
+        cost
     }
 }
 
@@ -289,12 +204,13 @@ fn parse_unsigned(input: &str) -> IResult<&str, usize> {
     Ok((i, number))
 }
 fn parse_code(input: &str) -> IResult<&str, usize>  {
-    let (i, result) = parse_unsigned(input)?;
+    // The door keypad only takes three digits
+    let (i, result) = verify(parse_unsigned, |&code| code < 1000)(input)?;
     let (i, _) = tag("A")(i)?;
     Ok((i, result))
 }
-fn parse(input: String) -> Vec<usize> {
-    separated_list1(tag("\n"), parse_code)(input.trim()).unwrap().1
+fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    parse_with(input, preceded(multispace0, separated_list1(tag("\n"), parse_code)))
 }
 fn three_digits_string(n: usize) -> [usize; 3] {
     let s = format!("{:03}", n); // always 3 digits with leading zeros if needed
@@ -305,32 +221,36 @@ fn three_digits_string(n: usize) -> [usize; 3] {
     }
     digits
 }
-fn get_complexity(code: usize) -> i32 {
-    let mut door = KeyPad::new();
-    let mut robot1 = DirectionPad::new();
-    let mut robot2 = DirectionPad::new();
+fn get_complexity(code: usize, costs: &mut CostModel) -> u64 {
     let mut keys = three_digits_string(code).iter().map(|&d| *KEYPAD_MAP.get(&d).unwrap()).collect::<Vec<_>>();
     keys.push(KeyA);
-    println!("{:?}", keys);
-    let me = keys.iter().flat_map(|k| door.push(*k))
-        .flat_map(|k| robot1.push(k))
-        .flat_map(|k| robot2.push(k))
-        .collect::<Vec<_>>();
-
-    println!("{}", me.iter().map(|d| d.to_string()).collect::<String>());
-    println!("{}: {:?}", code, me.len());
-    me.len() as i32 * code as i32
+    debug!("{:?}", keys);
+    let presses = costs.door_cost(&keys);
+    debug!("{}: {:?}", code, presses);
+    presses * code as u64
+}
+fn total_complexity(codes: &[usize], robots: usize) -> u64 {
+    let mut costs = CostModel::new(robots);
+    codes.iter().map(|&code| get_complexity(code, &mut costs)).sum()
 }
-pub(crate) fn solve(input: String) -> (i32, i32) {
-    let codes = parse(input);
-    println!("{:?}", codes);
+pub(crate) struct Day21;
+
+impl Solution for Day21 {
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let codes = parse(input)?;
+        debug!("{:?}", codes);
+        Ok(codes)
+    }
 
-    let mut sum = 0;
-    for code in codes {
-        sum += get_complexity(code);
+    fn part1(codes: &Self::Input) -> u64 {
+        total_complexity(codes, 2)
     }
 
-    (sum,1) // 439_726 is too high // 180_204 is too high // 176_964 is too high
+    fn part2(codes: &Self::Input) -> u64 {
+        total_complexity(codes, 25)
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -340,7 +260,7 @@ mod tests {
     static ref TEST_MAP: HashMap<usize, &'static str> = {
         // We can build from an array of tuples:
         let data: [(usize, &str); 5] = [
-    (029,"<vA<AA>>^AvAA<^A>A<v<A>>^AvA^A<vA>^A<v<A>^A>AAvA^A<v<A>A>^AAAvA<^A>A"),
+    (29,"<vA<AA>>^AvAA<^A>A<v<A>>^AvA^A<vA>^A<v<A>^A>AAvA^A<v<A>A>^AAAvA<^A>A"),
     (980,"<v<A>>^AAAvA^A<vA<AA>>^AvAA<^A>A<v<A>A>^AAAvA<^A>A<vA>^A<A>A"),
     (179,"<v<A>>^A<vA<A>>^AAvAA<^A>A<v<A>>^AAvA^A<vA>^AA<A>A<v<A>A>^AAAvA<^A>A"),
     (456,"<v<A>>^AA<vA<A>>^AAvAA<^A>A<vA>^A<A>A<vA>^A<A>A<v<A>A>^AAvA<^A>A"),
@@ -352,9 +272,9 @@ mod tests {
 
     #[test]
     fn test_parse() {
-        let input = "029A".to_string();
+        let input = "029A";
         let expected = vec![29];
-        assert_eq!(parse(input), expected);
+        assert_eq!(parse(input).unwrap(), expected);
     }
     #[test]
     fn test_three_digits_string() {
@@ -366,14 +286,26 @@ mod tests {
     #[test]
     fn test_test_map() {
         for (code, expected) in TEST_MAP.iter() {
-            println!(" testing: {:?} expected: {}", code, expected.len());
-            println!("{}", expected);
-            assert_eq!(get_complexity(*code), expected.len() as i32 * *code as i32);
+            assert_eq!(get_complexity(*code, &mut CostModel::new(2)), expected.len() as u64 * *code as u64);
         }
     }
-    // [Key3, Key7, Key9, KeyA] expected 64 was 68
-    // <v<A>>^AvA^A<v A<  AA>>^AAvA<^A>AAvA^A<vA>^AA<A>A<v< A>A>^AAAvA<^A>A
-    // v<<A>>^AvA^Av<<A>>^AA<vA<A>>^AAvAA<^A>A<vA^>AA<A>Av<<A>A^>AAA<Av>A^A
+    #[test]
+    fn test_orderings() {
+        let render = |orderings: Vec<Vec<DirectionPad>>| {
+            orderings.iter().map(|keys| keys.iter().map(|k| k.to_string()).collect::<String>()).collect::<Vec<_>>()
+        };
+        // from A to 1 on the door: every ordering but the one through the gap
+        assert_eq!(render(orderings(KeyA.position(), Key1.position(), (0, 3))), vec!["<^<", "^<<"]);
+        assert_eq!(render(orderings(Key0.position(), Key0.position(), (0, 3))), vec![""]);
+        assert_eq!(render(orderings(Push.position(), Left.position(), (0, 0))), vec!["<v<", "v<<"]);
+        assert_eq!(render(orderings(Key2.position(), Key9.position(), (0, 3))).len(), 3);
+    }
+    #[test]
+    fn test_provided() {
+        let codes = parse("029A\n980A\n179A\n456A\n379A\n").unwrap();
+        assert_eq!(total_complexity(&codes, 2), 126384);
+        // with no robots in between you type straight onto the door: <A^A^^>AvvvA
+        assert_eq!(get_complexity(29, &mut CostModel::new(0)), 12 * 29);
+        assert_eq!(total_complexity(&codes, 25), 154115708116294);
+    }
 }
-
-
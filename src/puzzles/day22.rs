@@ -1,20 +1,21 @@
 use crate::puzzles::day14;
 use ahash::AHashMap;
 use nom::bytes::complete::tag;
+use nom::character::complete::multispace0;
 use nom::multi::separated_list1;
+use nom::sequence::preceded;
 use nom::IResult;
-use std::time::Instant;
-use timing_util::measure_time;
+
+use crate::error::{parse_with, ParseError};
+use crate::solution::Solution;
 const MASK: i32 = 16777216 - 1;
 
 fn parse_line(input: &str) -> IResult<&str, usize> {
     let (i, result) = day14::parse_unsigned(input)?;
     Ok((i, result))
 }
-fn parse(input: String) -> Vec<usize> {
-    separated_list1(tag("\n"), parse_line)(input.trim())
-        .unwrap()
-        .1
+fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    parse_with(input, preceded(multispace0, separated_list1(tag("\n"), parse_line)))
 }
 #[inline(always)]
 fn next(current: i32) -> i32 {
@@ -50,17 +51,28 @@ fn get_sequences(input: i32) -> AHashMap<[i8; 4], usize> {
     }
     sequences
 }
-pub(crate) fn solve(input: String) -> (i32, i32) {
-    let numbers = parse(input);
-    let part_1 = measure_time!(part_1(&numbers));
+pub(crate) struct Day22;
+
+impl Solution for Day22 {
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
 
-    (part_1, measure_time!( part_2(numbers))) // 225 is too low
+    fn part1(numbers: &Self::Input) -> u64 {
+        part_1(numbers)
+    }
+
+    fn part2(numbers: &Self::Input) -> i32 {
+        part_2(numbers.clone()) // 225 is too low
+    }
 }
 
-fn part_1(numbers: &Vec<usize>) -> i32 {
+fn part_1(numbers: &[usize]) -> u64 {
     numbers
         .iter()
-        .map(|number| two_thousandth(*number as i32))
+        .map(|number| two_thousandth(*number as i32) as u64)
         .sum()
 }
 
@@ -85,7 +97,7 @@ mod tests {
 
         #[test]
         fn parse_input_test() {
-            assert_eq!(parse(get_input()), vec![1, 10, 100, 2024]);
+            assert_eq!(parse(&get_input()).unwrap(), vec![1, 10, 100, 2024]);
         }
 
         #[test]
@@ -137,12 +149,7 @@ mod tests {
             assert_eq!(part_2([1, 2, 3, 2024].to_vec()),23);
         }
         fn get_input() -> String {
-            "\
-1
-10
-100
-2024"
-                .to_string()
+            include_str!("../../examples/day22/example.txt").to_string()
         }
     }
 }
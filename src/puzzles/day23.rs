@@ -4,8 +4,10 @@ use nom::multi::separated_list1;
 use nom::sequence::separated_pair;
 use nom::IResult;
 use std::collections::{HashMap, HashSet};
-use timing_util::measure_time;
-use std::time::Instant;
+use log::debug;
+
+use crate::error::ParseError;
+use crate::solution::Solution;
 // --- Day 23: LAN Party ---
 // read all connections in to a list of tuples
 // populate a map for each connection: key: computer - value: list of connected computers
@@ -29,19 +31,19 @@ fn parse(input: &str) -> IResult<&str, Vec<(&str, &str)>> {
     let (input, grid) = separated_list1(line_ending, parse_pair)(input)?;
     Ok((input, grid))
 }
-fn prep<'a>(
-    pairs: &'a Vec<(&'a str, &'a str)>,
+fn prep(
+    pairs: &[(&str, &str)],
 ) -> (
-    Vec<&'a str>,
+    Vec<String>,
     HashMap<usize, Vec<usize>>,
 ) {
     let computer_set: HashSet<&str> = pairs.iter().flat_map(|(a, b)| vec![*a, *b]).collect();
     let mut computer_lookup: HashMap<&str, usize> = HashMap::new();
-    let mut computer_index: Vec<&str> = Vec::with_capacity(computer_set.len());
+    let mut computer_index: Vec<String> = Vec::with_capacity(computer_set.len());
     let mut computer_map: HashMap<usize, Vec<usize>> = HashMap::new();
     computer_set.iter().enumerate().for_each(|(i, c)| {
         computer_lookup.insert(c, i);
-        computer_index.push(c);
+        computer_index.push(c.to_string());
         computer_map.insert(i, [i].to_vec()); // add self to the list of connected computers helps with comparisons
     });
 
@@ -60,7 +62,7 @@ fn prep<'a>(
     }
     (computer_index, computer_map)
 }
-fn part_1(computer_index: &Vec<&str>, computer_map: &HashMap<usize, Vec<usize>>) -> usize {
+fn part_1(computer_index: &[String], computer_map: &HashMap<usize, Vec<usize>>) -> usize {
     let mut connected_computers: HashSet<usize> = HashSet::new();
     let base = computer_index.len();
     computer_index
@@ -119,7 +121,7 @@ fn combi<T: Clone>(n: usize, lst: &[T]) -> Vec<Vec<T>> {
     [with_first, without_first].concat()
 }
 
-fn part_2(computer_index: &Vec<&str>, computer_map: &HashMap<usize, Vec<usize>>) -> String {
+fn part_2(computer_index: &[String], computer_map: &HashMap<usize, Vec<usize>>) -> String {
     
     let mut max = 0;
     let mut found: Vec<usize> = Vec::with_capacity(14);
@@ -130,7 +132,7 @@ fn part_2(computer_index: &Vec<&str>, computer_map: &HashMap<usize, Vec<usize>>)
             if n <= max {
                 break;
             }
-            for combination in combi(n, &c) {
+            for combination in combi(n, c) {
                 // if we get all the friends in this combination and create a frequency distribution,
                 // then if it's a match we will have the frequency of each computer in the group will be n
                 let mut distribution: HashMap<usize, usize> = HashMap::new();
@@ -145,32 +147,37 @@ fn part_2(computer_index: &Vec<&str>, computer_map: &HashMap<usize, Vec<usize>>)
                 if combination.iter().map(|computer| distribution.get(computer).unwrap()).all(|x| *x == n) {
                     max = n;
                     found = combination;
-                    println!("Found: {:?}", n);
+                    debug!("Found: {:?}", n);
                     break;
                 }
             }
         }
     }
-    let mut computers = found.iter().map(|i| computer_index[*i]).collect::<Vec<&str>>();
+    let mut computers = found.iter().map(|i| computer_index[*i].as_str()).collect::<Vec<&str>>();
     computers.sort();
     computers.join(",")
 }
 
-pub(crate) fn solve(input: String) -> (String, String) {
-    match parse(&input) {
-        Ok((_remaining, pairs)) => {
-            let (computer_index, computer_map) = measure_time!(prep(&pairs));
-            println!("Pairs: {:?}", pairs.len());
-            println!("Computer Set: {:?}", computer_index.len());
-            println!("Computer Map: {:?}", computer_map.len());
-            let part_1_result: usize = measure_time!(part_1(&computer_index, &computer_map));
-            let part_2_result: String = measure_time!(part_2(&computer_index, &computer_map));
-            (part_1_result.to_string(), part_2_result)
-        }
-        Err(err) => {
-            eprintln!("Error parsing input: {err}");
-            (String::new(), String::new())
-        }
+pub(crate) struct Day23;
+
+impl Solution for Day23 {
+    type Input = (Vec<String>, HashMap<usize, Vec<usize>>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let (_remaining, pairs) = parse(input).map_err(|error| ParseError::from_nom(input, error))?;
+        let (computer_index, computer_map) = prep(&pairs);
+        debug!("Pairs: {:?}", pairs.len());
+        debug!("Computer Set: {:?}", computer_index.len());
+        debug!("Computer Map: {:?}", computer_map.len());
+        Ok((computer_index, computer_map))
+    }
+
+    fn part1((computer_index, computer_map): &Self::Input) -> usize {
+        part_1(computer_index, computer_map)
+    }
+
+    fn part2((computer_index, computer_map): &Self::Input) -> String {
+        part_2(computer_index, computer_map)
     }
 }
 
@@ -215,65 +222,33 @@ mod tests {
         }
         #[test]
         fn test_combi_pick_2_from_3() {
-            let result = combi(2, &vec![1, 2, 3]);
+            let result = combi(2, &[1, 2, 3]);
             // We expect all 2-element subsets: [1,2], [1,3], and [2,3]
             assert_eq!(result, vec![vec![1, 2], vec![1, 3], vec![2, 3]]);
         }
 
         #[test]
         fn test_combi_pick_0_from_3() {
-            let result = combi(0, &vec![1, 2, 3]);
+            let result = combi(0, &[1, 2, 3]);
             // Picking 0 elements should yield the empty subset as the only "combination"
             assert_eq!(result, vec![vec![]]);
         }
 
         #[test]
         fn test_combi_pick_3_from_3() {
-            let result = combi(3, &vec![1, 2, 3]);
+            let result = combi(3, &[1, 2, 3]);
             // Picking all elements from the list should yield exactly one combination
             assert_eq!(result, vec![vec![1, 2, 3]]);
         }
 
         #[test]
         fn test_combi_pick_more_than_available() {
-            let result = combi(4, &vec![1, 2, 3]);
+            let result = combi(4, &[1, 2, 3]);
             // It's impossible to pick 4 elements from a 3-element list, so this should be empty
             assert_eq!(result, Vec::<Vec<i32>>::new());
         }
     }
     fn input() -> &'static str {
-        "\
-kh-tc
-qp-kh
-de-cg
-ka-co
-yn-aq
-qp-ub
-cg-tb
-vc-aq
-tb-ka
-wh-tc
-yn-cg
-kh-ub
-ta-co
-de-co
-tc-td
-tb-wq
-wh-td
-ta-ka
-td-qp
-aq-cg
-wq-ub
-ub-vc
-de-ta
-wq-aq
-wq-vc
-wh-yn
-ka-de
-kh-ta
-co-tc
-wh-qp
-tb-vc
-td-yn"
+        include_str!("../../examples/day23/example.txt")
     }
 }
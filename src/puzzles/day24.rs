@@ -7,37 +7,17 @@ use nom::{
     ,
     IResult,
 };
-use num::abs;
-use std::fmt::Write as _;
-use std::fs::File;
-use std::io::Write as _;
-use std::time::Instant;
-use timing_util::measure_time;
-#[derive(Debug, Clone)]
-struct Wire {
-    name: String,
-    value: Option<u8>,
-}
-
-#[derive(Debug, Clone)]
-enum GateType {
-    AND,
-    OR,
-    XOR,
-}
-
-#[derive(Debug)]
-struct Gate{
-    gate_type: GateType,
-    inputs: [usize; 2],
-    output: usize,
-}
-
-#[derive(Debug)]
-struct Circuit {
-    wires: Vec<Wire>,
-    gates: Vec<Gate>,
-}
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::fmt;
+use log::{debug, warn};
+use nom::error::ErrorKind;
+use num::BigUint;
+
+use crate::error::{parse_with, ParseError};
+use crate::puzzles::day24_export::{exporter, Circuit};
+use crate::puzzles::day24_netlist::{Bits, Gate, GateType, Netlist, NetlistError, Wire};
+use crate::solution::{ExportFormat, Solution};
 
 // Parse a single wire (e.g., x00: 1 or y03 without a value)
 fn parse_wire(input: &str) -> IResult<&str, Wire> {
@@ -51,13 +31,13 @@ fn parse_wire(input: &str) -> IResult<&str, Wire> {
 // Parse a gate type (AND, OR, XOR)
 fn parse_gate_type(input: &str) -> IResult<&str, GateType> {
     alt((
-        map(tag("AND"), |_| GateType::AND),
-        map(tag("OR"), |_| GateType::OR),
-        map(tag("XOR"), |_| GateType::XOR),
+        map(tag("AND"), |_| GateType::And),
+        map(tag("OR"), |_| GateType::Or),
+        map(tag("XOR"), |_| GateType::Xor),
     ))(input)
 }
 // Parse a single gate (e.g., `x00 OR x03 -> fst`)
-fn parse_gate(input: &str) -> IResult<&str, (GateType, [Wire;2], Wire)> {
+fn parse_gate(input: &str) -> IResult<&str, GateSpec> {
     let (input, in1)  = parse_wire(input)?;
     let (input, _) = space1(input)?;
     let (input, gate_type) = parse_gate_type(input)?;
@@ -80,274 +60,253 @@ fn parse_gate(input: &str) -> IResult<&str, (GateType, [Wire;2], Wire)> {
     ))
 }
 
+type GateSpec = (GateType, [Wire; 2], Wire);
+
 // Parse the full input into wires and gates
-fn parse(input: &str) -> IResult<&str, (Vec<Wire>, Vec<(GateType, [Wire;2], Wire)>)> {
+fn parse(input: &str) -> IResult<&str, (Vec<Wire>, Vec<GateSpec>)> {
     let (input, wires) = separated_list1(tag("\n"), parse_wire)(input)?;
     let (input, _) = tag("\n\n")(input)?;
     let (input, gates) = separated_list1(tag("\n"), parse_gate)(input)?;
     Ok((input, (wires, gates)))
 }
 
-fn part_1(wires: &Vec<Wire>, gates: &Vec<Gate>) -> String {
-    println!("part_1 Wires: {:#?}", wires.len());
-    let result = resolve(gates, wires).to_string();
-    println!("part_1 done: {:#?}", wires.len());
-    result
+fn part_1(wires: &[Wire], gates: &[Gate]) -> String {
+    let netlist = Netlist::new(wires, gates).expect("loops are rejected when parsing");
+    let mut signals = netlist.initial();
+    netlist.evaluate(&mut signals);
+    netlist.register(&signals, 'z').expect("every z wire should be driven").to_string()
 }
 
-fn resolve(gates: &Vec<Gate>, wires: &[Wire]) -> u64 {
-    let mut mut_wires = wires.to_vec();
-    // println!("Resolving for: X {:#?} Y {}",  get_register(&mut mut_wires, 'x'), get_register(&mut mut_wires, 'y'));
-    let mut changes = true;
-    while changes {
-        changes = false;
-        for gate in gates {
-            if let Some(_output) = mut_wires[gate.output].value {
-                continue;
-            }
-            if let (Some(in1), Some(in2)) = (mut_wires[gate.inputs[0]].value, mut_wires[gate.inputs[1]].value) {
-                let result = match gate.gate_type {
-                    GateType::AND => in1 & in2,
-                    GateType::OR => in1 | in2,
-                    GateType::XOR => in1 ^ in2,
-                };
-                mut_wires[gate.output].value = Some(result);
-                changes = true;
-            }
-        }
-    }
-    let register = 'z';
-    // println!("Wires: {:#?}", mut_wires);
-    let decimal = get_register(&mut mut_wires, register);
-    decimal
+/// The circuit in `format`, with the gates [`suspicious_outputs`] picks out highlighted.
+pub fn export(input: &str, format: ExportFormat) -> Result<String, ParseError> {
+    let (wires, gates) = Day24::parse(input)?;
+    let suspicious: HashSet<usize> = suspicious_outputs(&wires, &gates).into_iter().collect();
+    Ok(exporter(format).export(&Circuit { wires: &wires, gates: &gates, suspicious }))
 }
 
-fn get_register(mut_wires: &mut Vec<Wire>, register: char) -> u64 {
-    let mut zs: Vec<(&String, u8)> = mut_wires
-        .iter()
-        .filter(|wire| wire.name.chars().nth(0) == Some(register))
-        .map(|wire| (&wire.name, wire.value.unwrap()))
-        .collect::<Vec<(&String, u8)>>();
-    zs.sort();
-    zs.reverse();
-
-    let binary = zs.iter().map(|(_name, value)| value).map(|v| v.to_string()).collect::<Vec<String>>().join("");
-    let decimal = u64::from_str_radix(&binary, 2).unwrap();
-    decimal
+fn part_2(wires: &[Wire], gates: &[Gate]) -> Result<String, SwapError> {
+    find_swaps(wires, gates).map(|swaps| swaps.join(","))
 }
 
-fn generate_plantuml(wires: &[Wire], gates: &[Gate]) -> String {
-
-    let mut adders: Vec<Vec<String>> = vec![Vec::new(); 100];
-    let mut names = wires.iter().map(|wire| wire.name.clone()).collect::<Vec<String>>();
-    names.sort();
-    for (_i, wire) in names.iter().enumerate() {
-        if let Some(wire_label) = wire_rectangle(wire) {
-            let color = match wire_label {
-                "in" => "#green",
-                "out" => "#red",
-                _ => "#black",
-            };
-            let id = usize::from_str_radix(&wire[1..], 10).unwrap();
-            let mut storage = String::new();
-            writeln!(storage, "storage {} \"{}\" as {}", color, wire_label, wire).unwrap();
-            adders[id].push(storage);
-        }
-    }
+/// Why no swaps of gate outputs make the circuit an adder.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SwapError {
+    /// There are no `x` wires, so there is nothing to add.
+    NoInputs,
+    /// No pairing of these outputs, fixing one bit at a time, makes the circuit add.
+    NoFix(Vec<String>),
+}
 
-    let mut plantuml = String::new();
-    writeln!(plantuml, "@startuml").unwrap();
-    writeln!(plantuml, "left to right direction").unwrap();
-    writeln!(plantuml, "").unwrap();
-
-    writeln!(plantuml).unwrap();
-    // Define inputs and outputs
-
-    writeln!(plantuml).unwrap();
-
-    // Define gates and connections
-    for (i, gate) in gates.iter().enumerate() {
-        let gate_label = match gate.gate_type {
-            GateType::AND => "AND",
-            GateType::OR => "OR",
-            GateType::XOR => "XOR",
-        };
-        let shape = match gate.gate_type {
-            GateType::AND => "component",
-            GateType::OR => "cloud",
-            GateType::XOR => "boundary",
-        };
-        let mut gateuml = String::new();
-        writeln!(gateuml, "{} \"{}\" as gate_{}", shape, gate_label, i).unwrap();
-        let mut gate_wires:Vec<usize> = gate.inputs.clone().iter().map(|idx| *idx).collect();
-        gate_wires.push(gate.output.clone());
-        let mut adder_idx: Option<usize> = None;
-        gate_wires.iter().for_each(|idx| {
-            let wire = &wires[*idx];
-            if let Some(_wire_label) = wire_rectangle(wire.name.as_str()) {
-                adder_idx = Some(usize::from_str_radix(&wire.name[1..], 10).unwrap());
+impl fmt::Display for SwapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapError::NoInputs => f.write_str("there are no x wires, so the circuit is not an adder"),
+            SwapError::NoFix(outputs) => {
+                write!(f, "no pairing of the suspicious outputs {} makes the circuit add", outputs.join(","))
             }
-        });
-        if let Some(adder_idx) = adder_idx {
-            adders[adder_idx].push(gateuml.clone());
-        } else {
-            writeln!(plantuml, "{}", gateuml).unwrap();
         }
     }
+}
 
-    // adders
-    for (i, adder) in adders.iter().enumerate() {
-        if adder.len() > 0 {
-            writeln!(plantuml, "package adder_{} {{", i).unwrap();
-            for line in adder {
-                writeln!(plantuml, "{}", line).unwrap();
-            }
-            writeln!(plantuml, "}}").unwrap();
-        }
-    }
+impl std::error::Error for SwapError {}
 
+/// The highest bit of the x register, or `None` when there are no x wires.
+fn max_bit(wires: &[Wire]) -> Option<i32> {
+    wires
+        .iter()
+        .filter(|wire| wire.name.starts_with('x'))
+        .filter_map(|wire| wire.name[1..].parse::<i32>().ok())
+        .max()
+}
 
-    // wires
-    for (i, gate) in gates.iter().enumerate() {
-        let output = &wires[gate.output];
+/// The outputs of gates that do not fit a ripple-carry adder. For bit i > 0 that is
+///
+/// ```text
+/// xi XOR yi -> ai    ai XOR carry -> zi
+/// xi AND yi -> bi    ai AND carry -> ci    bi OR ci -> carry out
+/// ```
+///
+/// with a half adder for bit 0 and the last carry out going straight to the top z wire.
+fn suspicious_outputs(wires: &[Wire], gates: &[Gate]) -> Vec<usize> {
+    let name = |idx: usize| wires[idx].name.as_str();
+    let last_z = max_bit(wires).map(|bit| format!("z{:02}", bit + 1));
+    let from_inputs = |gate: &Gate| gate.inputs.iter().all(|&idx| name(idx).starts_with(['x', 'y']));
+    let first_bit = |gate: &Gate| gate.inputs.iter().all(|&idx| name(idx)[1..] == *"00");
+    let feeds = |output: usize, gate_type: GateType| {
+        gates.iter().any(|gate| gate.gate_type == gate_type && gate.inputs.contains(&output))
+    };
 
-        for input_wire_idx in gate.inputs {
-            let input_wire = &wires[input_wire_idx];
-            if let Some(_wire_label) = wire_rectangle(input_wire.name.as_str()) {
-                writeln!(plantuml, "{} --> gate_{}:{}", input_wire.name, i, input_wire.name).unwrap();
-            } else {
-                for (j, other_gate) in gates.iter().enumerate() {
-                    if other_gate.output == input_wire_idx {
-                        writeln!(plantuml, "gate_{} --> gate_{}:{}", j, i, input_wire.name).unwrap();
-                    }
+    let mut suspicious: Vec<usize> = gates
+        .iter()
+        .filter(|gate| {
+            let output = name(gate.output);
+            match gate.gate_type {
+                // only the final carry out goes straight to a z wire
+                _ if last_z.as_deref() == Some(output) => gate.gate_type != GateType::Or,
+                GateType::Xor if from_inputs(gate) => {
+                    // the half sum goes on to the sum XOR, apart from bit 0 where it is the sum
+                    if first_bit(gate) { output != "z00" } else { !feeds(gate.output, GateType::Xor) }
                 }
+                GateType::Xor => !output.starts_with('z'),
+                _ if output.starts_with('z') => true,
+                // both carries go on to the OR, apart from bit 0 where the AND is the carry
+                GateType::And => !first_bit(gate) && !feeds(gate.output, GateType::Or),
+                GateType::Or => false,
             }
-        }
+        })
+        .map(|gate| gate.output)
+        .collect();
+    suspicious.sort_by_key(|&idx| name(idx));
+    suspicious
+}
 
-        if output.name.starts_with('z') {
-            writeln!(plantuml, "gate_{} --> {}:{}", i, output.name, output.name).unwrap();
+fn swap_outputs(gates: &[Gate], pairs: &[(usize, usize)]) -> Vec<Gate> {
+    let mut swapped = gates.to_vec();
+    for gate in swapped.iter_mut() {
+        for &(a, b) in pairs {
+            if gate.output == a {
+                gate.output = b;
+            } else if gate.output == b {
+                gate.output = a;
+            }
         }
     }
-    writeln!(plantuml).unwrap();
-    writeln!(plantuml, "@enduml").unwrap();
-    plantuml
+    swapped
 }
 
-fn wire_rectangle(wire: &str) -> Option<&str> {
-    let wire_label = match wire.chars().nth(0) {
-        Some('x') => Some("in"),
-        Some('y') => Some("in"),
-        Some('z') => Some("out"),
-        _ => None,
+/// x/y pairs to check a `width`-bit adder with: each bit on its own, each carry, then pseudo-random values.
+fn samples(width: usize) -> Vec<(BigUint, BigUint)> {
+    let bit = |bit: usize| BigUint::from(1u8) << bit;
+    let zero = BigUint::default;
+    let mut samples: Vec<(BigUint, BigUint)> =
+        (0..width).flat_map(|i| [(bit(i), zero()), (zero(), bit(i)), (bit(i), bit(i))]).collect();
+    // xorshift, so the check is the same from run to run
+    let mut state: u64 = 0x2024_1224;
+    let mut next = || {
+        let bits = (0..width).map(|bit| {
+            if bit % 64 == 0 {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+            }
+            (state >> (bit % 64)) & 1 == 1
+        });
+        BigUint::from(&Bits(bits.collect()))
     };
-    wire_label
+    samples.extend((0..32).map(|_| (next(), next())));
+    samples
 }
-fn write_string_to_file(filename: &str, content: &str) -> std::io::Result<()> {
-    let mut file = File::create(filename)?;
-    file.write_all(content.as_bytes())?;
-    Ok(())
+
+/// The lowest bit of z that comes out wrong for any sample, or `None` when the gates add x and
+/// y into z. A z wire left undriven, say behind a loop, is wrong too.
+fn first_wrong_bit(wires: &[Wire], gates: &[Gate]) -> Option<usize> {
+    let Ok(netlist) = Netlist::ignoring_loops(wires, gates) else {
+        return Some(0);
+    };
+    let width = netlist.width('x').max(netlist.width('y'));
+    samples(width)
+        .into_iter()
+        .filter_map(|(x, y)| {
+            let mut signals = netlist.initial();
+            netlist.set_register(&mut signals, 'x', &Bits::from_value(&x, width));
+            netlist.set_register(&mut signals, 'y', &Bits::from_value(&y, width));
+            netlist.evaluate(&mut signals);
+            let z = netlist.register_signals(&signals, 'z');
+            let sum = x + y;
+            (0..z.len().max(sum.bits() as usize)).find(|&bit| z.get(bit).copied().flatten() != Some(sum.bit(bit as u64)))
+        })
+        .min()
 }
-fn part_2(wires: &Vec<Wire>, gates: &Vec<Gate>) -> String {
-    write_string_to_file("input/day24.puml", &generate_plantuml(wires, gates)).unwrap();
-    let wires = wires.clone();
-    let mut input = wires
-        .iter()
-        .filter(|wire| wire.name.starts_with('x'))
-        .map(|wire| wire.name.clone())
-        .map(|name| {usize::from_str_radix(&name[1..], 10).unwrap()})
-            .collect::<Vec<usize>>();
-    input.sort();
-    let max_bits = *input.last().unwrap() as i32;
-    // create a map of wires using wire name as the key
-
-
-    // for each bit from 0..=max_bit create inputs where x = 0 and y = 1 >> bit -- expected addition will be 2^bit
-    // then do the same but reversed for x = 1 >> bit and y = 0
-    for bit in 0..=max_bits {
-        let x:u64 = 0;
-        let y:u64 = 1 << bit;
-        let input_wires = set_inputs(&wires, max_bits, x, y);
-        let result = resolve(gates, &input_wires);
-        let expected = x + y;
-        if (result != expected) {
-            let diff = abs(result as i64 - expected as i64);
-            let mut diff_bits: Vec<i32> = vec![];
-            for bit in 0..=max_bits {
-                let mask = 1 << bit;
-                if (diff & mask) > 0 {
-                    diff_bits.push(bit);
-                }
+
+/// Pairs of `candidates` whose swaps make the gates add, found one swap at a time: each swap
+/// must move the lowest wrong bit, `wrong`, further up, so a bad pair is dropped as soon as it
+/// is tried rather than in every pairing it could be part of.
+fn fix_bit_by_bit(wires: &[Wire], gates: &[Gate], candidates: &[usize], wrong: usize) -> Option<Vec<(usize, usize)>> {
+    let mut better = vec![];
+    for (i, &a) in candidates.iter().enumerate() {
+        for &b in &candidates[i + 1..] {
+            let swapped = swap_outputs(gates, &[(a, b)]);
+            match first_wrong_bit(wires, &swapped) {
+                None => return Some(vec![(a, b)]),
+                Some(bit) if bit > wrong => better.push((bit, (a, b), swapped)),
+                Some(_) => {}
             }
-            println!("Bit: {} Result: {} Expected: {} Difference Bits = {:#?}", bit, result, expected, diff_bits);
         }
     }
-    for bit in 0..max_bits {
-        let x:u64 = (1 << bit) + (1 << (bit + 1));
-        let y:u64 = (1 << bit) + (1 << (bit + 1));
-        let input_wires = set_inputs(&wires, max_bits, x, y);
-        let result = resolve(gates, &input_wires);
-        let expected = x + y;
-        if (result != expected) {
-            let diff = abs(result as i64 - expected as i64);
-            let mut diff_bits: Vec<i32> = vec![];
-            for bit in 0..=max_bits {
-                let mask = 1 << bit;
-                if (diff & mask) > 0 {
-                    diff_bits.push(bit);
-                }
-            }
-            println!("Bit: {} Result: {} Expected: {} Difference Bits = {:#?}", bit, result, expected, diff_bits);
+    // the swaps that fix the most bits first
+    better.sort_by_key(|&(bit, ..)| Reverse(bit));
+    better.into_iter().find_map(|(bit, (a, b), swapped)| {
+        let rest: Vec<usize> = candidates.iter().copied().filter(|&output| output != a && output != b).collect();
+        let mut pairs = fix_bit_by_bit(wires, &swapped, &rest, bit)?;
+        pairs.push((a, b));
+        Some(pairs)
+    })
+}
+
+/// The sorted names of the swapped gate outputs: the suspicious outputs, paired up so the circuit adds.
+fn find_swaps(wires: &[Wire], gates: &[Gate]) -> Result<Vec<String>, SwapError> {
+    if max_bit(wires).is_none() {
+        return Err(SwapError::NoInputs);
+    }
+    let Some(wrong) = first_wrong_bit(wires, gates) else {
+        return Ok(vec![]);
+    };
+    let suspicious = suspicious_outputs(wires, gates);
+    let names = |outputs: &mut dyn Iterator<Item = usize>| {
+        let mut names: Vec<String> = outputs.map(|idx| wires[idx].name.clone()).collect();
+        names.sort();
+        names
+    };
+    debug!("suspicious: {:?}", names(&mut suspicious.iter().copied()));
+    let pairs = fix_bit_by_bit(wires, gates, &suspicious, wrong)
+        .ok_or_else(|| SwapError::NoFix(names(&mut suspicious.iter().copied())))?;
+    Ok(names(&mut pairs.into_iter().flat_map(|(a, b)| [a, b])))
+}
+
+pub(crate) struct Day24;
+
+impl Solution for Day24 {
+    type Input = (Vec<Wire>, Vec<Gate>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let (wires, gates) = parse_with(input, parse)?;
+        let (wires, gates) = prep(wires, gates);
+        if let Err(error) = Netlist::new(&wires, &gates) {
+            warn!("{}", error);
+            let line = match &error {
+                NetlistError::Loop(wires) => driving_line(input, &wires[0]),
+                NetlistError::MultipleDrivers(wire) => driving_line(input, wire),
+                NetlistError::Undriven(wire) => reading_line(input, wire),
+            };
+            return Err(ParseError::at(input, line, ErrorKind::Verify));
         }
+        Ok((wires, gates))
     }
-    let mut result = vec!["","","","","","","",""];
-    result.sort();
-    let part_2 = result.join(",");
-    part_2
 
-}
+    fn part1((wires, gates): &Self::Input) -> String {
+        part_1(wires, gates)
+    }
 
-fn set_inputs(wires: &[Wire], max_bits: i32, x: u64, y: u64) -> Vec<Wire> {
-    let mut input_wires = wires.to_vec();
-    let mut updates: Vec<(usize, Wire)> = vec![];
-    for i in 0..=max_bits {
-        updates.push(get_update(&input_wires, i, 'x', x).unwrap());
-        updates.push(get_update(&input_wires, i, 'y', y).unwrap());
+    fn part2((wires, gates): &Self::Input) -> Result<String, SwapError> {
+        part_2(wires, gates)
     }
-    updates.iter().for_each(|(j, wire)| {
-        input_wires[*j] = wire.clone();
-    });
-    input_wires
 }
 
-fn get_update(input_wires: &[Wire], bit: i32, register: char, value: u64) -> Option<(usize, Wire)> {
-    let mut update: Option<(usize, Wire)> = None;
-    let name = format!("{}{:02}", register, bit);
-    input_wires.iter().enumerate().filter(|(j, w)| w.name == name.as_str()).for_each(|(j, wire)| {
-        let mut wire = wire.clone();
-        wire.value = Some(((value >> bit) & 1) as u8);
-        update = Some((j, wire));
-    });
-    update
+/// The line of the gate driving `wire`, the last one if several do.
+fn driving_line<'a>(input: &'a str, wire: &str) -> &'a str {
+    let arrow = format!("-> {}", wire);
+    input.lines().rfind(|line| line.trim_end().ends_with(&arrow)).unwrap_or(input)
 }
 
-pub(crate) fn solve<'a>(input: String) -> (String, String) {
-    match parse(&input) {
-        Ok((_remaining, (wires, gates))) => {
-            let (wires, gates) = prep(wires, gates);
-            let part_1_result: String = measure_time!(part_1(&wires, &gates));
-            let part_2_result: String = measure_time!(part_2(&wires, &gates));
-            (part_1_result.to_string(), part_2_result)
-        }
-        Err(err) => {
-            eprintln!("Error parsing input: {err}");
-            (String::new(), String::new())
-        }
-    }
+/// The line of the first gate reading `wire`.
+fn reading_line<'a>(input: &'a str, wire: &str) -> &'a str {
+    let reads = |line: &str| line.split(" -> ").next().is_some_and(|inputs| inputs.split_whitespace().any(|word| word == wire));
+    input.lines().find(|line| line.contains(" -> ") && reads(line)).unwrap_or(input)
 }
 
-fn prep<'a>(
+fn prep(
     wires: Vec<Wire>,
-    gates: Vec<(GateType, [Wire; 2], Wire)>,
+    gates: Vec<GateSpec>,
 ) -> (Vec<Wire>, Vec<Gate>) {
     // Collect all wires into a single vector
     let mut updated_wires = wires.clone();
@@ -366,7 +325,7 @@ fn prep<'a>(
         let in2 = updated_wires.iter().position(|wire| wire.name == in2.name).unwrap();
         let output = updated_wires.iter().position(|wire| wire.name == output.name).unwrap();
         updated_gates.push(Gate {
-            gate_type: gate_type.clone(),
+            gate_type,
             inputs: [in1, in2],
             output,
         });
@@ -379,21 +338,16 @@ fn prep<'a>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fmt::Write as _;
+
+    fn adds_correctly(wires: &[Wire], gates: &[Gate]) -> bool {
+        first_wrong_bit(wires, gates).is_none()
+    }
 
     #[test]
     fn test_part_1_provided() {
-        let input = "\
-x00: 1
-x01: 1
-x02: 1
-y00: 0
-y01: 1
-y02: 0
-
-x00 AND y00 -> z00
-x01 XOR y01 -> z01
-x02 OR y02 -> z02".to_string();
-        let (_input, (wires, gates)) = parse(&input).unwrap();
+        let input = include_str!("../../examples/day24/small.txt");
+        let (_input, (wires, gates)) = parse(input).unwrap();
         let ( wires, gates) = prep(wires, gates);
         assert_eq!(part_1(&wires, &gates), "4".to_string());
     }
@@ -408,54 +362,116 @@ x02 OR y02 -> z02".to_string();
     }
 
     fn input() -> String {
-        "\
-x00: 1
-x01: 0
-x02: 1
-x03: 1
-x04: 0
-y00: 1
-y01: 1
-y02: 1
-y03: 1
-y04: 1
-
-ntg XOR fgs -> mjb
-y02 OR x01 -> tnw
-kwq OR kpj -> z05
-x00 OR x03 -> fst
-tgd XOR rvg -> z01
-vdt OR tnw -> bfw
-bfw AND frj -> z10
-ffh OR nrd -> bqk
-y00 AND y03 -> djm
-y03 OR y00 -> psh
-bqk OR frj -> z08
-tnw OR fst -> frj
-gnj AND tgd -> z11
-bfw XOR mjb -> z00
-x03 OR x00 -> vdt
-gnj AND wpb -> z02
-x04 AND y00 -> kjc
-djm OR pbm -> qhw
-nrd AND vdt -> hwm
-kjc AND fst -> rvg
-y04 OR y02 -> fgs
-y01 AND x02 -> pbm
-ntg OR kjc -> kwq
-psh XOR fgs -> tgd
-qhw XOR tgd -> z09
-pbm OR djm -> kpj
-x03 XOR y03 -> ffh
-x00 XOR y04 -> ntg
-bfw OR bqk -> z06
-nrd XOR fgs -> wpb
-frj XOR qhw -> z04
-bqk OR frj -> z07
-y03 OR x01 -> nrd
-hwm AND bqk -> z03
-tgd XOR rvg -> z12
-tnw OR pbm -> gnj".to_string()
+        include_str!("../../examples/day24/larger.txt").to_string()
+    }
+    /// A ripple-carry adder for `bits`-bit numbers, as puzzle input, with the outputs of each pair in `swaps` exchanged.
+    fn adder_input(bits: usize, swaps: &[(&str, &str)]) -> String {
+        let mut input = String::new();
+        for register in ['x', 'y'] {
+            for bit in 0..bits {
+                writeln!(input, "{}{:02}: 0", register, bit).unwrap();
+            }
+        }
+        let mut gates = vec![
+            ("x00 XOR y00".to_string(), "z00".to_string()),
+            ("x00 AND y00".to_string(), "d00".to_string()),
+        ];
+        for bit in 1..bits {
+            let carry = if bit + 1 == bits { format!("z{:02}", bits) } else { format!("d{:02}", bit) };
+            gates.extend([
+                (format!("x{:02} XOR y{:02}", bit, bit), format!("a{:02}", bit)),
+                (format!("x{:02} AND y{:02}", bit, bit), format!("b{:02}", bit)),
+                (format!("a{:02} XOR d{:02}", bit, bit - 1), format!("z{:02}", bit)),
+                (format!("a{:02} AND d{:02}", bit, bit - 1), format!("c{:02}", bit)),
+                (format!("b{:02} OR c{:02}", bit, bit), carry),
+            ]);
+        }
+        writeln!(input).unwrap();
+        for (gate, output) in gates {
+            let output = swaps
+                .iter()
+                .find_map(|&(a, b)| (output == a).then_some(b).or((output == b).then_some(a)))
+                .unwrap_or(&output);
+            writeln!(input, "{} -> {}", gate, output).unwrap();
+        }
+        input
+    }
+    fn prep_adder(input: &str) -> (Vec<Wire>, Vec<Gate>) {
+        let (_input, (wires, gates)) = parse(input).unwrap();
+        prep(wires, gates)
+    }
+    #[test]
+    fn test_adder_input() {
+        let (wires, gates) = prep_adder(&adder_input(6, &[]));
+        assert!(suspicious_outputs(&wires, &gates).is_empty());
+        assert!(adds_correctly(&wires, &gates));
+        assert_eq!(find_swaps(&wires, &gates), Ok(vec![]));
+    }
+    #[test]
+    fn test_find_swaps() {
+        let (wires, gates) = prep_adder(&adder_input(8, &[("a03", "b03"), ("z05", "c05"), ("d01", "z06")]));
+        assert!(!adds_correctly(&wires, &gates));
+        assert_eq!(find_swaps(&wires, &gates).unwrap(), vec!["a03", "b03", "c05", "d01", "z05", "z06"]);
+    }
+    #[test]
+    fn test_swap_outputs() {
+        let (wires, gates) = prep_adder(&adder_input(8, &[("a03", "b03"), ("z05", "c05")]));
+        let index = |name: &str| wires.iter().position(|wire| wire.name == name).unwrap();
+        let fixed = swap_outputs(&gates, &[(index("a03"), index("b03")), (index("c05"), index("z05"))]);
+        assert!(adds_correctly(&wires, &fixed));
+        assert!(!adds_correctly(&wires, &swap_outputs(&gates, &[(index("a03"), index("c05")), (index("b03"), index("z05"))])));
+    }
+    #[test]
+    fn parse_loop_test() {
+        let input = "x00: 1\ny00: 0\n\nx00 AND abc -> z00\nz00 OR y00 -> abc\n";
+        let error = Day24::parse(input).unwrap_err();
+        // the loop is reported as abc -> z00, starting from the gate driving abc
+        assert_eq!((error.line, error.column, error.kind), (5, 1, ErrorKind::Verify));
+        let input = "x00: 1\ny00: 0\n\nx00 AND y00 -> z00\nx00 OR y00 -> z00\n";
+        assert_eq!(Day24::parse(input).unwrap_err().line, 5);
+    }
+    #[test]
+    fn parse_undriven_test() {
+        let input = "x00: 1\ny00: 0\n\nx00 XOR y00 -> z01\nx00 AND q00 -> z00\n";
+        let error = Day24::parse(input).unwrap_err();
+        assert_eq!((error.line, error.column, error.kind), (5, 1, ErrorKind::Verify));
+    }
+    #[test]
+    fn parse_trailing_line_test() {
+        let input = "x00: 1\ny00: 0\n\nx00 XOR y00 -> z00\nx00 NAND y00 -> z01\n";
+        let error = Day24::parse(input).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (5, 1, "x00 NAND y00 -> z01"));
+    }
+    #[test]
+    fn test_wide_register() {
+        let (wires, gates) = prep_adder(&adder_input(70, &[]));
+        assert!(adds_correctly(&wires, &gates));
+        let input = adder_input(70, &[]).replace("x69: 0", "x69: 1").replace("y69: 0", "y69: 1");
+        let (wires, gates) = prep_adder(&input);
+        assert_eq!(part_1(&wires, &gates), (BigUint::from(1u8) << 70u32).to_string());
+    }
+    #[test]
+    fn test_find_eight_swaps() {
+        // 16 suspicious outputs, one swap in front of another: each swap is found as the one
+        // that fixes the lowest wrong bit, so the search tries at most 120 pairs per swap
+        let swaps = [
+            ("a02", "b02"), ("z07", "c07"), ("d11", "z14"), ("a16", "b16"),
+            ("z21", "c21"), ("a26", "b26"), ("d31", "z34"), ("z38", "c38"),
+        ];
+        let (wires, gates) = prep_adder(&adder_input(45, &swaps));
+        let mut expected: Vec<&str> = swaps.iter().flat_map(|&(a, b)| [a, b]).collect();
+        expected.sort();
+        assert_eq!(find_swaps(&wires, &gates).unwrap(), expected);
+    }
+    #[test]
+    fn test_no_fix() {
+        // z01 is driven, but by the carry: nothing to swap it with makes this add
+        let (wires, gates) = prep_adder("x00: 1\ny00: 1\n\nx00 AND y00 -> z01\n");
+        assert_eq!(find_swaps(&wires, &gates), Err(SwapError::NoFix(vec!["z01".to_string()])));
+        let (wires, gates) = prep_adder("a00: 1\nb00: 1\n\na00 AND b00 -> z00\n");
+        assert_eq!(max_bit(&wires), None);
+        assert_eq!(find_swaps(&wires, &gates), Err(SwapError::NoInputs));
+        assert_eq!(SwapError::NoInputs.to_string(), "there are no x wires, so the circuit is not an adder");
     }
     #[test]
     fn prep_test() {
@@ -472,7 +488,7 @@ tnw OR pbm -> gnj".to_string()
             Wire { name: "y04".to_string(), value: Some(1) }
         ];
         let gates = vec![
-            (GateType::XOR,
+            (GateType::Xor,
                  [Wire { name: "x00".to_string(), value: None }, Wire { name: "x01".to_string(), value: None }],
              Wire { name: "z01".to_string(), value: None },),
 
@@ -488,8 +504,3 @@ tnw OR pbm -> gnj".to_string()
 
     }
 }
-
-
-
-
-
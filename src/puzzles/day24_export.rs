@@ -0,0 +1,341 @@
+//! Day 24's circuit written out for other tools: a PlantUML diagram, a Graphviz DOT graph
+//! or a structural Verilog module. The diagrams group each bit's gates into `adder_N`,
+//! and every format marks the suspicious gates, the ones that do not fit a ripple-carry adder.
+//!
+//! ```text
+//! aoc2024 export 24 --to dot --output day24.dot
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write as _;
+
+use crate::puzzles::day24_netlist::{Gate, GateType, Wire};
+use crate::solution::ExportFormat;
+
+/// A netlist and the gate outputs to highlight.
+pub(crate) struct Circuit<'a> {
+    pub(crate) wires: &'a [Wire],
+    pub(crate) gates: &'a [Gate],
+    pub(crate) suspicious: HashSet<usize>,
+}
+
+impl Circuit<'_> {
+    fn is_suspicious(&self, gate: &Gate) -> bool {
+        self.suspicious.contains(&gate.output)
+    }
+
+    /// The bit of the adder a gate belongs to: the number of its output if that is a z wire,
+    /// otherwise of its last x or y input.
+    fn adder_of(&self, gate: &Gate) -> Option<usize> {
+        [gate.output, gate.inputs[1], gate.inputs[0]].iter().find_map(|&wire| Some(register_bit(&self.wires[wire].name)?.1))
+    }
+
+    /// The gates with their index, grouped by [`Circuit::adder_of`]; the ungrouped gates come first.
+    fn gates_by_adder(&self) -> (Numbered<'_>, BTreeMap<usize, Numbered<'_>>) {
+        let mut loose = vec![];
+        let mut adders: BTreeMap<usize, Vec<(usize, &Gate)>> = BTreeMap::new();
+        for (i, gate) in self.gates.iter().enumerate() {
+            match self.adder_of(gate) {
+                Some(adder) => adders.entry(adder).or_default().push((i, gate)),
+                None => loose.push((i, gate)),
+            }
+        }
+        (loose, adders)
+    }
+
+    /// The indices of the gates driving `wire`.
+    fn drivers(&self, wire: usize) -> impl Iterator<Item = usize> + '_ {
+        self.gates.iter().enumerate().filter(move |(_, gate)| gate.output == wire).map(|(j, _)| j)
+    }
+}
+
+/// Gates with their index in the netlist.
+type Numbered<'a> = Vec<(usize, &'a Gate)>;
+
+/// A file format a [`Circuit`] can be written in.
+pub(crate) trait Exporter {
+    fn export(&self, circuit: &Circuit) -> String;
+}
+
+pub(crate) fn exporter(format: ExportFormat) -> &'static dyn Exporter {
+    match format {
+        ExportFormat::PlantUml => &PlantUml,
+        ExportFormat::Dot => &Dot,
+        ExportFormat::Verilog => &Verilog,
+    }
+}
+
+/// `x05` is bit 5 of register x; only the x, y and z wires make up registers.
+fn register_bit(name: &str) -> Option<(char, usize)> {
+    let register = name.chars().next().filter(|register| matches!(register, 'x' | 'y' | 'z'))?;
+    Some((register, name[1..].parse().ok()?))
+}
+
+fn gate_label(gate_type: GateType) -> &'static str {
+    match gate_type {
+        GateType::And => "AND",
+        GateType::Or => "OR",
+        GateType::Xor => "XOR",
+    }
+}
+
+struct PlantUml;
+
+impl Exporter for PlantUml {
+    fn export(&self, circuit: &Circuit) -> String {
+        let wires = circuit.wires;
+        let mut adders: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        let mut names = wires.iter().map(|wire| wire.name.as_str()).collect::<Vec<&str>>();
+        names.sort();
+        for wire in names {
+            if let Some((register, bit)) = register_bit(wire) {
+                let (color, label) = if register == 'z' { ("#red", "out") } else { ("#green", "in") };
+                adders.entry(bit).or_default().push(format!("storage {} \"{}\" as {}", color, label, wire));
+            }
+        }
+
+        let mut plantuml = String::new();
+        writeln!(plantuml, "@startuml").unwrap();
+        writeln!(plantuml, "left to right direction").unwrap();
+        writeln!(plantuml).unwrap();
+
+        // gates
+        for (i, gate) in circuit.gates.iter().enumerate() {
+            let shape = match gate.gate_type {
+                GateType::And => "component",
+                GateType::Or => "cloud",
+                GateType::Xor => "boundary",
+            };
+            let highlight = if circuit.is_suspicious(gate) { " #orange" } else { "" };
+            let gateuml = format!("{} \"{}\" as gate_{}{}", shape, gate_label(gate.gate_type), i, highlight);
+            match circuit.adder_of(gate) {
+                Some(adder) => adders.entry(adder).or_default().push(gateuml),
+                None => writeln!(plantuml, "{}", gateuml).unwrap(),
+            }
+        }
+
+        // adders
+        for (i, adder) in adders {
+            writeln!(plantuml, "package adder_{} {{", i).unwrap();
+            for line in adder {
+                writeln!(plantuml, "{}", line).unwrap();
+            }
+            writeln!(plantuml, "}}").unwrap();
+        }
+
+        // wires
+        for (i, gate) in circuit.gates.iter().enumerate() {
+            for input in gate.inputs {
+                let name = &wires[input].name;
+                if register_bit(name).is_some() {
+                    writeln!(plantuml, "{} --> gate_{}:{}", name, i, name).unwrap();
+                } else {
+                    for j in circuit.drivers(input) {
+                        writeln!(plantuml, "gate_{} --> gate_{}:{}", j, i, name).unwrap();
+                    }
+                }
+            }
+            let output = &wires[gate.output].name;
+            if output.starts_with('z') {
+                writeln!(plantuml, "gate_{} --> {}:{}", i, output, output).unwrap();
+            }
+        }
+        writeln!(plantuml).unwrap();
+        writeln!(plantuml, "@enduml").unwrap();
+        plantuml
+    }
+}
+
+struct Dot;
+
+impl Dot {
+    fn gate(circuit: &Circuit, i: usize, gate: &Gate) -> String {
+        let highlight = if circuit.is_suspicious(gate) { ", style=filled, fillcolor=orange" } else { "" };
+        format!("gate_{} [label=\"{}\"{}];", i, gate_label(gate.gate_type), highlight)
+    }
+}
+
+impl Exporter for Dot {
+    fn export(&self, circuit: &Circuit) -> String {
+        let wires = circuit.wires;
+        let mut registers: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+        for wire in wires {
+            if let Some((_, bit)) = register_bit(&wire.name) {
+                registers.entry(bit).or_default().push(&wire.name);
+            }
+        }
+        let (loose, adders) = circuit.gates_by_adder();
+
+        let mut dot = String::new();
+        writeln!(dot, "digraph day24 {{").unwrap();
+        writeln!(dot, "  rankdir=LR;").unwrap();
+        for (i, gate) in loose {
+            writeln!(dot, "  {}", Dot::gate(circuit, i, gate)).unwrap();
+        }
+        let bits: BTreeSet<usize> = registers.keys().chain(adders.keys()).copied().collect();
+        for bit in bits {
+            writeln!(dot, "  subgraph cluster_adder_{} {{", bit).unwrap();
+            writeln!(dot, "    label=\"adder_{}\";", bit).unwrap();
+            for name in registers.get(&bit).into_iter().flatten() {
+                let color = if name.starts_with('z') { "red" } else { "green" };
+                writeln!(dot, "    {} [shape=box, color={}];", name, color).unwrap();
+            }
+            for &(i, gate) in adders.get(&bit).into_iter().flatten() {
+                writeln!(dot, "    {}", Dot::gate(circuit, i, gate)).unwrap();
+            }
+            writeln!(dot, "  }}").unwrap();
+        }
+
+        for (i, gate) in circuit.gates.iter().enumerate() {
+            for input in gate.inputs {
+                let name = &wires[input].name;
+                if register_bit(name).is_some() {
+                    writeln!(dot, "  {} -> gate_{};", name, i).unwrap();
+                } else {
+                    for j in circuit.drivers(input) {
+                        writeln!(dot, "  gate_{} -> gate_{} [label=\"{}\"];", j, i, name).unwrap();
+                    }
+                }
+            }
+            let output = &wires[gate.output].name;
+            if register_bit(output).is_some() {
+                writeln!(dot, "  gate_{} -> {};", i, output).unwrap();
+            }
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+
+struct Verilog;
+
+impl Verilog {
+    /// `x05` becomes the bit select `x[5]`; other wires keep their names.
+    fn net(name: &str) -> String {
+        match register_bit(name) {
+            Some((register, bit)) => format!("{}[{}]", register, bit),
+            None => name.to_string(),
+        }
+    }
+}
+
+impl Exporter for Verilog {
+    fn export(&self, circuit: &Circuit) -> String {
+        let wires = circuit.wires;
+        let mut widths: BTreeMap<char, usize> = BTreeMap::new();
+        let mut internal = BTreeSet::new();
+        for wire in wires {
+            match register_bit(&wire.name) {
+                Some((register, bit)) => {
+                    let width = widths.entry(register).or_default();
+                    *width = (*width).max(bit + 1);
+                }
+                None => {
+                    internal.insert(wire.name.as_str());
+                }
+            }
+        }
+        let ports: Vec<String> = widths
+            .iter()
+            .map(|(&register, width)| {
+                let direction = if register == 'z' { "output" } else { "input" };
+                format!("{} [{}:0] {}", direction, width - 1, register)
+            })
+            .collect();
+
+        let mut verilog = String::new();
+        writeln!(verilog, "module day24({});", ports.join(", ")).unwrap();
+        if !internal.is_empty() {
+            writeln!(verilog, "  wire {};", internal.into_iter().collect::<Vec<_>>().join(", ")).unwrap();
+        }
+        for (i, gate) in circuit.gates.iter().enumerate() {
+            let primitive = gate_label(gate.gate_type).to_lowercase();
+            let [in1, in2] = gate.inputs.map(|input| Verilog::net(&wires[input].name));
+            let output = Verilog::net(&wires[gate.output].name);
+            let note = if circuit.is_suspicious(gate) { " // suspicious" } else { "" };
+            writeln!(verilog, "  {} gate_{}({}, {}, {});{}", primitive, i, output, in1, in2, note).unwrap();
+        }
+        writeln!(verilog, "endmodule").unwrap();
+        verilog
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wire(name: &str) -> Wire {
+        Wire { name: name.to_string(), value: None }
+    }
+
+    // a half adder for bit 0 whose carry feeds an OR outside any adder
+    fn circuit() -> (Vec<Wire>, Vec<Gate>) {
+        let wires = ["x00", "y00", "z00", "c00", "out"].map(wire).to_vec();
+        let gates = vec![
+            Gate { gate_type: GateType::Xor, inputs: [0, 1], output: 2 },
+            Gate { gate_type: GateType::And, inputs: [0, 1], output: 3 },
+            Gate { gate_type: GateType::Or, inputs: [3, 3], output: 4 },
+        ];
+        (wires, gates)
+    }
+
+    fn export(format: ExportFormat) -> String {
+        let (wires, gates) = circuit();
+        exporter(format).export(&Circuit { wires: &wires, gates: &gates, suspicious: HashSet::from([3]) })
+    }
+
+    #[test]
+    fn plantuml_test() {
+        assert_eq!(
+            export(ExportFormat::PlantUml),
+            "\
+@startuml
+left to right direction
+
+cloud \"OR\" as gate_2
+package adder_0 {
+storage #green \"in\" as x00
+storage #green \"in\" as y00
+storage #red \"out\" as z00
+boundary \"XOR\" as gate_0
+component \"AND\" as gate_1 #orange
+}
+x00 --> gate_0:x00
+y00 --> gate_0:y00
+gate_0 --> z00:z00
+x00 --> gate_1:x00
+y00 --> gate_1:y00
+gate_1 --> gate_2:c00
+gate_1 --> gate_2:c00
+
+@enduml
+"
+        );
+    }
+
+    #[test]
+    fn dot_test() {
+        let dot = export(ExportFormat::Dot);
+        assert!(dot.starts_with("digraph day24 {\n  rankdir=LR;\n  gate_2 [label=\"OR\"];\n  subgraph cluster_adder_0 {\n"));
+        assert!(dot.contains("    gate_1 [label=\"AND\", style=filled, fillcolor=orange];\n"));
+        assert!(dot.contains("    z00 [shape=box, color=red];\n"));
+        assert!(dot.contains("  gate_1 -> gate_2 [label=\"c00\"];\n  gate_1 -> gate_2 [label=\"c00\"];\n"));
+        assert!(dot.contains("  gate_0 -> z00;\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn verilog_test() {
+        assert_eq!(
+            export(ExportFormat::Verilog),
+            "\
+module day24(input [0:0] x, input [0:0] y, output [0:0] z);
+  wire c00, out;
+  xor gate_0(z[0], x[0], y[0]);
+  and gate_1(c00, x[0], y[0]); // suspicious
+  or gate_2(out, c00, c00);
+endmodule
+"
+        );
+    }
+}
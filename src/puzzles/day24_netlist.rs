@@ -0,0 +1,318 @@
+//! The gate netlist from day 24. Gates are sorted once when the netlist is built, so
+//! evaluating it is a single pass, and a register (the wires `x00`, `x01`, ... sharing a
+//! prefix) is read and written as a bit vector of any width.
+//!
+//! ```text
+//! let netlist = Netlist::new(&wires, &gates)?;
+//! let mut signals = netlist.initial();
+//! netlist.set_register(&mut signals, 'x', &Bits::from_value(&x, width));
+//! netlist.evaluate(&mut signals);
+//! let z = netlist.register(&signals, 'z');
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use num::BigUint;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Wire {
+    pub(crate) name: String,
+    pub(crate) value: Option<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum GateType {
+    And,
+    Or,
+    Xor,
+}
+
+impl GateType {
+    fn apply(self, in1: bool, in2: bool) -> bool {
+        match self {
+            GateType::And => in1 & in2,
+            GateType::Or => in1 | in2,
+            GateType::Xor => in1 ^ in2,
+        }
+    }
+}
+
+/// A gate whose inputs and output are indices into the wires.
+#[derive(Debug, Clone)]
+pub(crate) struct Gate {
+    pub(crate) gate_type: GateType,
+    pub(crate) inputs: [usize; 2],
+    pub(crate) output: usize,
+}
+
+/// The value of every wire, by index; `None` until something drives it.
+pub(crate) type Signals = Vec<Option<bool>>;
+
+/// A register's value, least significant bit first.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct Bits(pub(crate) Vec<bool>);
+
+impl Bits {
+    /// The low `width` bits of `value`.
+    pub(crate) fn from_value(value: &BigUint, width: usize) -> Self {
+        Bits((0..width).map(|bit| value.bit(bit as u64)).collect())
+    }
+}
+
+impl From<&Bits> for BigUint {
+    fn from(bits: &Bits) -> Self {
+        let mut value = BigUint::default();
+        for (bit, _) in bits.0.iter().enumerate().filter(|(_, &set)| set) {
+            value.set_bit(bit as u64, true);
+        }
+        value
+    }
+}
+
+impl fmt::Display for Bits {
+    /// The value in decimal, however wide.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", BigUint::from(self))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum NetlistError {
+    /// The wires around a loop of gates, each driving the next.
+    Loop(Vec<String>),
+    /// A wire that is the output of more than one gate.
+    MultipleDrivers(String),
+    /// A wire a gate reads that is neither given a value nor driven by a gate.
+    Undriven(String),
+}
+
+impl fmt::Display for NetlistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetlistError::Loop(wires) => write!(f, "combinational loop through {}", wires.join(" -> ")),
+            NetlistError::MultipleDrivers(wire) => write!(f, "wire {} is driven by more than one gate", wire),
+            NetlistError::Undriven(wire) => write!(f, "wire {} is read but has no value and no gate driving it", wire),
+        }
+    }
+}
+
+impl std::error::Error for NetlistError {}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Netlist {
+    initial: Signals,
+    /// In topological order: every gate comes after the gates driving its inputs.
+    gates: Vec<Gate>,
+    /// The wires of each register by prefix, bit 0 first.
+    registers: HashMap<char, Vec<usize>>,
+}
+
+impl Netlist {
+    pub(crate) fn new(wires: &[Wire], gates: &[Gate]) -> Result<Self, NetlistError> {
+        Self::build(wires, gates, false)
+    }
+
+    /// Like [`Netlist::new`], but a gate in a loop, or fed by one, is left out rather than
+    /// rejected, so its output stays undriven: for judging how far a broken circuit is from working.
+    pub(crate) fn ignoring_loops(wires: &[Wire], gates: &[Gate]) -> Result<Self, NetlistError> {
+        Self::build(wires, gates, true)
+    }
+
+    fn build(wires: &[Wire], gates: &[Gate], ignore_loops: bool) -> Result<Self, NetlistError> {
+        let mut driver = vec![None; wires.len()];
+        for (index, gate) in gates.iter().enumerate() {
+            if driver[gate.output].replace(index).is_some() {
+                return Err(NetlistError::MultipleDrivers(wires[gate.output].name.clone()));
+            }
+        }
+        let undriven = |input: usize| driver[input].is_none() && wires[input].value.is_none();
+        if let Some(input) = gates.iter().flat_map(|gate| gate.inputs).find(|&input| undriven(input)) {
+            return Err(NetlistError::Undriven(wires[input].name.clone()));
+        }
+
+        // Kahn's algorithm: a gate is ready once every gate driving its inputs has been placed
+        let mut readers = vec![vec![]; wires.len()];
+        gates.iter().enumerate().for_each(|(index, gate)| gate.inputs.iter().for_each(|&input| readers[input].push(index)));
+        let mut waiting: Vec<usize> =
+            gates.iter().map(|gate| gate.inputs.iter().filter(|&&input| driver[input].is_some()).count()).collect();
+        let mut ready: Vec<usize> = (0..gates.len()).filter(|&index| waiting[index] == 0).collect();
+        let mut order = Vec::with_capacity(gates.len());
+        while let Some(index) = ready.pop() {
+            order.push(index);
+            for &reader in &readers[gates[index].output] {
+                waiting[reader] -= 1;
+                if waiting[reader] == 0 {
+                    ready.push(reader);
+                }
+            }
+        }
+        if order.len() < gates.len() && !ignore_loops {
+            return Err(NetlistError::Loop(find_loop(wires, gates, &driver, &waiting)));
+        }
+
+        let mut registers: HashMap<char, Vec<(u32, usize)>> = HashMap::new();
+        for (index, wire) in wires.iter().enumerate() {
+            let mut chars = wire.name.chars();
+            if let (Some(prefix), Ok(bit)) = (chars.next(), chars.as_str().parse::<u32>()) {
+                registers.entry(prefix).or_default().push((bit, index));
+            }
+        }
+        Ok(Netlist {
+            initial: wires.iter().map(|wire| wire.value.map(|value| value == 1)).collect(),
+            gates: order.into_iter().map(|index| gates[index].clone()).collect(),
+            registers: registers
+                .into_iter()
+                .map(|(prefix, mut wires)| {
+                    wires.sort();
+                    (prefix, wires.into_iter().map(|(_, index)| index).collect())
+                })
+                .collect(),
+        })
+    }
+
+    /// The values the puzzle input gives the wires.
+    pub(crate) fn initial(&self) -> Signals {
+        self.initial.clone()
+    }
+
+    /// How many wires the register named by `prefix` has.
+    pub(crate) fn width(&self, prefix: char) -> usize {
+        self.registers.get(&prefix).map_or(0, Vec::len)
+    }
+
+    /// Drive the register's wires from `value`; wires past the end of `value` are set to 0.
+    pub(crate) fn set_register(&self, signals: &mut Signals, prefix: char, value: &Bits) {
+        for (bit, &wire) in self.registers.get(&prefix).into_iter().flatten().enumerate() {
+            signals[wire] = Some(value.0.get(bit).copied().unwrap_or(false));
+        }
+    }
+
+    /// Work out every gate's output, in one pass. A gate with an undriven input drives nothing.
+    pub(crate) fn evaluate(&self, signals: &mut Signals) {
+        for gate in &self.gates {
+            let [in1, in2] = gate.inputs.map(|input| signals[input]);
+            signals[gate.output] = in1.zip(in2).map(|(in1, in2)| gate.gate_type.apply(in1, in2));
+        }
+    }
+
+    /// The register named by `prefix`, or `None` while any of its wires is undriven.
+    pub(crate) fn register(&self, signals: &Signals, prefix: char) -> Option<Bits> {
+        self.register_signals(signals, prefix).into_iter().collect::<Option<_>>().map(Bits)
+    }
+
+    /// The signal on each of the register's wires, bit 0 first, driven or not.
+    pub(crate) fn register_signals(&self, signals: &Signals, prefix: char) -> Signals {
+        let wires = self.registers.get(&prefix).map_or(&[][..], Vec::as_slice);
+        wires.iter().map(|&wire| signals[wire]).collect()
+    }
+}
+
+/// Walk back from a gate that could not be sorted, through inputs driven by other such gates,
+/// until a wire repeats; the wires from there on form a loop.
+fn find_loop(wires: &[Wire], gates: &[Gate], driver: &[Option<usize>], waiting: &[usize]) -> Vec<String> {
+    let unsorted = |wire: usize| driver[wire].filter(|&gate| waiting[gate] > 0);
+    let mut gate = (0..gates.len()).find(|&index| waiting[index] > 0).expect("some gate is unsorted");
+    let mut path = vec![];
+    loop {
+        let output = gates[gate].output;
+        if let Some(start) = path.iter().position(|&wire| wire == output) {
+            let mut cycle: Vec<String> = path[start..].iter().map(|&wire: &usize| wires[wire].name.clone()).collect();
+            // walking back reversed the flow
+            cycle.reverse();
+            return cycle;
+        }
+        path.push(output);
+        gate = gates[gate].inputs.iter().find_map(|&input| unsorted(input)).expect("an unsorted gate waits on another");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wire(name: &str, value: Option<u8>) -> Wire {
+        Wire { name: name.to_string(), value }
+    }
+
+    fn gate(gate_type: GateType, inputs: [usize; 2], output: usize) -> Gate {
+        Gate { gate_type, inputs, output }
+    }
+
+    // x00, x01, y00, y01 -> z00 = x00 XOR y00, z01 = (x01 XOR y01) XOR (x00 AND y00), z02 = carry
+    fn half_adders() -> (Vec<Wire>, Vec<Gate>) {
+        let names = ["x00", "x01", "y00", "y01", "z00", "z01", "z02", "sum", "c00", "c01", "d01"];
+        let wires = names.iter().map(|name| wire(name, name.starts_with(['x', 'y']).then_some(0))).collect();
+        // listed out of order, so evaluation relies on the sort
+        let gates = vec![
+            gate(GateType::Or, [9, 10], 6),
+            gate(GateType::Xor, [7, 8], 5),
+            gate(GateType::And, [7, 8], 10),
+            gate(GateType::Xor, [1, 3], 7),
+            gate(GateType::And, [1, 3], 9),
+            gate(GateType::Xor, [0, 2], 4),
+            gate(GateType::And, [0, 2], 8),
+        ];
+        (wires, gates)
+    }
+
+    fn bits(value: u64, width: usize) -> Bits {
+        Bits::from_value(&BigUint::from(value), width)
+    }
+
+    #[test]
+    fn evaluate_test() {
+        let (wires, gates) = half_adders();
+        let netlist = Netlist::new(&wires, &gates).unwrap();
+        assert_eq!((netlist.width('x'), netlist.width('z'), netlist.width('s')), (2, 3, 0));
+        for x in 0..4 {
+            for y in 0..4 {
+                let mut signals = netlist.initial();
+                netlist.set_register(&mut signals, 'x', &bits(x, 2));
+                netlist.set_register(&mut signals, 'y', &bits(y, 2));
+                netlist.evaluate(&mut signals);
+                assert_eq!(netlist.register(&signals, 'z'), Some(bits(x + y, 3)));
+            }
+        }
+        // with y unset nothing downstream of it is driven
+        let mut signals = netlist.initial();
+        signals[2..4].fill(None);
+        netlist.set_register(&mut signals, 'x', &bits(3, 2));
+        netlist.evaluate(&mut signals);
+        assert_eq!(netlist.register(&signals, 'z'), None);
+        assert_eq!(netlist.register(&signals, 'x'), Some(bits(3, 2)));
+    }
+
+    #[test]
+    fn loop_test() {
+        let (wires, mut gates) = half_adders();
+        // feed the carry back into the low half adder
+        gates[6].inputs = [0, 6];
+        assert_eq!(
+            Netlist::new(&wires, &gates).unwrap_err(),
+            NetlistError::Loop(vec!["c00".to_string(), "d01".to_string(), "z02".to_string()])
+        );
+        gates[6].output = 4;
+        assert_eq!(Netlist::new(&wires, &gates).unwrap_err(), NetlistError::MultipleDrivers("z00".to_string()));
+    }
+
+    #[test]
+    fn undriven_test() {
+        let (mut wires, gates) = half_adders();
+        wires[3].value = None;
+        let error = Netlist::new(&wires, &gates).unwrap_err();
+        assert_eq!(error, NetlistError::Undriven("y01".to_string()));
+        assert_eq!(error.to_string(), "wire y01 is read but has no value and no gate driving it");
+    }
+
+    #[test]
+    fn bits_test() {
+        let wide = (BigUint::from(1u8) << 100u32) + 5u8;
+        let bits = Bits::from_value(&wide, 101);
+        assert_eq!(bits.0.len(), 101);
+        assert_eq!(BigUint::from(&bits), wide);
+        assert_eq!(bits.to_string(), "1267650600228229401496703205381");
+        assert_eq!(Bits::from_value(&wide, 3), Bits(vec![true, false, true]));
+        assert_eq!(Bits::default().to_string(), "0");
+    }
+}
@@ -7,6 +7,9 @@ use nom::{
     IResult,
 };
 
+use crate::error::ParseError;
+use crate::solution::Solution;
+
 #[derive(Debug)]
 enum Instruction {
     Mul(i32, i32),
@@ -98,21 +101,27 @@ fn parse_all_muls(input: &str) -> Vec<(i32, i32)> {
     res
 }
 
-pub(crate) fn test() {
-    let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
-    solve(input.to_string()); //161
-}
+pub(crate) struct Day3;
+
+impl Solution for Day3 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &Self::Input) -> i32 {
+        input
+            .lines()
+            .flat_map(parse_all_muls)
+            .map(|(a, b)| a * b)
+            .sum::<i32>()
+    }
 
-pub(crate) fn solve(input: String) {
-    let part1: i32 = input
-        .lines()
-        .flat_map(parse_all_muls)
-        .map(|(a, b)| a * b)
-        .sum::<i32>();
-    let part2: i32 = parse_all_instructions(&*input)
-        .iter()
-        .map(|(a, b)| a * b)
-        .sum::<i32>();
-
-    println!("part1: {}, part2: {}", part1, part2);
+    fn part2(input: &Self::Input) -> i32 {
+        parse_all_instructions(input)
+            .iter()
+            .map(|(a, b)| a * b)
+            .sum::<i32>()
+    }
 }
@@ -1,14 +1,22 @@
 use nom::{
     bytes::complete::tag,
     character::complete::{digit1, line_ending},
-    combinator::map_res,
+    combinator::{map, map_res},
+    error::{Error, ErrorKind},
     multi::separated_list1,
-    sequence::separated_pair,
+    sequence::{pair, separated_pair},
     IResult,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-fn parse_rule(input: &str) -> IResult<&str, (u32, u32)> {
+use crate::error::{parse_with, ParseError};
+use crate::solution::Solution;
+
+type Rule = (u32, u32);
+type Rules = HashSet<Rule>;
+type Update = Vec<u32>;
+
+fn parse_rule(input: &str) -> IResult<&str, Rule> {
     let (input, (a, b)) = separated_pair(parse_number, tag("|"), parse_number)(input)?;
     Ok((input, (a, b)))
 }
@@ -17,26 +25,41 @@ fn parse_number(input: &str) -> IResult<&str, u32> {
     map_res(digit1, |s: &str| s.parse::<u32>())(input)
 }
 
-fn parse_rules(input: &str) -> IResult<&str, Vec<(u32, u32)>> {
-    separated_list1(line_ending, parse_rule)(input)
+fn parse_rules(input: &str) -> IResult<&str, Rules> {
+    map(separated_list1(line_ending, parse_rule), |rules| rules.into_iter().collect())(input)
 }
 
 fn parse_update(input: &str) -> IResult<&str, Vec<u32>> {
     separated_list1(tag(","), parse_number)(input)
 }
 
-fn parse_updates(input: &str) -> IResult<&str, Vec<Vec<u32>>> {
-    separated_list1(line_ending, parse_update)(input)
+/// Updates whose pages the rules can put in order; a page repeated within an update is an error
+/// at the repeat, and a cycle among an update's pages is an error at the update.
+fn parse_updates<'a>(rules: &Rules, input: &'a str) -> IResult<&'a str, Vec<Update>> {
+    let orderable = |input: &'a str| {
+        let (rest, update) = parse_update(input)?;
+        let mut seen = HashSet::new();
+        let pages = input[..input.len() - rest.len()].split(',');
+        if let Some((repeat, _)) = pages.zip(&update).find(|(_, page)| !seen.insert(**page)) {
+            return Err(nom::Err::Failure(Error::new(repeat, ErrorKind::Verify)));
+        }
+        match sort_update(rules, &update) {
+            Some(_) => Ok((rest, update)),
+            // a failure, so the cycle is reported rather than ending the list early
+            None => Err(nom::Err::Failure(Error::new(input, ErrorKind::Verify))),
+        }
+    };
+    separated_list1(line_ending, orderable)(input)
 }
 
-fn parse_input(input: &str) -> IResult<&str, (Vec<(u32, u32)>, Vec<Vec<u32>>)> {
+fn parse_input(input: &str) -> IResult<&str, (Rules, Vec<Update>)> {
     let (input, rules) = parse_rules(input)?;
-    let (input, _) = line_ending(input)?;
-    let (input, updates) = parse_updates(input)?;
+    let (input, _) = pair(line_ending, line_ending)(input)?;
+    let (input, updates) = parse_updates(&rules, input)?;
     Ok((input, (rules, updates)))
 }
 
-fn is_valid_update(rules: &[(u32, u32)], update: &[u32]) -> bool {
+fn is_valid_update(rules: &Rules, update: &[u32]) -> bool {
     let index_map: HashMap<u32, usize> = update
         .iter()
         .enumerate()
@@ -60,43 +83,138 @@ fn middle_page(update: &[u32]) -> u32 {
     update[mid_index]
 }
 
-fn part1(input: &str) -> u32 {
-    let (_, (rules, updates)) = parse_input(input).unwrap();
+/// The update's pages, which must be distinct, in an order that satisfies every rule between them, or `None` if
+/// the rules form a cycle.
+///
+/// A topological sort (Kahn's algorithm) of the rule graph restricted to the update's pages.
+fn sort_update(rules: &Rules, update: &[u32]) -> Option<Update> {
+    let mut before_count: HashMap<u32, usize> = update.iter().map(|&page| (page, 0)).collect();
+    let relevant: Vec<Rule> = rules
+        .iter()
+        .filter(|(before, after)| before_count.contains_key(before) && before_count.contains_key(after))
+        .copied()
+        .collect();
+    for (_, after) in &relevant {
+        *before_count.get_mut(after).unwrap() += 1;
+    }
+    // take pages in their original order when several are free, so the sort is deterministic
+    let mut ready: Vec<u32> = update.iter().rev().copied().filter(|page| before_count[page] == 0).collect();
+    let mut sorted = Vec::with_capacity(update.len());
+    while let Some(page) = ready.pop() {
+        sorted.push(page);
+        for &(_, after) in relevant.iter().filter(|(before, _)| *before == page) {
+            let count = before_count.get_mut(&after).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                ready.push(after);
+            }
+        }
+    }
+    (sorted.len() == update.len()).then_some(sorted)
+}
 
+fn part1(rules: &Rules, updates: &[Vec<u32>]) -> u32 {
     updates
         .iter()
-        .filter(|update| is_valid_update(&rules, update))
+        .filter(|update| is_valid_update(rules, update))
         .map(|update| middle_page(update))
         .sum()
 }
 
-pub fn solve(input: String) {
-    let result = part1(&input);
-    println!("Sum of middle pages of valid updates: {}", result);
+fn part2(rules: &Rules, updates: &[Vec<u32>]) -> u32 {
+    updates
+        .iter()
+        .filter(|update| !is_valid_update(rules, update))
+        .map(|update| middle_page(&sort_update(rules, update).expect("parse rejects cyclic updates")))
+        .sum()
+}
+
+pub(crate) struct Day5;
+
+impl Solution for Day5 {
+    type Input = (Rules, Vec<Vec<u32>>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_with(input, parse_input)
+    }
+
+    fn part1((rules, updates): &Self::Input) -> u32 {
+        part1(rules, updates)
+    }
+
+    fn part2((rules, updates): &Self::Input) -> u32 {
+        part2(rules, updates)
+    }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
 
-//     #[test]
-//     fn test_parse_input() {
-//         let input = "\
-// 47|53
-// 97|13
-// 
-// 75,47,61,53,29
-// 97,61,53,29,13";
-//         let (_, (rules, updates)) = parse_input(input).unwrap();
-//         assert_eq!(rules, vec![(47, 53), (97, 13)]);
-//         assert_eq!(
-//             updates,
-//             vec![vec![75, 47, 61, 53, 29], vec![97, 61, 53, 29, 13],]
-//         );
-//     }
+    const PROVIDED: &str = "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+";
+
+    #[test]
+    fn test_parse_input() {
+        let input = "\
+47|53
+97|13
+
+75,47,61,53,29
+97,61,53,29,13";
+        let (_, (rules, updates)) = parse_input(input).unwrap();
+        assert_eq!(rules, HashSet::from([(47, 53), (97, 13)]));
+        assert_eq!(
+            updates,
+            vec![vec![75, 47, 61, 53, 29], vec![97, 61, 53, 29, 13],]
+        );
+    }
+
+    #[test]
+    fn test_parse_cycle() {
+        let input = "1|2\n2|3\n3|1\n\n1,2\n1,2,3\n";
+        let error = Day5::parse(input).unwrap_err();
+        assert_eq!((error.line, error.column, error.kind), (6, 1, ErrorKind::Verify));
+    }
+
+    #[test]
+    fn test_parse_repeated_page() {
+        // reported at the second 2, not at the start of the line as a cycle would be
+        let input = "1|2\n2|3\n\n1,2,3\n3,2,1,2\n";
+        let error = Day5::parse(input).unwrap_err();
+        assert_eq!((error.line, error.column, error.kind), (5, 7, ErrorKind::Verify));
+    }
 
     #[test]
     fn test_is_valid_update() {
-        let rules = vec![(47, 53), (75, 29)];
+        let rules = HashSet::from([(47, 53), (75, 29)]);
         let update = vec![75, 47, 53, 29];
         assert!(is_valid_update(&rules, &update));
         let invalid_update = vec![75, 53, 47, 29];
@@ -109,9 +227,24 @@ mod tests {
         assert_eq!(middle_page(&update), 61);
     }
 
-    // #[test]
-    // fn test_part1() {
-    //     let input = "47|53\n97|13\n\n75,47,61,53,29\n97,61,53,29,13\n75,29,13\n";
-    //     assert_eq!(part1(input), 143);
-    // }
+    #[test]
+    fn test_sort_update() {
+        let (rules, _) = Day5::parse(PROVIDED).unwrap();
+        assert_eq!(sort_update(&rules, &[75, 97, 47, 61, 53]), Some(vec![97, 75, 47, 61, 53]));
+        assert_eq!(sort_update(&rules, &[61, 13, 29]), Some(vec![61, 29, 13]));
+        assert_eq!(sort_update(&rules, &[97, 13, 75, 29, 47]), Some(vec![97, 75, 47, 29, 13]));
+        assert_eq!(sort_update(&HashSet::from([(1, 2), (2, 1)]), &[1, 2]), None);
+    }
+
+    #[test]
+    fn test_part1() {
+        let (rules, updates) = Day5::parse(PROVIDED).unwrap();
+        assert_eq!(part1(&rules, &updates), 143);
+    }
+
+    #[test]
+    fn test_part2() {
+        let (rules, updates) = Day5::parse(PROVIDED).unwrap();
+        assert_eq!(part2(&rules, &updates), 123);
+    }
 }
@@ -7,12 +7,12 @@ use nom::{
     sequence::terminated,
     IResult,
 };
-use std::time::Instant;
+
+use crate::error::{parse_with, ParseError};
+use crate::solution::Solution;
 
 fn parse_i64(input: &str) -> IResult<&str, i64, Error<&str>> {
-    let (input, num_str) = digit1::<&str, Error<&str>>(input)?;
-    let val = num_str.parse::<i64>().unwrap();
-    Ok((input, val))
+    map_res(digit1::<&str, Error<&str>>, |s: &str| s.parse::<i64>())(input)
 }
 
 fn parse_nums(input: &str) -> IResult<&str, Vec<i64>, Error<&str>> {
@@ -35,7 +35,9 @@ fn parse_line(input: &str) -> IResult<&str, (i64, Vec<i64>), Error<&str>> {
     Ok((input, (test_val, nums_line)))
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<(i64, Vec<i64>)>, Error<&str>> {
+type Equation = (i64, Vec<i64>);
+
+fn parse_input(input: &str) -> IResult<&str, Vec<Equation>, Error<&str>> {
     many1(terminated(parse_line, opt(tag("\n"))))(input)
 }
 
@@ -123,18 +125,22 @@ fn part2(data: &[(i64, Vec<i64>)]) -> i64 {
         .sum()
 }
 
-pub fn solve(input: String) {
-    let start = Instant::now();
-    let (_, data) = parse_input(&input).unwrap();
-    let parse_duration = start.elapsed();
-    let start_solve = Instant::now();
-    let ans_part1 = part1(&data);
-    let ans_part2 = part2(&data);
-    let solve_duration = start_solve.elapsed();
-    println!("Part1: {}", ans_part1);
-    println!("Part2: {}", ans_part2);
-    println!("Parsing took: {} microseconds", parse_duration.as_micros());
-    println!("Solving took: {} microseconds", solve_duration.as_micros());
+pub(crate) struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<(i64, Vec<i64>)>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_with(input, parse_input)
+    }
+
+    fn part1(data: &Self::Input) -> i64 {
+        part1(data)
+    }
+
+    fn part2(data: &Self::Input) -> i64 {
+        part2(data)
+    }
 }
 
 #[cfg(test)]
@@ -182,8 +188,8 @@ mod tests {
         }
     }
     mod part_2 {
-        use crate::puzzles::day7::{can_make_target_with_concatenation, part2};
-        use crate::puzzles::day7_test_util;
+        use crate::puzzles::day7::{can_make_target_with_concatenation, part2, Day7};
+        use crate::solution::Solution;
 
         #[test]
         fn provided() {
@@ -221,14 +227,13 @@ mod tests {
         }
         #[test]
         fn verify_successful_sequences() {
-            let vec = day7_test_util::get_test_cases();
-            for (line, nums, ops, expected_result) in vec {
+            let equations = Day7::parse(include_str!("../../examples/day7/concatenation.txt")).unwrap();
+            for (line, (expected_result, nums)) in equations.into_iter().enumerate() {
                 assert!(
                     can_make_target_with_concatenation(expected_result, &nums),
-                    "Line {}: nums = {:?}, ops = {:?}, expected = {}",
-                    line,
+                    "Line {}: nums = {:?}, expected = {}",
+                    line + 1,
                     nums,
-                    ops,
                     expected_result
                 );
             }
@@ -258,7 +263,7 @@ mod tests {
         fn test_concatenation_only() {
             // Only concatenation makes sense:
             // 123: 1 2 3 -> 1||2||3 = 123
-            assert_eq!(part2(&vec![(123, vec![1, 2, 3])]), 123);
+            assert_eq!(part2(&[(123, vec![1, 2, 3])]), 123);
         }
 
         // Test concatenation + addition:
@@ -1,159 +1,76 @@
-use nom::{
-    bytes::complete::take_while1, character::complete::newline, multi::separated_list1, IResult,
-};
 use std::collections::HashSet;
-use std::time::Instant;
+use std::iter::successors;
 
-fn parse(input: &str) -> IResult<&str, Vec<Vec<char>>> {
-    separated_list1(
-        newline,
-        take_while1(|c: char| c.is_alphanumeric() || c == '.' || c == '#'),
-    )(input)
-    .map(|(next_input, rows)| {
-        (
-            next_input,
-            rows.iter().map(|row| row.chars().collect()).collect(),
-        )
-    })
-}
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point {
-    x: isize,
-    y: isize,
-}
+use crate::error::ParseError;
+use crate::grid::{Grid, Point};
+use crate::solution::Solution;
 
-fn find_antennas(grid: &[Vec<char>]) -> Vec<(Point, char)> {
-    let mut antennas = Vec::new();
-    for (y, row) in grid.iter().enumerate() {
-        for (x, &cell) in row.iter().enumerate() {
-            if cell != '.' && cell != '#' {
-                antennas.push((
-                    Point {
-                        x: x as isize,
-                        y: y as isize,
-                    },
-                    cell,
-                ));
-            }
-        }
-    }
-    antennas
+fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse(input, |c| (c.is_alphanumeric() || c == '.' || c == '#').then_some(c))
 }
-fn calculate_antinodes(antennas: &[(Point, char)], grid_width: isize, grid_height: isize) -> usize {
-    let mut antinodes = HashSet::new();
 
-    for i in 0..antennas.len() {
-        for j in i + 1..antennas.len() {
-            let (p1, freq1) = antennas[i];
-            let (p2, freq2) = antennas[j];
+fn find_antennas(grid: &Grid<char>) -> Vec<(Point, char)> {
+    grid.iter()
+        .filter(|(_, &cell)| cell != '.' && cell != '#')
+        .map(|(point, &cell)| (point, cell))
+        .collect()
+}
 
-            if freq1 != freq2 {
-                continue;
-            }
+/// Every pair of antennas on the same frequency, with the step from the first to the second.
+fn pairs(antennas: &[(Point, char)]) -> impl Iterator<Item = (Point, Point, isize, isize)> + '_ {
+    antennas.iter().enumerate().flat_map(move |(i, &(p1, freq1))| {
+        antennas[i + 1..]
+            .iter()
+            .filter(move |&&(_, freq2)| freq1 == freq2)
+            .map(move |&(p2, _)| (p1, p2, p2.x as isize - p1.x as isize, p2.y as isize - p1.y as isize))
+    })
+}
 
-            let mut candidates = HashSet::new();
-            let dx = p2.x - p1.x;
-            let dy = p2.y - p1.y;
-            candidates.insert(Point {
-                x: p1.x - dx,
-                y: p1.y - dy,
-            });
-            candidates.insert(Point {
-                x: p2.x + dx,
-                y: p2.y + dy,
+fn calculate_antinodes(antennas: &[(Point, char)], grid: &Grid<char>) -> usize {
+    let mut antinodes = HashSet::new();
+    for (p1, p2, dx, dy) in pairs(antennas) {
+        [p1.offset(-dx, -dy), p2.offset(dx, dy)]
+            .into_iter()
+            .flatten()
+            .filter(|&point| grid.contains(point))
+            .for_each(|point| {
+                antinodes.insert(point);
             });
-            // println!("candidates: {:?}", candidates);
-            candidates
-                .iter()
-                .filter(|&Point { x, y }| {
-                    x >= &0isize && y >= &0isize && x < &grid_width && y < &grid_height
-                })
-                .for_each(|&Point { x, y }| {
-                    antinodes.insert(Point { x, y });
-                });
-        }
     }
-    // println!("antennas: {:?}", antennas);
-    // println!("antinodes: {:?}", antinodes);
     antinodes.len()
 }
-fn calculate_antinodes2(
-    antennas: &[(Point, char)],
-    grid_width: isize,
-    grid_height: isize,
-) -> usize {
+fn calculate_antinodes2(antennas: &[(Point, char)], grid: &Grid<char>) -> usize {
     let mut antinodes = HashSet::new();
-
-    for i in 0..antennas.len() {
-        for j in i + 1..antennas.len() {
-            let (p1, freq1) = antennas[i];
-            let (p2, freq2) = antennas[j];
-
-            if freq1 != freq2 {
-                continue;
-            }
-
-            let dx = p2.x - p1.x;
-            let dy = p2.y - p1.y;
-            let mut mult = 0;
-            while p1.x - dx * mult >= 0
-                && p1.y - dy * mult >= 0
-                && p1.x - dx * mult < grid_width
-                && p1.y - dy * mult < grid_height
-            {
-                let x = p1.x - dx * mult;
-                let y = p1.y - dy * mult;
-                antinodes.insert(Point { x, y });
-                mult += 1;
-            }
-            mult = 0;
-            while p2.x + dx * mult >= 0
-                && p2.y + dy * mult >= 0
-                && p2.x + dx * mult < grid_width
-                && p2.y + dy * mult < grid_height
-            {
-                antinodes.insert(Point {
-                    x: p2.x + dx * mult,
-                    y: p2.y + dy * mult,
-                });
-                mult += 1
-            }
-        }
+    for (p1, p2, dx, dy) in pairs(antennas) {
+        let back = successors(Some(p1), |point| point.offset(-dx, -dy));
+        let forward = successors(Some(p2), |point| point.offset(dx, dy));
+        antinodes.extend(back.take_while(|&point| grid.contains(point)));
+        antinodes.extend(forward.take_while(|&point| grid.contains(point)));
     }
-    // println!("antennas: {:?}", antennas);
-    // println!("antinodes: {:?}", antinodes);
     antinodes.len()
 }
-pub fn part1(input: &str) -> usize {
-    let (_, grid) = parse(input).unwrap();
-    let antennas = find_antennas(&grid);
-    let grid_width = grid[0].len() as isize;
-    let grid_height = grid.len() as isize;
-
-    let result = calculate_antinodes(&antennas, grid_width, grid_height);
-    result
+pub fn part1(grid: &Grid<char>) -> usize {
+    calculate_antinodes(&find_antennas(grid), grid)
 }
-pub fn part2(input: &str) -> usize {
-    let (_, grid) = parse(input).unwrap();
-    let antennas = find_antennas(&grid);
-    let grid_width = grid[0].len() as isize;
-    let grid_height = grid.len() as isize;
-
-    let result = calculate_antinodes2(&antennas, grid_width, grid_height);
-    result
+pub fn part2(grid: &Grid<char>) -> usize {
+    calculate_antinodes2(&find_antennas(grid), grid)
 }
-pub fn solve(input: String) {
-    let start = Instant::now();
+pub(crate) struct Day8;
 
-    let parse_duration = start.elapsed();
-    let start_solve = Instant::now();
-    let ans_part1 = part1(&*input);
-    let ans_part2 = part2(&*input);
-    let solve_duration = start_solve.elapsed();
-    println!("Part1: {}", ans_part1);
-    println!("Part2: {}", ans_part2);
-    println!("Parsing took: {} microseconds", parse_duration.as_micros());
-    println!("Solving took: {} microseconds", solve_duration.as_micros());
+impl Solution for Day8 {
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        part2(input)
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -173,22 +90,22 @@ mod tests {
 ..........
 ..........
 ..........";
-            assert_eq!(part1(input), 2);
+            assert_eq!(part1(&Day8::parse(input).unwrap()), 2);
         }
         #[test]
         fn simpler() {
             let input = "..a.a.....";
-            assert_eq!(part1(input), 2);
+            assert_eq!(part1(&Day8::parse(input).unwrap()), 2);
         }
         #[test]
         fn border() {
             let input = "a.a.....";
-            assert_eq!(part1(input), 1);
+            assert_eq!(part1(&Day8::parse(input).unwrap()), 1);
         }
         #[test]
         fn collision() {
             let input = "a.a...b.b";
-            assert_eq!(part1(input), 1);
+            assert_eq!(part1(&Day8::parse(input).unwrap()), 1);
         }
         #[test]
         fn test_part1() {
@@ -205,11 +122,12 @@ mod tests {
 .........A..
 ............
 ............";
-            assert_eq!(part1(input), 14);
+            assert_eq!(part1(&Day8::parse(input).unwrap()), 14);
         }
     }
     mod part2 {
-        use crate::puzzles::day8::part2;
+        use crate::puzzles::day8::{part2, Day8};
+        use crate::solution::Solution;
 
         #[test]
         fn provided() {
@@ -226,7 +144,7 @@ mod tests {
 .........A..
 ............
 ............";
-            assert_eq!(part2(input), 34);
+            assert_eq!(part2(&Day8::parse(input).unwrap()), 34);
         }
         #[test]
         fn simple() {
@@ -241,7 +159,7 @@ T....#....
 ..........
 ....#.....
 ..........";
-            assert_eq!(part2(input), 9);
+            assert_eq!(part2(&Day8::parse(input).unwrap()), 9);
         }
     }
 }
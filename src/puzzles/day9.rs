@@ -1,247 +1,596 @@
-use std::time::Instant;
-use timing_util::measure_time;
-
-//add clone
-#[derive(Debug, Clone, PartialEq, Default)]
-struct File {
-    id: usize,
-    size: usize,
-}
-#[derive(Debug, Clone, PartialEq, Default)]
-struct Partition {
-    id: usize,
-    data: [Option<File>; 9],
-    free: usize,
-}
+use log::{debug, log_enabled, Level};
+use nom::error::ErrorKind;
+use timing_util::span;
+
+use crate::error::ParseError;
+use crate::puzzles::day9_disk::{Disk, Policy};
+use crate::solution::{param_value, Config, Configured, ParamError, Params, Solution};
 
-/// Parse all digits from the input into an array of integers.
-fn parse_digits(input: &str) -> Vec<usize> {
+/// Parse all digits from the input into an array of integers, ignoring whitespace.
+fn parse_digits(input: &str) -> Result<Vec<usize>, ParseError> {
     input
-        .chars()
-        .filter_map(|ch| ch.to_digit(10).map(|d| d as usize))
+        .char_indices()
+        .filter(|(_, ch)| !ch.is_whitespace())
+        .map(|(i, ch)| {
+            ch.to_digit(10)
+                .map(|d| d as usize)
+                .ok_or_else(|| ParseError::at(input, &input[i..], ErrorKind::Digit))
+        })
         .collect()
 }
 
-/// Chunk the digits array into an array of Partitions
-fn chunk(input: &[usize]) -> Vec<Partition> {
-    let capacity = input.len() / 2; // Assuming each file has size + free space
-    let mut partitions = Vec::with_capacity(capacity);
+/// The compaction policy each part uses.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Policies {
+    part1: Policy,
+    part2: Policy,
+}
 
-    input.chunks(2).enumerate().for_each(|(id, chunk)| {
-        let size = chunk[0];
-        let free = if chunk.len() == 2 { chunk[1] } else { 0 };
+impl Default for Policies {
+    fn default() -> Self {
+        Policies { part1: Policy::Blocks, part2: Policy::FirstFit }
+    }
+}
 
-        let file = File { size, id };
-        let mut data: [Option<File>; 9] = Default::default();
-        data[0] = Some(file);
+impl Config for Policies {
+    const KEYS: &'static [&'static str] = &["part1", "part2"];
 
-        partitions.push(Partition { id, data, free });
-    });
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError> {
+        match key {
+            "part1" => self.part1 = param_value(key, value)?,
+            "part2" => self.part2 = param_value(key, value)?,
+            _ => return Err(Self::unknown(key)),
+        }
+        Ok(())
+    }
+}
 
-    partitions
+/// The most blocks a disk can have for a verbose run to draw it at every step; the puzzle
+/// input's has around 95 000.
+const DRAWN_BLOCKS: usize = 200;
+
+/// Lay out the disk, compact it under `policy` and checksum it.
+///
+/// With debug logging on, a disk small enough to draw is logged as it is after every move, and
+/// then as the reference model for `policy` compacts it, if there is one.
+fn compact(digits: &[usize], policy: Policy) -> i64 {
+    let mut disk = span!("layout", Disk::from_map(digits));
+    if log_enabled!(Level::Debug) && disk.blocks() <= DRAWN_BLOCKS {
+        debug!("{}", disk);
+        disk.clone().steps(policy).for_each(|step| debug!("{}", step));
+        log_reference(digits, policy);
+    }
+    let report = span!("compact", disk.compact(policy));
+    debug!("{}: {}", policy, report);
+    report.checksum
 }
-fn compact_partitions(mut partitions: Vec<Partition>) -> Vec<Partition> {
-    let mut last_free_of_size = [0; 9];
-
-    // Iterate over partitions in reverse
-    for i in (0..partitions.len()).rev() {
-        // Clone the file to avoid immutable borrow conflicts
-        let file = partitions[i].data[0].as_ref().cloned();
-
-        if let Some(file) = file {
-            let last = last_free_of_size[file.size - 1];
-            if last < partitions[i].id {
-                // Use split_at_mut to safely create non-overlapping mutable slices
-                let (left, _right) = partitions.split_at_mut(i);
-
-                // Check partitions in the range `last..i` for available space
-                for candidate_partition in &mut left[last..] {
-                    if candidate_partition.free >= file.size {
-                        // Find the first empty slot in the candidate partition
-                        if let Some(slot) = candidate_partition
-                            .data
-                            .iter_mut()
-                            .find(|entry| entry.is_none())
-                        {
-                            // Move the file
-                            *slot = Some(file.clone());
-                            candidate_partition.free -= file.size;
-
-                            // Update the last free of this size
-                            last_free_of_size[file.size - 1] = candidate_partition.id;
-
-                            // Set the file.id in the original position to 0
-                            if let Some(original_file) = partitions[i].data[0].as_mut() {
-                                original_file.id = 0;
-                                // 0 works it will not now accrue to the check sum, and this preserves the empty space in the correct position
-                                // there is a weird edge case where a small size file followed by a large space could have some other file moved into its free space, but then get moved itself.
-                                // so long as we set the id to 0, and then process the checksum normally, this won't be an issue.
-                                // I'll write the test case for this situation
-                            }
-                            break;
-                        }
-                    }
-                }
+
+/// Log the reference model's steps and checksum for `policy`: the block array for part 1's
+/// blocks, the partitions for part 2's first fit. The other policies have none.
+fn log_reference(digits: &[usize], policy: Policy) {
+    match policy {
+        Policy::Blocks => {
+            let mut disk = reference::populate_disk(digits);
+            debug!("reference: {}", reference::render_disk(&disk));
+            for step in reference::compact_disk_steps(disk.clone()) {
+                debug!("reference: {}", reference::render_disk(&step));
+                disk = step;
+            }
+            debug!("reference checksum: {}", reference::calculate_checksum(&disk));
+        }
+        Policy::FirstFit => {
+            let mut partitions = reference::chunk(digits);
+            debug!("reference: {}", reference::render_partitions(&partitions));
+            for step in reference::compact_partitions_steps(partitions.clone()) {
+                debug!("reference: {}", reference::render_partitions(&step));
+                partitions = step;
             }
+            debug!("reference checksum: {}", reference::calculate_checksum_from_partition(&partitions));
         }
+        Policy::BestFit | Policy::WorstFit => {}
     }
-    partitions
 }
 
-/// Create the initial disk array based on parsed digits.
-fn create_disk(digits: &[usize]) -> Vec<i32> {
-    let disk_size: usize = digits.iter().sum(); // Total size of the disk
-    println!("creating a disk : {}", &disk_size);
-    vec![-1; disk_size]
+pub(crate) struct Day9;
+
+impl Solution for Day9 {
+    type Input = Configured<Vec<usize>, Policies>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        span!("parse", parse_digits(input)).map(Configured::new)
+    }
+
+    fn configure(input: &mut Self::Input, params: &Params) -> Result<(), ParamError> {
+        input.config.apply(params)
+    }
+
+    /// Compact block by block and checksum the disk, unless configured otherwise.
+    fn part1(Configured { input: digits, config }: &Self::Input) -> i64 {
+        span!("part1", compact(digits, config.part1))
+    }
+
+    /// Compact whole files into the first gap that fits and checksum the disk, unless configured otherwise.
+    fn part2(Configured { input: digits, config }: &Self::Input) -> i64 {
+        span!("part2", compact(digits, config.part2))
+    }
 }
 
-/// Fill the disk array with file IDs and free spaces based on parsed digits.
-fn populate_disk(digits: &[usize]) -> Vec<i32> {
-    let mut disk = create_disk(digits);
-    let mut file_id = 0; // Start file IDs at 0
-    let mut pos = 0;
+/// The block array and the partitions are the puzzle-sized models the disk in `day9_disk`
+/// grew out of; they stay as the references its policies are checked against, and verbose runs
+/// draw them beside it.
+mod reference {
+    use crate::puzzles::day9_disk::block_char;
 
-    let mut iter = digits.iter();
-    while let Some(&file_len) = iter.next() {
-        for _ in 0..file_len {
-            disk[pos] = file_id;
-            pos += 1;
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub(super) struct File {
+        pub(super) id: usize,
+        pub(super) size: usize,
+    }
+    /// Room for a partition's own file and the most files that can move into its free space:
+    /// nine one-block files filling a gap of nine.
+    pub(super) const SLOTS: usize = 10;
+
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub(super) struct Partition {
+        pub(super) id: usize,
+        pub(super) data: [Option<File>; SLOTS],
+        pub(super) free: usize,
+    }
+
+    /// Chunk the digits array into an array of Partitions
+    pub(super) fn chunk(input: &[usize]) -> Vec<Partition> {
+        let capacity = input.len() / 2; // Assuming each file has size + free space
+        let mut partitions = Vec::with_capacity(capacity);
+
+        input.chunks(2).enumerate().for_each(|(id, chunk)| {
+            let size = chunk[0];
+            let free = if chunk.len() == 2 { chunk[1] } else { 0 };
+
+            let file = File { size, id };
+            let mut data: [Option<File>; SLOTS] = Default::default();
+            data[0] = Some(file);
+
+            partitions.push(Partition { id, data, free });
+        });
+
+        partitions
+    }
+    #[cfg(test)]
+    pub(super) fn compact_partitions(mut partitions: Vec<Partition>) -> Vec<Partition> {
+        let mut cursor = PartitionCursor::new(&partitions);
+        while cursor.step(&mut partitions) {}
+        partitions
+    }
+
+    /// The partitions after each file `compact_partitions` moves.
+    pub(super) fn compact_partitions_steps(mut partitions: Vec<Partition>) -> impl Iterator<Item = Vec<Partition>> {
+        let mut cursor = PartitionCursor::new(&partitions);
+        std::iter::from_fn(move || cursor.step(&mut partitions).then(|| partitions.clone()))
+    }
+
+    /// Where `compact_partitions` has got to.
+    pub(super) struct PartitionCursor {
+        last_free_of_size: [usize; 9],
+        /// The partition whose file is tried next, counting down; `None` once all have been tried.
+        next: Option<usize>,
+    }
+
+    impl PartitionCursor {
+        fn new(partitions: &[Partition]) -> Self {
+            PartitionCursor { last_free_of_size: [0; 9], next: partitions.len().checked_sub(1) }
         }
-        file_id += 1;
 
-        if let Some(&free_len) = iter.next() {
-            pos += free_len; // Skip free spaces
+        /// Move the next file that fits somewhere to its left; false once none is left to try.
+        fn step(&mut self, partitions: &mut [Partition]) -> bool {
+            // Iterate over partitions in reverse
+            while let Some(i) = self.next {
+                self.next = i.checked_sub(1);
+                if self.move_file(partitions, i) {
+                    return true;
+                }
+            }
+            false
+        }
+
+        fn move_file(&mut self, partitions: &mut [Partition], i: usize) -> bool {
+            // Clone the file to avoid immutable borrow conflicts
+            let file = partitions[i].data[0].as_ref().cloned();
+
+            if let Some(file) = file {
+                let last = self.last_free_of_size[file.size - 1];
+                if last < partitions[i].id {
+                    // Use split_at_mut to safely create non-overlapping mutable slices
+                    let (left, _right) = partitions.split_at_mut(i);
+
+                    // Check partitions in the range `last..i` for available space
+                    for candidate_partition in &mut left[last..] {
+                        if candidate_partition.free >= file.size {
+                            // Find the first empty slot in the candidate partition
+                            if let Some(slot) = candidate_partition
+                                .data
+                                .iter_mut()
+                                .find(|entry| entry.is_none())
+                            {
+                                // Move the file
+                                *slot = Some(file.clone());
+                                candidate_partition.free -= file.size;
+
+                                // Update the last free of this size
+                                self.last_free_of_size[file.size - 1] = candidate_partition.id;
+
+                                // Set the file.id in the original position to 0
+                                if let Some(original_file) = partitions[i].data[0].as_mut() {
+                                    original_file.id = 0;
+                                    // 0 works it will not now accrue to the check sum, and this preserves the empty space in the correct position
+                                    // there is a weird edge case where a small size file followed by a large space could have some other file moved into its free space, but then get moved itself.
+                                    // so long as we set the id to 0, and then process the checksum normally, this won't be an issue.
+                                    // I'll write the test case for this situation
+                                }
+                                return true;
+                            }
+                        }
+                    }
+                }
+            }
+            false
         }
     }
-    disk
-}
 
-/// Compact the disk by moving blocks one at a time from the end to the leftmost free space.
-fn compact_disk(disk: &mut Vec<i32>) {
-    let mut write_pos = 0; // Pointer to the leftmost free space
-    let mut read_pos = disk.len() - 1; // Start from the last block
+    /// The partitions drawn as the puzzle draws a disk, e.g. `0099.111...2...`. A file moved out
+    /// of its partition, left behind with id 0, is drawn as the free space it now is.
+    pub(super) fn render_partitions(partitions: &[Partition]) -> String {
+        let mut rendered = String::new();
+        for partition in partitions {
+            for (slot, file) in partition.data.iter().enumerate() {
+                if let Some(file) = file {
+                    let moved = slot == 0 && file.id == 0 && partition.id != 0;
+                    let block = if moved { '.' } else { block_char(file.id) };
+                    rendered.extend(std::iter::repeat_n(block, file.size));
+                }
+            }
+            rendered.extend(std::iter::repeat_n('.', partition.free));
+        }
+        rendered
+    }
+
+    /// Create the initial disk array based on parsed digits.
+    pub(super) fn create_disk(digits: &[usize]) -> Vec<i32> {
+        let disk_size: usize = digits.iter().sum(); // Total size of the disk
+        vec![-1; disk_size]
+    }
 
-    while read_pos >= write_pos {
-        if disk[read_pos] != -1 {
-            // Find the next free space on the left
-            while write_pos < disk.len() && disk[write_pos] != -1 {
-                write_pos += 1;
+    /// Fill the disk array with file IDs and free spaces based on parsed digits.
+    pub(super) fn populate_disk(digits: &[usize]) -> Vec<i32> {
+        let mut disk = create_disk(digits);
+        let mut file_id = 0; // Start file IDs at 0
+        let mut pos = 0;
+
+        let mut iter = digits.iter();
+        while let Some(&file_len) = iter.next() {
+            for _ in 0..file_len {
+                disk[pos] = file_id;
+                pos += 1;
             }
-            if write_pos < read_pos {
-                disk[write_pos] = disk[read_pos];
-                disk[read_pos] = -1;
+            file_id += 1;
+
+            if let Some(&free_len) = iter.next() {
+                pos += free_len; // Skip free spaces
             }
         }
-        if read_pos == 0 {
-            break;
-        } // Prevent underflow
-        read_pos -= 1;
+        disk
     }
-}
 
-/// Calculate the checksum of the disk.
-fn calculate_checksum(disk: &[i32]) -> i64 {
-    disk.iter()
-        .enumerate()
-        .filter(|(_, &id)| id != -1)
-        .map(|(pos, &id)| pos as i64 * id as i64)
-        .sum()
-}
+    /// Compact the disk by moving blocks one at a time from the end to the leftmost free space.
+    #[cfg(test)]
+    pub(super) fn compact_disk(disk: &mut [i32]) {
+        let mut cursor = BlockCursor::new(disk);
+        while cursor.step(disk) {}
+    }
 
-/// Solve function for parsing, compaction, and checksum calculation.
-pub fn solve(input: String) -> (i64, i64, i64) {
-    let start = Instant::now();
-    let digits = parse_digits(&input);
-    let mut disk = populate_disk(&digits);
-    let parse_duration = start.elapsed();
-
-    let start_compact = Instant::now();
-    compact_disk(&mut disk);
-    let part1 = calculate_checksum(&disk);
-    let part1_duration = start_compact.elapsed();
-
-    let start_compact = Instant::now();
-    let partitions = measure_time!({ compact_partitions(measure_time!({ chunk(&digits) })) });
-
-    let part2_2: i64 = measure_time!({ calculate_checksum_from_partition(&partitions) });
-    let part2_2_duration = start_compact.elapsed();
-
-    println!("Checksums: {} {} 0", part1, part2_2);
-    println!("Parsing took: {} microseconds", parse_duration.as_micros());
-    println!(
-        "Compaction and checksum took: {}, {} microseconds",
-        part1_duration.as_micros(),
-        part2_2_duration.as_micros()
-    );
-    println!("total duration {:?}", start.elapsed());
-    (part1, part2_2, part2_2)
-}
+    /// The disk after each block `compact_disk` moves.
+    pub(super) fn compact_disk_steps(mut disk: Vec<i32>) -> impl Iterator<Item = Vec<i32>> {
+        let mut cursor = BlockCursor::new(&disk);
+        std::iter::from_fn(move || cursor.step(&mut disk).then(|| disk.clone()))
+    }
+
+    /// Where `compact_disk` has got to.
+    pub(super) struct BlockCursor {
+        /// The leftmost block that may be free.
+        write_pos: usize,
+        /// The block tried next, counting down from the end; `None` once past the start.
+        read_pos: Option<usize>,
+    }
 
-fn calculate_checksum_from_partition(partitions: &Vec<Partition>) -> i64 {
-    let mut sum: i64 = 0;
-    let mut index = 0;
+    impl BlockCursor {
+        fn new(disk: &[i32]) -> Self {
+            BlockCursor { write_pos: 0, read_pos: disk.len().checked_sub(1) }
+        }
 
-    for partition in partitions {
-        for maybe_file in &partition.data {
-            if let Some(file) = maybe_file {
+        /// Move the next block into the leftmost free space; false once the disk is compact.
+        fn step(&mut self, disk: &mut [i32]) -> bool {
+            while let Some(read_pos) = self.read_pos.filter(|&read_pos| read_pos >= self.write_pos) {
+                self.read_pos = read_pos.checked_sub(1);
+                if disk[read_pos] != -1 {
+                    // Find the next free space on the left
+                    while self.write_pos < disk.len() && disk[self.write_pos] != -1 {
+                        self.write_pos += 1;
+                    }
+                    if self.write_pos < read_pos {
+                        disk[self.write_pos] = disk[read_pos];
+                        disk[read_pos] = -1;
+                        return true;
+                    }
+                }
+            }
+            false
+        }
+    }
+
+    /// The disk drawn as the puzzle draws it, e.g. `00...111...2...`.
+    pub(super) fn render_disk(disk: &[i32]) -> String {
+        disk.iter().map(|&id| if id == -1 { '.' } else { block_char(id as usize) }).collect()
+    }
+
+    /// Calculate the checksum of the disk.
+    pub(super) fn calculate_checksum(disk: &[i32]) -> i64 {
+        disk.iter()
+            .enumerate()
+            .filter(|(_, &id)| id != -1)
+            .map(|(pos, &id)| pos as i64 * id as i64)
+            .sum()
+    }
+
+    pub(super) fn calculate_checksum_from_partition(partitions: &[Partition]) -> i64 {
+        let mut sum: i64 = 0;
+        let mut index = 0;
+
+        for partition in partitions {
+            for file in partition.data.iter().flatten() {
                 let id = file.id as i64;
                 for _ in 0..file.size {
                     sum += id * index;
                     index += 1;
                 }
             }
+            index += partition.free as i64;
         }
-        index += partition.free as i64;
+        sum
     }
-    sum
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::reference::*;
+    use crate::check;
+    use crate::generate::Rng;
+
+    /// Move each whole file, highest id first, to the run of free blocks before it that `policy`
+    /// picks: part 2 done block by block on the disk `compact_disk` works on.
+    fn compact_files(disk: &mut [i32], policy: Policy) {
+        let highest = disk.iter().copied().max().unwrap_or(-1);
+        for id in (0..=highest).rev() {
+            let Some(start) = disk.iter().position(|&block| block == id) else {
+                continue;
+            };
+            let size = disk.iter().filter(|&&block| block == id).count();
+            // every whole run of free blocks before the file, as (start, length)
+            let mut runs: Vec<(usize, usize)> = vec![];
+            for (position, &block) in disk[..start].iter().enumerate() {
+                match (block, runs.last_mut()) {
+                    (-1, Some((run, len))) if *run + *len == position => *len += 1,
+                    (-1, _) => runs.push((position, 1)),
+                    _ => {}
+                }
+            }
+            let mut fits = runs.into_iter().filter(|&(_, len)| len >= size);
+            let run = match policy {
+                Policy::FirstFit => fits.next(),
+                Policy::BestFit => fits.min_by_key(|&(run, len)| (len, run)),
+                Policy::WorstFit => fits.max_by_key(|&(run, len)| (len, std::cmp::Reverse(run))),
+                Policy::Blocks => unreachable!("compact_disk moves blocks"),
+            };
+            if let Some((run, _)) = run {
+                disk[run..run + size].fill(id);
+                disk[start..start + size].fill(-1);
+            }
+        }
+    }
+
+    /// (file size, free space after it) pairs, with sizes as the puzzle's single digits allow.
+    /// Files are often a single block with no gap after them, so runs of them can fill a big gap.
+    fn disk_map(rng: &mut Rng) -> Vec<(u8, u8)> {
+        (0..rng.between(1, 30))
+            .map(|_| if rng.chance(50) { (1, 0) } else { (rng.between(1, 9) as u8, rng.between(0, 9) as u8) })
+            .collect()
+    }
+
+    /// (file size, free space after it) pairs with sizes past a single digit, and files of size 0.
+    fn large_disk_map(rng: &mut Rng) -> Vec<(u8, u8)> {
+        (0..rng.between(1, 30)).map(|_| (rng.between(0, 25) as u8, rng.between(0, 25) as u8)).collect()
+    }
+
+    fn has_blocks(map: &[(u8, u8)]) -> bool {
+        map.iter().any(|&(size, free)| size + free > 0)
+    }
+
+    fn digits(map: &[(u8, u8)]) -> Vec<usize> {
+        map.iter().flat_map(|&(size, free)| [size as usize, free as usize]).collect()
+    }
+
+    #[test]
+    fn compact_partitions_agrees_with_compact_files_test() {
+        // partitions cannot merge the gaps either side of a file of size 0, so every file has blocks
+        let valid = |map: &Vec<(u8, u8)>| !map.is_empty() && map.iter().all(|&(size, free)| (1..=9).contains(&size) && free <= 9);
+        let naive = |map: &Vec<(u8, u8)>| {
+            let mut disk = populate_disk(&digits(map));
+            compact_files(&mut disk, Policy::FirstFit);
+            calculate_checksum(&disk)
+        };
+        let fast = |map: &Vec<(u8, u8)>| calculate_checksum_from_partition(&compact_partitions(chunk(&digits(map))));
+        check::agree(2000, disk_map, valid, naive, fast);
+    }
+
+    #[test]
+    fn disk_blocks_agrees_with_compact_disk_test() {
+        let naive = |map: &Vec<(u8, u8)>| {
+            let mut disk = populate_disk(&digits(map));
+            compact_disk(&mut disk);
+            calculate_checksum(&disk)
+        };
+        let fast = |map: &Vec<(u8, u8)>| Disk::from_map(&digits(map)).compact(Policy::Blocks).checksum;
+        check::agree(1000, disk_map, |map| has_blocks(map), naive, fast);
+        check::agree(1000, large_disk_map, |map| has_blocks(map), naive, fast);
+    }
+
+    #[test]
+    fn disk_policies_agree_with_compact_files_test() {
+        for policy in [Policy::FirstFit, Policy::BestFit, Policy::WorstFit] {
+            let naive = |map: &Vec<(u8, u8)>| {
+                let mut disk = populate_disk(&digits(map));
+                compact_files(&mut disk, policy);
+                calculate_checksum(&disk)
+            };
+            let fast = |map: &Vec<(u8, u8)>| Disk::from_map(&digits(map)).compact(policy).checksum;
+            check::agree(1000, disk_map, |map| has_blocks(map), naive, fast);
+            check::agree(1000, large_disk_map, |map| has_blocks(map), naive, fast);
+        }
+    }
+
+    /// The puzzle's example, as `render_disk` draws it before and after each block moves.
+    const BLOCK_STEPS: [&str; 13] = [
+        "00...111...2...333.44.5555.6666.777.888899",
+        "009..111...2...333.44.5555.6666.777.88889.",
+        "0099.111...2...333.44.5555.6666.777.8888..",
+        "00998111...2...333.44.5555.6666.777.888...",
+        "009981118..2...333.44.5555.6666.777.88....",
+        "0099811188.2...333.44.5555.6666.777.8.....",
+        "009981118882...333.44.5555.6666.777.......",
+        "0099811188827..333.44.5555.6666.77........",
+        "00998111888277.333.44.5555.6666.7.........",
+        "009981118882777333.44.5555.6666...........",
+        "009981118882777333644.5555.666............",
+        "00998111888277733364465555.66.............",
+        "0099811188827773336446555566..............",
+    ];
+
+    /// The same, as `render_partitions` draws it before and after each whole file moves.
+    const FILE_STEPS: [&str; 5] = [
+        "00...111...2...333.44.5555.6666.777.888899",
+        "0099.111...2...333.44.5555.6666.777.8888..",
+        "0099.1117772...333.44.5555.6666.....8888..",
+        "0099.111777244.333....5555.6666.....8888..",
+        "00992111777.44.333....5555.6666.....8888..",
+    ];
+
+    fn example() -> Vec<usize> {
+        parse_digits("2333133121414131402").unwrap()
+    }
+
+    #[test]
+    fn render_test() {
+        assert_eq!(render_disk(&populate_disk(&example())), BLOCK_STEPS[0]);
+        assert_eq!(render_partitions(&chunk(&example())), FILE_STEPS[0]);
+        assert_eq!(render_disk(&[]), "");
+        assert_eq!(Disk::from_map(&example()).to_string(), BLOCK_STEPS[0]);
+    }
+
+    #[test]
+    fn compact_disk_steps_test() {
+        let disk = populate_disk(&example());
+        let steps: Vec<String> = compact_disk_steps(disk.clone()).map(|step| render_disk(&step)).collect();
+        assert_eq!(steps, BLOCK_STEPS[1..]);
+        let mut compacted = disk;
+        compact_disk(&mut compacted);
+        assert_eq!(render_disk(&compacted), BLOCK_STEPS[12]);
+        assert_eq!(compact_disk_steps(vec![]).count(), 0);
+    }
+
+    #[test]
+    fn compact_partitions_steps_test() {
+        let partitions = chunk(&example());
+        let steps: Vec<String> =
+            compact_partitions_steps(partitions.clone()).map(|step| render_partitions(&step)).collect();
+        assert_eq!(steps, FILE_STEPS[1..]);
+        assert_eq!(render_partitions(&compact_partitions(partitions)), FILE_STEPS[4]);
+    }
+
+    #[test]
+    fn disk_steps_test() {
+        let steps = |policy| Disk::from_map(&example()).steps(policy).map(|step| step.to_string()).collect::<Vec<_>>();
+        // a move takes as much of the last file as fits in the first gap, not one block at a time
+        assert_eq!(steps(Policy::Blocks), [2, 3, 6, 9, 10, 11, 12].map(|step| BLOCK_STEPS[step]));
+        assert_eq!(steps(Policy::FirstFit), FILE_STEPS[1..]);
+    }
+
+    #[test]
+    fn policies_test() {
+        let params = Params::new(&[("part1", "worst-fit"), ("part2", "blocks")]);
+        let answers = crate::solution::run_with::<Day9>("2333133121414131402", &params).unwrap();
+        assert_eq!((answers.part2.as_deref(), answers.part1.is_some()), (Some("1928"), true));
+        let error = crate::solution::run_with::<Day9>("12", &Params::new(&[("part2", "last-fit")])).unwrap_err();
+        assert!(error.to_string().contains("last-fit"), "{}", error);
+    }
+
     mod integration {
-        use crate::puzzles::day9::solve;
+        use crate::puzzles::day9::Day9;
+        use crate::solution::Solution;
+
+        fn solve(input: String) -> (i64, i64) {
+            let digits = Day9::parse(&input).unwrap();
+            (Day9::part1(&digits), Day9::part2(&digits))
+        }
 
         #[test]
         fn provided() {
             assert_eq!(
                 solve(String::from("2333133121414131402")),
-                (1928, 2858, 2858)
+                (1928, 2858)
             )
         }
         #[test]
         fn simple() {
-            assert_eq!(solve(String::from("1234")), (6, 12, 12))
+            assert_eq!(solve(String::from("1234")), (6, 12))
         }
         #[test]
         fn simple2() {
-            assert_eq!(solve(String::from("1245")), (10, 18, 18))
+            assert_eq!(solve(String::from("1245")), (10, 18))
         }
         #[test]
         fn simpler() {
-            assert_eq!(solve(String::from("10")), (0, 0, 0))
+            assert_eq!(solve(String::from("10")), (0, 0))
         }
         #[test]
         fn simpler1() {
-            assert_eq!(solve(String::from("1010")), (1, 1, 1))
+            assert_eq!(solve(String::from("1010")), (1, 1))
         }
         #[test]
         fn simpler2() {
-            assert_eq!(solve(String::from("101010")), (5, 5, 5))
+            assert_eq!(solve(String::from("101010")), (5, 5))
         }
         #[test]
         fn simpler_move() {
-            assert_eq!(solve(String::from("1110")), (1, 1, 1))
+            assert_eq!(solve(String::from("1110")), (1, 1))
         }
         #[test]
         fn simpler_move2() {
-            assert_eq!(solve(String::from("101110")), (5, 5, 5))
+            assert_eq!(solve(String::from("101110")), (5, 5))
+        }
+        // nine one-block files fill the gap after file 0, which then holds ten files
+        #[test]
+        fn full_gap() {
+            assert_eq!(solve(String::from("19101010101010101010")), (165, 165))
+        }
+        // file 1 has no blocks
+        #[test]
+        fn empty_file() {
+            assert_eq!(solve(String::from("12031")), (2, 2))
         }
     }
     #[test]
     fn test_parse_digits() {
         let input = "2333133121414131402";
-        let digits = parse_digits(input);
+        let digits = parse_digits(input).unwrap();
         assert_eq!(
             digits,
             vec![2, 3, 3, 3, 1, 3, 3, 1, 2, 1, 4, 1, 4, 1, 3, 1, 4, 0, 2]
@@ -249,7 +598,7 @@ mod tests {
     }
 
     mod create_disk {
-        use crate::puzzles::day9::create_disk;
+        use crate::puzzles::day9::reference::create_disk;
 
         #[test]
         fn test_create_disk() {
@@ -268,7 +617,7 @@ mod tests {
     }
 
     mod populate_disk {
-        use crate::puzzles::day9::populate_disk;
+        use crate::puzzles::day9::reference::populate_disk;
 
         #[test]
         fn test_populate_disk() {
@@ -292,17 +641,18 @@ mod tests {
     }
 
     mod compact_example {
-        use crate::puzzles::day9::compact_disk;
+        use crate::puzzles::day9::reference::{compact_disk, render_disk};
 
         #[test]
         fn test_compact_example_steps() {
             let mut disk = vec![0, -1, -1, 1, 1, 1, -1, -1, -1, 2, 2, 2, 2, 2];
             compact_disk(&mut disk);
             assert_eq!(disk, vec![0, 2, 2, 1, 1, 1, 2, 2, 2, -1, -1, -1, -1, -1]);
+            assert_eq!(render_disk(&disk), "022111222.....");
         }
     }
     mod chunk {
-        use crate::puzzles::day9::{chunk, File, Partition};
+        use crate::puzzles::day9::reference::{chunk, File, Partition, SLOTS};
 
         #[test]
         fn simple() {
@@ -320,6 +670,7 @@ mod tests {
                         Default::default(),
                         Default::default(),
                         Default::default(),
+                        Default::default(),
                     ],
                     free: 2,
                 },
@@ -335,6 +686,7 @@ mod tests {
                         Default::default(),
                         Default::default(),
                         Default::default(),
+                        Default::default(),
                     ],
                     free: 4,
                 },
@@ -350,7 +702,7 @@ mod tests {
                 Partition {
                     id: 0,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 10, id: 0 });
                         data
                     },
@@ -359,7 +711,7 @@ mod tests {
                 Partition {
                     id: 1,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 20, id: 1 });
                         data
                     },
@@ -368,7 +720,7 @@ mod tests {
                 Partition {
                     id: 2,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 30, id: 2 });
                         data
                     },
@@ -388,7 +740,7 @@ mod tests {
                 Partition {
                     id: 0,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 10, id: 0 });
                         data
                     },
@@ -397,7 +749,7 @@ mod tests {
                 Partition {
                     id: 1,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 20, id: 1 });
                         data
                     },
@@ -448,7 +800,7 @@ mod tests {
                 Partition {
                     id: 0,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 3, id: 0 });
                         data
                     },
@@ -457,7 +809,7 @@ mod tests {
                 Partition {
                     id: 1,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 3, id: 1 });
                         data
                     },
@@ -472,7 +824,7 @@ mod tests {
                 Partition {
                     id: 0,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 3, id: 0 });
                         data[1] = Some(File { size: 3, id: 1 });
                         data
@@ -482,7 +834,7 @@ mod tests {
                 Partition {
                     id: 1,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 3, id: 0 });
                         data
                     },
@@ -499,7 +851,7 @@ mod tests {
                 Partition {
                     id: 0,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 1, id: 1 });
                         data
                     },
@@ -519,7 +871,7 @@ mod tests {
                 Partition {
                     id: 0,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 1, id: 1 });
                         data
                     },
@@ -541,7 +893,7 @@ mod tests {
                 Partition {
                     id: 0,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 3, id: 1 });
                         data
                     },
@@ -587,7 +939,7 @@ mod tests {
                 Partition {
                     id: 0,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { size: 3, id: 1 });
                         data
                     },
@@ -621,6 +973,7 @@ mod tests {
                         Default::default(),
                         Default::default(),
                         Default::default(),
+                        Default::default(),
                     ],
                     free: 1, // 1 will get moved into here
                 },
@@ -636,6 +989,7 @@ mod tests {
                         Default::default(),
                         Default::default(),
                         Default::default(),
+                        Default::default(),
                     ],
                     free: 5, // 2 will get moved in here
                 },
@@ -651,6 +1005,7 @@ mod tests {
                         Default::default(),
                         Default::default(),
                         Default::default(),
+                        Default::default(),
                     ],
                     free: 0,
                 },
@@ -668,6 +1023,7 @@ mod tests {
                         Default::default(),
                         Default::default(),
                         Default::default(),
+                        Default::default(),
                     ],
                     free: 0, // 1 has been moved into here
                 },
@@ -686,6 +1042,7 @@ mod tests {
                         Default::default(),
                         Default::default(),
                         Default::default(),
+                        Default::default(),
                     ],
                     free: 0, // 2 was moved in here
                 },
@@ -704,6 +1061,7 @@ mod tests {
                         Default::default(),
                         Default::default(),
                         Default::default(),
+                        Default::default(),
                     ],
                     free: 0,
                 },
@@ -712,14 +1070,14 @@ mod tests {
         }
     }
     mod calculate_checksum_from_partition {
-        use crate::puzzles::day9::{calculate_checksum_from_partition, File, Partition};
+        use crate::puzzles::day9::reference::{calculate_checksum_from_partition, File, Partition, SLOTS};
 
         #[test]
         fn simple_1() {
             let input = vec![Partition {
                 id: 0,
                 data: {
-                    let mut data: [Option<File>; 9] = Default::default();
+                    let mut data: [Option<File>; SLOTS] = Default::default();
                     data[0] = Some(File { id: 0, size: 1 });
                     data[1] = Some(File { id: 1, size: 1 });
                     data
@@ -734,7 +1092,7 @@ mod tests {
             let input = vec![Partition {
                 id: 0,
                 data: {
-                    let mut data: [Option<File>; 9] = Default::default();
+                    let mut data: [Option<File>; SLOTS] = Default::default();
                     data[0] = Some(File { id: 0, size: 1 });
                     data[1] = Some(File { id: 1, size: 1 });
                     data[2] = Some(File { id: 2, size: 1 });
@@ -751,7 +1109,7 @@ mod tests {
                 Partition {
                     id: 0,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { id: 0, size: 1 });
                         data[1] = Some(File { id: 1, size: 1 });
                         data
@@ -761,7 +1119,7 @@ mod tests {
                 Partition {
                     id: 1,
                     data: {
-                        let mut data: [Option<File>; 9] = Default::default();
+                        let mut data: [Option<File>; SLOTS] = Default::default();
                         data[0] = Some(File { id: 2, size: 2 });
                         data[1] = Some(File { id: 3, size: 1 });
                         data
@@ -808,7 +1166,7 @@ mod tests {
     }
 
     mod calculate_checksum {
-        use crate::puzzles::day9::calculate_checksum;
+        use crate::puzzles::day9::reference::calculate_checksum;
 
         #[test]
         fn test_calculate_checksum() {
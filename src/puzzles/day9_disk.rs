@@ -0,0 +1,404 @@
+//! The disk from day 9 as extents, runs of blocks holding one file or free space, so files
+//! and gaps can be any size, zero included. Compacting it under a [`Policy`] moves file data
+//! towards the start of the disk and [`Report`]s the checksum and how fragmented it is left.
+//!
+//! ```text
+//! let mut disk = Disk::from_map(&digits);
+//! let report = disk.compact(Policy::FirstFit);
+//! ```
+//!
+//! [`Disk::steps`] goes one move at a time instead, and a disk displays as the puzzle draws
+//! it, e.g. `0099.111...2...`.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+/// How [`Disk::compact`] moves file data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Policy {
+    /// Move blocks one at a time from the end of the disk into its first free block, as part 1
+    /// does. Files end up split wherever a gap was too small for them.
+    Blocks,
+    /// Move each whole file, highest id first, into the first gap before it that fits it, as
+    /// part 2 does.
+    FirstFit,
+    /// As `FirstFit`, but into the smallest gap that fits, the first of those on a tie.
+    BestFit,
+    /// As `FirstFit`, but into the largest gap, the first of those on a tie.
+    WorstFit,
+}
+
+impl Policy {
+    pub(crate) const ALL: [Policy; 4] = [Policy::Blocks, Policy::FirstFit, Policy::BestFit, Policy::WorstFit];
+
+    /// The name `--param` takes.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Policy::Blocks => "blocks",
+            Policy::FirstFit => "first-fit",
+            Policy::BestFit => "best-fit",
+            Policy::WorstFit => "worst-fit",
+        }
+    }
+}
+
+impl fmt::Display for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Policy {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Policy::ALL.into_iter().find(|policy| policy.name() == name).ok_or(())
+    }
+}
+
+/// The outcome of compacting a disk.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct Report {
+    pub(crate) checksum: i64,
+    /// Runs of blocks moved: whole files, or the pieces of them `Blocks` moves.
+    pub(crate) moves: usize,
+    /// Files split over more than one extent.
+    pub(crate) fragmented_files: usize,
+    /// Runs of free blocks before the last used block.
+    pub(crate) gaps: usize,
+    /// Free blocks in those gaps.
+    pub(crate) gap_blocks: usize,
+    /// The longest run of free blocks, counting the free space after the last used block.
+    pub(crate) largest_free: usize,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "checksum {}, {} moves, {} fragmented files, {} gaps of {} blocks, largest free run {}",
+            self.checksum, self.moves, self.fragmented_files, self.gaps, self.gap_blocks, self.largest_free
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct Disk {
+    /// Each extent of file data by start, with the file's id and the extent's length.
+    /// Neighbouring extents of the same file are merged.
+    used: BTreeMap<usize, (usize, usize)>,
+    /// Each run of free blocks by start, with its length. Neighbouring runs are merged.
+    free: BTreeMap<usize, usize>,
+    files: usize,
+}
+
+impl Disk {
+    /// Lay out a disk map: alternating file and free space lengths, file ids counting up from 0.
+    pub(crate) fn from_map(digits: &[usize]) -> Self {
+        let mut disk = Disk { files: digits.len().div_ceil(2), ..Default::default() };
+        let mut position = 0;
+        for (index, &len) in digits.iter().enumerate() {
+            if index % 2 == 0 {
+                disk.place(position, index / 2, len);
+            } else {
+                disk.release(position, len);
+            }
+            position += len;
+        }
+        disk
+    }
+
+    /// Compact the disk under `policy`.
+    pub(crate) fn compact(&mut self, policy: Policy) -> Report {
+        let mut compaction = Compaction::new(self, policy);
+        let mut moves = 0;
+        while compaction.step(self) {
+            moves += 1;
+        }
+        self.report(moves)
+    }
+
+    /// The disk after each move compacting it under `policy` makes.
+    pub(crate) fn steps(mut self, policy: Policy) -> impl Iterator<Item = Disk> {
+        let mut compaction = Compaction::new(&self, policy);
+        std::iter::from_fn(move || compaction.step(&mut self).then(|| self.clone()))
+    }
+
+    /// Move the end of the last extent into the front of the first gap; false once no gap is
+    /// left before any file data.
+    fn move_blocks(&mut self) -> bool {
+        let (Some((&gap, &gap_len)), Some((&start, &(id, len)))) = (self.free.first_key_value(), self.used.last_key_value())
+        else {
+            return false;
+        };
+        if gap > start {
+            return false;
+        }
+        let moved = gap_len.min(len);
+        self.take_free(gap, moved);
+        if moved == len {
+            self.used.remove(&start);
+        } else {
+            self.used.insert(start, (id, len - moved));
+        }
+        self.release(start + len - moved, moved);
+        self.place(gap, id, moved);
+        true
+    }
+
+    /// Move file `id`'s extent at `start` into the gap before it `policy` picks; false if none fits.
+    fn move_file(&mut self, id: usize, start: usize, policy: Policy) -> bool {
+        // an earlier move may have merged this extent into another piece of the file
+        let Some(&(owner, len)) = self.used.get(&start).filter(|(owner, _)| *owner == id) else {
+            return false;
+        };
+        let mut fits = self.free.range(..start).filter(|(_, &gap_len)| gap_len >= len);
+        let gap = match policy {
+            Policy::FirstFit => fits.next(),
+            Policy::BestFit => fits.min_by_key(|(&gap, &gap_len)| (gap_len, gap)),
+            Policy::WorstFit => fits.max_by_key(|(&gap, &gap_len)| (gap_len, Reverse(gap))),
+            Policy::Blocks => unreachable!("blocks are moved by move_blocks"),
+        };
+        let Some((&gap, _)) = gap else {
+            return false;
+        };
+        self.used.remove(&start);
+        self.take_free(gap, len);
+        self.release(start, len);
+        self.place(gap, owner, len);
+        true
+    }
+
+    /// Put `len` blocks of file `id` at `start`, merging with the file's neighbouring extents.
+    fn place(&mut self, mut start: usize, id: usize, mut len: usize) {
+        if len == 0 {
+            return;
+        }
+        if let Some((&before, &(owner, before_len))) = self.used.range(..start).next_back() {
+            if owner == id && before + before_len == start {
+                self.used.remove(&before);
+                start = before;
+                len += before_len;
+            }
+        }
+        if let Some(&(owner, after_len)) = self.used.get(&(start + len)) {
+            if owner == id {
+                self.used.remove(&(start + len));
+                len += after_len;
+            }
+        }
+        self.used.insert(start, (id, len));
+    }
+
+    /// Free `len` blocks at `start`, merging with the free runs either side.
+    fn release(&mut self, mut start: usize, mut len: usize) {
+        if len == 0 {
+            return;
+        }
+        if let Some((&before, &before_len)) = self.free.range(..start).next_back() {
+            if before + before_len == start {
+                self.free.remove(&before);
+                start = before;
+                len += before_len;
+            }
+        }
+        if let Some(after_len) = self.free.remove(&(start + len)) {
+            len += after_len;
+        }
+        self.free.insert(start, len);
+    }
+
+    /// Use the first `len` blocks of the free run at `start`.
+    fn take_free(&mut self, start: usize, len: usize) {
+        let gap_len = self.free.remove(&start).expect("a free run starts there");
+        if gap_len > len {
+            self.free.insert(start + len, gap_len - len);
+        }
+    }
+
+    /// How many blocks the disk has, used or free.
+    pub(crate) fn blocks(&self) -> usize {
+        let used = self.used.last_key_value().map_or(0, |(&start, &(_, len))| start + len);
+        let free = self.free.last_key_value().map_or(0, |(&start, &len)| start + len);
+        used.max(free)
+    }
+
+    /// Each extent of file data in disk order, as (start, file id, length).
+    pub(crate) fn extents(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.used.iter().map(|(&start, &(id, len))| (start, id, len))
+    }
+
+    /// The sum of each block's position times the id of the file in it.
+    pub(crate) fn checksum(&self) -> i64 {
+        self.extents()
+            .map(|(start, id, len)| {
+                // the positions start..start + len sum to len * (2 * start + len - 1) / 2
+                let positions = (len * (2 * start + len - 1) / 2) as i64;
+                positions * id as i64
+            })
+            .sum()
+    }
+
+    fn report(&self, moves: usize) -> Report {
+        let mut pieces = vec![0; self.files];
+        self.extents().for_each(|(_, id, _)| pieces[id] += 1);
+        let end = self.used.last_key_value().map_or(0, |(&start, &(_, len))| start + len);
+        let gaps: Vec<usize> = self.free.range(..end).map(|(_, &len)| len).collect();
+        Report {
+            checksum: self.checksum(),
+            moves,
+            fragmented_files: pieces.iter().filter(|&&count| count > 1).count(),
+            gaps: gaps.len(),
+            gap_blocks: gaps.iter().sum(),
+            largest_free: self.free.values().copied().max().unwrap_or(0),
+        }
+    }
+}
+
+impl fmt::Display for Disk {
+    /// Every block, as its file's [`block_char`] or `.` when free.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut position = 0;
+        for (start, id, len) in self.extents() {
+            (position..start).try_for_each(|_| f.write_char('.'))?;
+            (0..len).try_for_each(|_| f.write_char(block_char(id)))?;
+            position = start + len;
+        }
+        (position..self.blocks()).try_for_each(|_| f.write_char('.'))
+    }
+}
+
+/// A file's block: its id as a digit, then `a`-`z` for ids 10 to 35, and `#` past those.
+pub(crate) fn block_char(id: usize) -> char {
+    u32::try_from(id).ok().and_then(|id| char::from_digit(id, 36)).unwrap_or('#')
+}
+
+/// Where compacting a disk has got to, so it can go one move at a time. Files move highest id
+/// first; a file already in pieces moves piece by piece, the last first.
+struct Compaction {
+    policy: Policy,
+    /// The extents still to try moving, as (file id, start), the next one last.
+    pending: Vec<(usize, usize)>,
+}
+
+impl Compaction {
+    fn new(disk: &Disk, policy: Policy) -> Self {
+        let mut pending: Vec<(usize, usize)> = match policy {
+            Policy::Blocks => vec![],
+            Policy::FirstFit | Policy::BestFit | Policy::WorstFit => {
+                disk.used.iter().map(|(&start, &(id, _))| (id, start)).collect()
+            }
+        };
+        pending.sort();
+        Compaction { policy, pending }
+    }
+
+    /// Make the next move; false once there are none left to make.
+    fn step(&mut self, disk: &mut Disk) -> bool {
+        if self.policy == Policy::Blocks {
+            return disk.move_blocks();
+        }
+        while let Some((id, start)) = self.pending.pop() {
+            if disk.move_file(id, start, self.policy) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [usize; 19] = [2, 3, 3, 3, 1, 3, 3, 1, 2, 1, 4, 1, 4, 1, 3, 1, 4, 0, 2];
+
+    fn compacted(digits: &[usize], policy: Policy) -> (Disk, Report) {
+        let mut disk = Disk::from_map(digits);
+        let report = disk.compact(policy);
+        (disk, report)
+    }
+
+    #[test]
+    fn from_map_test() {
+        // a file of size 0 has no extent, and the free runs either side of it merge
+        let disk = Disk::from_map(&[2, 1, 0, 2, 1]);
+        assert_eq!(disk.extents().collect::<Vec<_>>(), vec![(0, 0, 2), (5, 2, 1)]);
+        assert_eq!(disk.free, BTreeMap::from([(2, 3)]));
+        assert_eq!(Disk::from_map(&[]).checksum(), 0);
+    }
+
+    #[test]
+    fn display_test() {
+        assert_eq!(Disk::from_map(&[2, 1, 0, 2, 1]).to_string(), "00...2");
+        assert_eq!(Disk::from_map(&[1, 2]).to_string(), "0..");
+        assert_eq!(Disk::from_map(&[]).to_string(), "");
+        assert_eq!([0, 9, 10, 35, 36].map(block_char), ['0', '9', 'a', 'z', '#']);
+    }
+
+    #[test]
+    fn steps_test() {
+        for policy in Policy::ALL {
+            let (compacted, report) = compacted(&EXAMPLE, policy);
+            let steps: Vec<Disk> = Disk::from_map(&EXAMPLE).steps(policy).collect();
+            assert_eq!((steps.len(), steps.last()), (report.moves, Some(&compacted)), "{}", policy);
+        }
+        assert_eq!(Disk::from_map(&[3, 0, 1]).steps(Policy::Blocks).count(), 0);
+    }
+
+    #[test]
+    fn example_test() {
+        let (disk, report) = compacted(&EXAMPLE, Policy::Blocks);
+        assert_eq!(report.checksum, 1928);
+        // 0099811188827773336446555566..............
+        assert_eq!((report.gaps, report.gap_blocks, report.largest_free), (0, 0, 14));
+        assert_eq!(report.fragmented_files, 2);
+        assert_eq!(disk.extents().next(), Some((0, 0, 2)));
+
+        let (_, report) = compacted(&EXAMPLE, Policy::FirstFit);
+        // 00992111777.44.333....5555.6666.....8888..
+        assert_eq!(
+            report,
+            Report { checksum: 2858, moves: 4, fragmented_files: 0, gaps: 5, gap_blocks: 12, largest_free: 5 }
+        );
+    }
+
+    #[test]
+    fn policies_test() {
+        let checksums = |digits: &[usize]| Policy::ALL.map(|policy| compacted(digits, policy).1.checksum);
+        // 0.....1...233: file 3 takes the front of the gap of 5, or the whole gap of 3 for best fit
+        // blocks, first and worst fit: 03321........
+        // best fit:                    01.....332...
+        assert_eq!(checksums(&[1, 5, 1, 3, 1, 0, 2]), [19, 19, 64, 19]);
+        // 0...1.....233: file 3 takes the front of the gap of 3, or of the gap of 5 for worst fit
+        // blocks, first and best fit: 03321........
+        // worst fit:                  021..33......
+        assert_eq!(checksums(&[1, 3, 1, 5, 1, 0, 2]), [19, 19, 19, 37]);
+        for policy in Policy::ALL {
+            assert_eq!(policy.name().parse(), Ok(policy));
+        }
+        assert_eq!("last-fit".parse::<Policy>(), Err(()));
+    }
+
+    #[test]
+    fn large_sizes_test() {
+        // sizes the puzzle's single digits cannot express, and files of size 0
+        let digits = [12, 30, 0, 7, 25, 0, 0, 11, 14];
+        for policy in Policy::ALL {
+            let (disk, report) = compacted(&digits, policy);
+            let blocks: usize = disk.extents().map(|(_, _, len)| len).sum();
+            assert_eq!(blocks, 12 + 25 + 14, "{}", policy);
+            assert!(report.moves > 0, "{}", policy);
+        }
+        let (disk, report) = compacted(&digits, Policy::FirstFit);
+        // file 4 moves into the gap of 37 after file 0, leaving 23 blocks there: too few for file 2
+        assert_eq!(disk.extents().collect::<Vec<_>>(), vec![(0, 0, 12), (12, 4, 14), (49, 2, 25)]);
+        assert_eq!((report.gaps, report.gap_blocks, report.largest_free), (1, 23, 25));
+        let (disk, report) = compacted(&digits, Policy::Blocks);
+        // the tail of file 2 fills the rest of the gap and joins up with its head
+        assert_eq!(disk.extents().collect::<Vec<_>>(), vec![(0, 0, 12), (12, 4, 14), (26, 2, 25)]);
+        assert_eq!((report.gaps, report.fragmented_files, report.moves), (0, 0, 2));
+    }
+}
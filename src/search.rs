@@ -0,0 +1,213 @@
+//! Shortest-path searches over any state space, for the maze days.
+//!
+//! A search is described by a start state and a successor function; the weighted
+//! searches expect `(next, cost)` pairs.
+//!
+//! ```text
+//! let costs = search::dijkstra((start, Direction::East), |&(point, facing)| moves(point, facing));
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// An edge or path cost; `Default` is the cost of not moving.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<T: Copy + Ord + Add<Output = T> + Default> Cost for T {}
+
+/// The number of steps from `start` to every state it can reach.
+pub fn bfs<S, I>(start: S, mut successors: impl FnMut(&S) -> I) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut steps = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(state) = queue.pop_front() {
+        let next_steps = steps[&state] + 1;
+        for next in successors(&state) {
+            if !steps.contains_key(&next) {
+                steps.insert(next.clone(), next_steps);
+                queue.push_back(next);
+            }
+        }
+    }
+    steps
+}
+
+/// The cheapest cost from `start` to every state it can reach.
+pub fn dijkstra<S, C, I>(start: S, successors: impl FnMut(&S) -> I) -> HashMap<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    search(start, successors, |_| C::default(), |_| false, false).0.costs
+}
+
+/// The cheapest path from `start` to a state satisfying `goal`, with its cost.
+///
+/// `heuristic` must never overestimate the cost left to a goal; `|_| 0` makes this Dijkstra.
+pub fn astar<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<(C, Vec<S>)>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let (paths, end) = search(start, successors, heuristic, goal, false);
+    let end = end?;
+    Some((paths.costs[&end], paths.path_to(&end)?))
+}
+
+/// Dijkstra from `start`, remembering every predecessor that lies on a cheapest path.
+pub fn shortest_paths<S, C, I>(start: S, successors: impl FnMut(&S) -> I) -> ShortestPaths<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    search(start, successors, |_| C::default(), |_| false, true).0
+}
+
+/// The cheapest costs from a start state, and how each state can be reached that cheaply.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<S, C> {
+    costs: HashMap<S, C>,
+    predecessors: HashMap<S, Vec<S>>,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> ShortestPaths<S, C> {
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.costs.get(state).copied()
+    }
+
+    /// Every state on some cheapest path from the start to one of `ends`, the ends included.
+    pub fn on_paths_to(&self, ends: impl IntoIterator<Item = S>) -> HashSet<S> {
+        let mut seen = HashSet::new();
+        let mut stack: Vec<S> = ends.into_iter().filter(|end| self.costs.contains_key(end)).collect();
+        while let Some(state) = stack.pop() {
+            if seen.insert(state.clone()) {
+                stack.extend(self.predecessors.get(&state).into_iter().flatten().cloned());
+            }
+        }
+        seen
+    }
+
+    /// One cheapest path from the start to `end`, start first.
+    pub fn path_to(&self, end: &S) -> Option<Vec<S>> {
+        self.costs.get(end)?;
+        let mut path = vec![end.clone()];
+        while let Some(previous) = self.predecessors.get(path.last()?).and_then(|previous| previous.first()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Best-first search ordered by cost plus `heuristic`, stopping at the first `goal` state settled.
+///
+/// With `keep_ties` every equally cheap predecessor is kept, not just the first found.
+fn search<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut goal: impl FnMut(&S) -> bool,
+    keep_ties: bool,
+) -> (ShortestPaths<S, C>, Option<S>)
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut paths = ShortestPaths { costs: HashMap::from([(start.clone(), C::default())]), predecessors: HashMap::new() };
+    // The heap holds indices into `states`, so states need not be `Ord`.
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut states = vec![start];
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        let state = states[index].clone();
+        if paths.costs[&state] < cost {
+            // Already settled more cheaply.
+            continue;
+        }
+        if goal(&state) {
+            return (paths, Some(state));
+        }
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            match paths.costs.get(&next) {
+                Some(&known) if known < next_cost => {}
+                Some(&known) if known == next_cost => {
+                    if keep_ties {
+                        paths.predecessors.entry(next).or_default().push(state.clone());
+                    }
+                }
+                _ => {
+                    paths.costs.insert(next.clone(), next_cost);
+                    paths.predecessors.insert(next.clone(), vec![state.clone()]);
+                    heap.push(Reverse((next_cost + heuristic(&next), next_cost, states.len())));
+                    states.push(next);
+                }
+            }
+        }
+    }
+    (paths, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -1-> 1 -1-> 3
+    // 0 -3-> 2 -1-> 3 -5-> 4
+    // 0 -2---------> 3
+    fn edges(node: &u8) -> Vec<(u8, u32)> {
+        match node {
+            0 => vec![(1, 1), (2, 3), (3, 2)],
+            1 => vec![(3, 1)],
+            2 => vec![(3, 1)],
+            3 => vec![(4, 5)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn bfs_test() {
+        let steps = bfs(0, |node| edges(node).into_iter().map(|(next, _)| next));
+        assert_eq!(steps, HashMap::from([(0, 0), (1, 1), (2, 1), (3, 1), (4, 2)]));
+    }
+
+    #[test]
+    fn dijkstra_test() {
+        let costs = dijkstra(0, edges);
+        assert_eq!(costs, HashMap::from([(0, 0), (1, 1), (2, 3), (3, 2), (4, 7)]));
+        assert_eq!(dijkstra(4, edges), HashMap::from([(4, 0)]));
+    }
+
+    #[test]
+    fn astar_test() {
+        let (cost, path) = astar(0, edges, |&node| if node == 4 { 0 } else { 5 }, |&node| node == 4).unwrap();
+        assert_eq!(cost, 7);
+        assert_eq!(path.first(), Some(&0));
+        assert_eq!(path.last(), Some(&4));
+        assert_eq!(astar(4, edges, |_| 0, |&node| node == 0), None);
+    }
+
+    #[test]
+    fn shortest_paths_test() {
+        let paths = shortest_paths(0, edges);
+        assert_eq!(paths.cost(&4), Some(7));
+        assert_eq!(paths.cost(&5), None);
+        assert_eq!(paths.on_paths_to([4]), HashSet::from([0, 1, 3, 4]));
+        assert_eq!(paths.on_paths_to([2, 4]), HashSet::from([0, 1, 2, 3, 4]));
+        assert_eq!(paths.on_paths_to([5]), HashSet::new());
+        assert_eq!(paths.path_to(&2), Some(vec![0, 2]));
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+use timing_util::{collect, timed, Span};
+
+use crate::error::ParseError;
+
+/// A day's puzzle, split into a parse step and the two parts.
+///
+/// `parse` runs once and both parts work from the parsed input, so a day only
+/// has to describe how to read its input and how to answer each question.
+/// Malformed input is reported from `parse`; the parts can assume it is well formed.
+pub(crate) trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> impl Answer;
+    fn part2(input: &Self::Input) -> impl Answer;
+
+    /// Apply `--param` overrides to the parsed input. Only days whose input is [`Configured`] take any.
+    fn configure(_input: &mut Self::Input, params: &Params) -> Result<(), ParamError> {
+        match params.0.first() {
+            Some((key, _)) => Err(ParamError::Unknown { key: key.clone(), known: &[] }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// What a part returns: its answer, or, for a day that cannot solve every input it parses,
+/// a `Result` whose error explains why there is no answer.
+pub(crate) trait Answer {
+    fn answer(self) -> Result<String, String>;
+}
+
+macro_rules! answer {
+    ($($type:ty),*) => {$(
+        impl Answer for $type {
+            fn answer(self) -> Result<String, String> {
+                Ok(self.to_string())
+            }
+        }
+    )*};
+}
+
+answer!(i32, i64, u32, u64, usize, String);
+
+impl<T: Display, E: Display> Answer for Result<T, E> {
+    fn answer(self) -> Result<String, String> {
+        self.map(|answer| answer.to_string()).map_err(|error| error.to_string())
+    }
+}
+
+/// A day's sizes and thresholds: the real puzzle's by default, the examples' with `--param`.
+pub(crate) trait Config: Default {
+    /// The parameter names [`Config::set`] accepts.
+    const KEYS: &'static [&'static str];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParamError>;
+
+    fn apply(&mut self, params: &Params) -> Result<(), ParamError> {
+        params.0.iter().try_for_each(|(key, value)| self.set(key, value))
+    }
+
+    /// The error for a key that is not in [`Config::KEYS`].
+    fn unknown(key: &str) -> ParamError {
+        ParamError::Unknown { key: key.to_string(), known: Self::KEYS }
+    }
+}
+
+/// Parse the value of parameter `key`.
+pub(crate) fn param_value<T: FromStr>(key: &str, value: &str) -> Result<T, ParamError> {
+    value.parse().map_err(|_| ParamError::Invalid { key: key.to_string(), value: value.to_string() })
+}
+
+/// A day's parsed input, with the [`Config`] to solve it under.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Configured<I, C> {
+    pub input: I,
+    pub config: C,
+}
+
+impl<I, C: Default> Configured<I, C> {
+    pub fn new(input: I) -> Self {
+        Configured { input, config: C::default() }
+    }
+}
+
+/// `--param key=value` overrides, in the order given.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Params(pub Vec<(String, String)>);
+
+impl Params {
+    #[cfg(test)]
+    pub fn new(pairs: &[(&str, &str)]) -> Self {
+        Params(pairs.iter().map(|&(key, value)| (key.to_string(), value.to_string())).collect())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamError {
+    /// `known` lists the parameters the day does take.
+    Unknown { key: String, known: &'static [&'static str] },
+    Invalid { key: String, value: String },
+}
+
+impl Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Unknown { key, known: [] } => write!(f, "unknown parameter `{}`; this day takes none", key),
+            ParamError::Unknown { key, known } => {
+                write!(f, "unknown parameter `{}`; this day takes {}", key, known.join(", "))
+            }
+            ParamError::Invalid { key, value } => write!(f, "`{}` is not a valid value for `{}`", value, key),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+/// Why a day could not be run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunError {
+    Parse(ParseError),
+    Param(ParamError),
+    /// The input parsed, but the part has no answer for it; the message says why.
+    Solve { part: Part, message: String },
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Parse(error) => write!(f, "{}", error),
+            RunError::Param(error) => write!(f, "{}", error),
+            RunError::Solve { part: Part::One, message } => write!(f, "part 1 has no answer: {}", message),
+            RunError::Solve { part: Part::Two, message } => write!(f, "part 2 has no answer: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+/// One half of a day's puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+/// A format a day's puzzle can be exported in, for looking at it with other tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    PlantUml,
+    Dot,
+    Verilog,
+}
+
+/// The answers to a day, already rendered for display.
+///
+/// A part is `None` when it was not asked for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.part1, &self.part2) {
+            (Some(part1), Some(part2)) => write!(f, "part1: {}, part2: {}", part1, part2),
+            (Some(part1), None) => write!(f, "part1: {}", part1),
+            (None, Some(part2)) => write!(f, "part2: {}", part2),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// How long each step of a run took. A part is `None` when it was not asked for.
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part1: Option<Duration>,
+    pub part2: Option<Duration>,
+    /// Finer-grained spans the day recorded with `timing_util::span!`.
+    pub spans: Vec<Span>,
+}
+
+impl Timings {
+    /// Time spent answering, excluding the parse.
+    pub fn solve(&self) -> Duration {
+        self.part1.unwrap_or_default() + self.part2.unwrap_or_default()
+    }
+}
+
+/// The answers and timings of a run, or why the input could not be parsed, configured or solved.
+pub type Outcome = Result<(Answers, Timings), RunError>;
+
+/// Parse the input, apply `params` and answer both parts of `S`.
+#[cfg(test)]
+pub fn run_with<S: Solution>(input: &str, params: &Params) -> Result<Answers, RunError> {
+    run_part::<S>(input, None, params).map(|(answers, _)| answers)
+}
+
+/// Parse the input, apply `params` and answer `part` of `S`, or both parts when `part` is `None`.
+pub fn run_part<S: Solution>(input: &str, part: Option<Part>, params: &Params) -> Outcome {
+    let (result, spans) = collect(|| {
+        let (parsed, parse) = timed!(S::parse(input));
+        let mut parsed = parsed.map_err(RunError::Parse)?;
+        S::configure(&mut parsed, params).map_err(RunError::Param)?;
+        let solved = |part, answer: Result<String, String>| answer.map_err(|message| RunError::Solve { part, message });
+        let (part1, time1) = (part != Some(Part::Two)).then(|| timed!(S::part1(&parsed).answer())).unzip();
+        let part1 = part1.map(|answer| solved(Part::One, answer)).transpose()?;
+        let (part2, time2) = (part != Some(Part::One)).then(|| timed!(S::part2(&parsed).answer())).unzip();
+        let part2 = part2.map(|answer| solved(Part::Two, answer)).transpose()?;
+        Ok(((part1, time1), (part2, time2), parse))
+    });
+    let ((answer1, time1), (answer2, time2), parse) = result?;
+    Ok((
+        Answers { part1: answer1, part2: answer2 },
+        Timings { parse, part1: time1, part2: time2, spans },
+    ))
+}
+
+/// Parse a day's input and render it in a format.
+pub type Export = fn(&str, ExportFormat) -> Result<String, ParseError>;
+
+/// An entry in the registry of days.
+pub struct Day {
+    pub day: u8,
+    pub run: fn(&str, Option<Part>, &Params) -> Outcome,
+    /// Renders the parsed input in an [`ExportFormat`], for the days that support it.
+    pub export: Option<Export>,
+}
+
+impl Day {
+    pub const fn new<S: Solution>(day: u8) -> Self {
+        Self {
+            day,
+            run: run_part::<S>,
+            export: None,
+        }
+    }
+
+    pub const fn with_export(self, export: Export) -> Self {
+        Self {
+            export: Some(export),
+            ..self
+        }
+    }
+}
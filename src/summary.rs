@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use crate::answers::{Verdict, Verdicts};
+use crate::solution::{Answers, Timings};
+
+/// The outcome of running one day: a line in the `run --all` table, or one JSON object.
+pub struct Row {
+    pub day: u8,
+    /// The error message when the day could not be run, e.g. its input is missing.
+    pub result: Result<(Answers, Timings), String>,
+    pub verdicts: Verdicts,
+}
+
+impl Row {
+    /// Whether the day could not be run or gave an answer known to be wrong.
+    pub fn failed(&self) -> bool {
+        self.result.is_err() || self.verdicts.any_failed()
+    }
+}
+
+const HEADINGS: [&str; 5] = ["day", "part 1", "part 2", "parse", "solve"];
+
+/// Render the rows as a table, with a totals line for the parse and solve times.
+pub fn render(rows: &[Row]) -> String {
+    let mut cells: Vec<[String; 5]> = vec![HEADINGS.map(String::from)];
+    let mut total_parse = Duration::ZERO;
+    let mut total_solve = Duration::ZERO;
+    for row in rows {
+        cells.push(match &row.result {
+            Ok((answers, timings)) => {
+                total_parse += timings.parse;
+                total_solve += timings.solve();
+                [
+                    row.day.to_string(),
+                    answer_cell(&answers.part1, row.verdicts.part1),
+                    answer_cell(&answers.part2, row.verdicts.part2),
+                    format_duration(timings.parse),
+                    format_duration(timings.solve()),
+                ]
+            }
+            Err(_) => [row.day.to_string(), "-".into(), "-".into(), "-".into(), "-".into()],
+        });
+    }
+    cells.push([
+        "total".into(),
+        String::new(),
+        String::new(),
+        format_duration(total_parse),
+        format_duration(total_solve),
+    ]);
+
+    let mut widths = [0; 5];
+    for line in &cells {
+        for (width, cell) in widths.iter_mut().zip(line) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    let last = cells.len() - 1;
+    for (i, line) in cells.iter().enumerate() {
+        if i == 1 || i == last {
+            table.push_str(&separator(&widths));
+        }
+        let padded: Vec<String> = line
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                // Answers read left to right; the day and the times line up on the right.
+                if column == 1 || column == 2 {
+                    format!("{:<width$}", cell)
+                } else {
+                    format!("{:>width$}", cell)
+                }
+            })
+            .collect();
+        table.push_str(padded.join(" | ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+fn answer_cell(answer: &Option<String>, verdict: Option<Verdict>) -> String {
+    match (answer, verdict) {
+        (Some(answer), Some(verdict)) => format!("{} {}", answer, verdict),
+        (Some(answer), None) => answer.clone(),
+        (None, _) => String::new(),
+    }
+}
+
+fn separator(widths: &[usize; 5]) -> String {
+    let dashes: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    format!("{}\n", dashes.join("-+-"))
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(day: u8, part1: &str, part2: &str, parse: u64, solve: u64) -> Row {
+        Row {
+            day,
+            verdicts: Verdicts::default(),
+            result: Ok((
+                Answers { part1: Some(part1.into()), part2: Some(part2.into()) },
+                Timings {
+                    parse: Duration::from_micros(parse),
+                    part1: Some(Duration::from_micros(solve)),
+                    part2: None,
+                    spans: Vec::new(),
+                },
+            )),
+        }
+    }
+
+    #[test]
+    fn render_test() {
+        let rows = vec![
+            row(1, "11", "31", 5, 20),
+            Row { day: 2, result: Err("no input".into()), verdicts: Verdicts::default() },
+            Row {
+                verdicts: Verdicts { part1: Some(Verdict::Pass), part2: Some(Verdict::TooLow) },
+                ..row(10, "36", "81", 15, 1_000)
+            },
+        ];
+        let expected = [
+            "  day | part 1  | part 2     |   parse |   solve",
+            "------+---------+------------+---------+--------",
+            "    1 | 11      | 31         |  5.00µs | 20.00µs",
+            "    2 | -       | -          |       - |       -",
+            "   10 | 36 PASS | 81 TOO_LOW | 15.00µs |  1.00ms",
+            "------+---------+------------+---------+--------",
+            "total |         |            | 20.00µs |  1.02ms",
+        ];
+        assert_eq!(render(&rows), expected.map(|line| format!("{}\n", line)).concat());
+    }
+}
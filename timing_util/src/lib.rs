@@ -1,15 +1,284 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
 /// Measure the execution time of a block and print the result with its name.
+///
+/// Inside a [`span`] or [`collect`] the timing is recorded as a child span instead of printed.
+/// The block runs in place, so `?`, `return` and `break` in it leave the caller as before; a
+/// block left that way records nothing.
 #[macro_export]
 macro_rules! measure_time {
     ($func:expr) => {{
-        let start = Instant::now();
+        let open = $crate::OpenSpan::open(stringify!($func));
         let result = $func;
-        let duration = start.elapsed();
-        println!(
-            "Function '{}' executed in {:?}",
-            stringify!($func),
-            duration
-        );
+        open.close();
         result
     }};
 }
+
+/// Measure the execution time of a block and hand it back, as `(result, duration)`, without printing.
+#[macro_export]
+macro_rules! timed {
+    ($func:expr) => {{
+        let start = ::std::time::Instant::now();
+        let result = $func;
+        (result, start.elapsed())
+    }};
+}
+
+/// Time a block as a named span; spans opened while it runs become its children.
+///
+/// `span!("compact", compact(disk))`
+#[macro_export]
+macro_rules! span {
+    ($name:expr, $func:expr) => {
+        $crate::span($name, || $func)
+    };
+}
+
+/// Run a block repeatedly, after some warm-up runs, and print its [`Stats`].
+///
+/// `bench!(solve(input))` uses 3 warm-up and 10 timed runs; `bench!(solve(input), 1, 100)` sets both.
+#[macro_export]
+macro_rules! bench {
+    ($func:expr) => {
+        $crate::bench!($func, 3, 10)
+    };
+    ($func:expr, $warmup:expr, $runs:expr) => {{
+        let stats = $crate::bench($warmup, $runs, || $func);
+        println!("Function '{}' {}", stringify!($func), stats);
+        stats
+    }};
+}
+
+/// A timed step and the steps timed inside it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub name: String,
+    pub duration: Duration,
+    pub children: Vec<Span>,
+}
+
+impl Span {
+    fn write_tree(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(f, "{:indent$}{}: {:.2?}", "", self.name, self.duration, indent = depth * 2)?;
+        for child in &self.children {
+            child.write_tree(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_tree(f, 0)
+    }
+}
+
+thread_local! {
+    // One buffer per open span or collector; finished spans land in the innermost one.
+    static OPEN: RefCell<Vec<Vec<Span>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pops the buffer it pushed even if the timed block panics, so later spans still nest correctly.
+struct Frame;
+
+impl Frame {
+    fn open() -> Self {
+        OPEN.with(|open| open.borrow_mut().push(Vec::new()));
+        Frame
+    }
+
+    fn close(self) -> Vec<Span> {
+        let children = OPEN.with(|open| open.borrow_mut().pop().unwrap_or_default());
+        std::mem::forget(self);
+        children
+    }
+}
+
+impl Drop for Frame {
+    fn drop(&mut self) {
+        OPEN.with(|open| open.borrow_mut().pop());
+    }
+}
+
+/// A span being timed, for [`measure_time!`] to time a block in place rather than in a closure.
+#[doc(hidden)]
+pub struct OpenSpan<'a> {
+    name: &'a str,
+    start: Instant,
+    frame: Frame,
+}
+
+impl<'a> OpenSpan<'a> {
+    pub fn open(name: &'a str) -> Self {
+        let frame = Frame::open();
+        OpenSpan { name, start: Instant::now(), frame }
+    }
+
+    /// Finish the span and record it, as [`span`] does.
+    pub fn close(self) {
+        let duration = self.start.elapsed();
+        let span = Span { name: self.name.to_string(), duration, children: self.frame.close() };
+        OPEN.with(|open| match open.borrow_mut().last_mut() {
+            Some(parent) => parent.push(span),
+            None if span.children.is_empty() => println!("Function '{}' executed in {:?}", span.name, span.duration),
+            None => print!("{}", span),
+        });
+    }
+}
+
+/// Time `f` as a span called `name`.
+///
+/// The span is attached to the enclosing span or collector. With neither, it is printed:
+/// a leaf as `Function '<name>' executed in <duration>`, a span with children as a tree.
+pub fn span<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    let open = OpenSpan::open(name);
+    let result = f();
+    open.close();
+    result
+}
+
+/// Run `f`, collecting the spans it records instead of printing them.
+pub fn collect<T>(f: impl FnOnce() -> T) -> (T, Vec<Span>) {
+    let frame = Frame::open();
+    let result = f();
+    (result, frame.close())
+}
+
+/// Summary statistics over repeated runs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub warmup: usize,
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarise a set of timings. Returns `None` when there are none.
+    pub fn from_durations(mut durations: Vec<Duration>, warmup: usize) -> Option<Self> {
+        durations.sort();
+        let runs = durations.len();
+        let p95 = (runs * 95).div_ceil(100).max(1) - 1;
+        Some(Stats {
+            warmup,
+            runs,
+            min: *durations.first()?,
+            median: durations[runs / 2],
+            p95: durations[p95],
+            max: durations[runs - 1],
+        })
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {:.2?}, median {:.2?}, p95 {:.2?} ({} runs after {} warm-up)",
+            self.min, self.median, self.p95, self.runs, self.warmup
+        )
+    }
+}
+
+/// Run `f` `warmup` times untimed, then `runs` times timed.
+///
+/// Spans recorded by `f` are collected and dropped, so they do not print on every run.
+///
+/// # Panics
+///
+/// If `runs` is zero.
+pub fn bench<T>(warmup: usize, runs: usize, mut f: impl FnMut() -> T) -> Stats {
+    assert!(runs > 0, "bench needs at least one timed run");
+    for _ in 0..warmup {
+        black_box(collect(&mut f));
+    }
+    let durations = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(collect(&mut f));
+            start.elapsed()
+        })
+        .collect();
+    Stats::from_durations(durations, warmup).expect("runs is not zero")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    #[test]
+    fn stats_test() {
+        let stats = Stats::from_durations(millis(&[5, 1, 4, 2, 3, 100, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19]), 2).unwrap();
+        assert_eq!(stats.runs, 20);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(11));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+        assert_eq!(stats.max, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn stats_single_run_test() {
+        let stats = Stats::from_durations(millis(&[7]), 0).unwrap();
+        assert_eq!((stats.min, stats.median, stats.p95), (Duration::from_millis(7), Duration::from_millis(7), Duration::from_millis(7)));
+        assert_eq!(Stats::from_durations(vec![], 0), None);
+    }
+
+    #[test]
+    fn bench_runs_test() {
+        let mut calls = 0;
+        let stats = bench(3, 5, || calls += 1);
+        assert_eq!(calls, 8);
+        assert_eq!((stats.warmup, stats.runs), (3, 5));
+    }
+
+    #[test]
+    fn nested_spans_test() {
+        let (result, spans) = collect(|| {
+            span!("solve", {
+                let digits = span!("parse", vec![1, 2, 3]);
+                let total: i32 = measure_time!(digits.iter().sum());
+                total
+            })
+        });
+        assert_eq!(result, 6);
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].name, "solve");
+        let children: Vec<&str> = spans[0].children.iter().map(|span| span.name.as_str()).collect();
+        assert_eq!(children, ["parse", "digits.iter().sum()"]);
+    }
+
+    #[test]
+    fn measure_time_in_place_test() {
+        // `?` leaves `first_even` itself, not a closure around the timed block
+        fn first_even(values: &[i32]) -> Option<i32> {
+            let even = measure_time!(values.iter().copied().find(|value| value % 2 == 0)?);
+            Some(even * 10)
+        }
+        let (found, spans) = collect(|| first_even(&[1, 4]));
+        assert_eq!((found, spans.len()), (Some(40), 1));
+        let (missing, spans) = collect(|| first_even(&[1, 3]));
+        assert_eq!((missing, spans.len()), (None, 0));
+        // and the frame it left open was popped
+        let ((), spans) = collect(|| span("after", || ()));
+        assert_eq!(spans.len(), 1);
+    }
+
+    #[test]
+    fn panicking_span_unwinds_test() {
+        let caught = std::panic::catch_unwind(|| span("boom", || panic!("boom")));
+        assert!(caught.is_err());
+        let ((), spans) = collect(|| span("after", || ()));
+        assert_eq!(spans.len(), 1);
+        assert!(spans[0].children.is_empty());
+    }
+}